@@ -0,0 +1,54 @@
+use std::ops::Range;
+use unicode_segmentation::UnicodeSegmentation;
+use crate::backend::AtlasBackend;
+use crate::features;
+use crate::{Text, TextWare};
+
+// One shaped cluster: the glyphs covering a byte range of a buffer line
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClusterAdvance {
+    pub line: usize,
+    pub run: usize,
+    pub range: Range<usize>,
+    pub x: f32,
+    pub advance: f32,
+    // Shaped advance minus the glyphs' plain advances, non zero where kerning applied
+    pub kerning: f32,
+    // More than one grapheme shaped into this cluster, e.g. "fi" or "=>"
+    pub ligature: bool,
+}
+
+impl<B: AtlasBackend> TextWare<B> {
+    pub fn cluster_advances(&mut self, text: &mut Text) -> Vec<ClusterAdvance> {
+        let _span = trace_span!("textware::cluster_advances");
+
+        self.shape(text);
+
+        let mut clusters: Vec<ClusterAdvance> = Vec::with_capacity(text.glyph_count());
+        for (run_index, run) in text.buffer.layout_runs().enumerate() {
+            let run_start = clusters.len();
+            for glyph in run.glyphs.iter() {
+                let natural = features::natural_advance(&mut self.font_system, glyph);
+
+                match clusters[run_start..].last_mut() {
+                    Some(cluster) if cluster.range == (glyph.start..glyph.end) => {
+                        cluster.x = cluster.x.min(glyph.x);
+                        cluster.advance += glyph.w;
+                        cluster.kerning += glyph.w - natural;
+                    }
+                    _ => clusters.push(ClusterAdvance {
+                        line: run.line_i,
+                        run: run_index,
+                        range: glyph.start..glyph.end,
+                        x: glyph.x,
+                        advance: glyph.w,
+                        kerning: glyph.w - natural,
+                        ligature: run.text[glyph.start..glyph.end].graphemes(true).nth(1).is_some(),
+                    }),
+                }
+            }
+        }
+
+        clusters
+    }
+}
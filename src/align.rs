@@ -0,0 +1,61 @@
+use crate::{Align, Text};
+
+// Start and End follow each paragraph's resolved base direction, so RTL
+// paragraphs line up on the right without locale checks in app code
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TextAlign {
+    #[default]
+    Start,
+    End,
+    Left,
+    Right,
+    Center,
+    Justified,
+}
+
+impl TextAlign {
+    pub fn resolve(self, rtl: bool) -> Align {
+        match (self, rtl) {
+            (TextAlign::Start, false) | (TextAlign::End, true) | (TextAlign::Left, _) => Align::Left,
+            (TextAlign::Start, true) | (TextAlign::End, false) | (TextAlign::Right, _) => Align::Right,
+            (TextAlign::Center, _) => Align::Center,
+            (TextAlign::Justified, _) => Align::Justified,
+        }
+    }
+}
+
+impl Text {
+    pub fn alignment(&self) -> Option<TextAlign> {
+        self.align
+    }
+
+    // None leaves the buffer lines' own alignment alone
+    pub fn set_alignment(&mut self, align: Option<TextAlign>) {
+        if self.align != align {
+            self.align = align;
+            self.dirty = true;
+        }
+    }
+
+    // Whether a paragraph shaped right to left, None before it is shaped
+    pub fn is_rtl(&self, line: usize) -> Option<bool> {
+        self.buffer.lines.get(line)?.shape_opt().as_ref().map(|shape| shape.rtl)
+    }
+}
+
+// Needs shaped lines for their direction, returns whether any line changed and needs layout again
+pub(crate) fn resolve_alignment(text: &mut Text) -> bool {
+    let Some(align) = text.align else {
+        return false;
+    };
+
+    let mut changed = false;
+    for line in text.buffer.lines.iter_mut() {
+        let Some(rtl) = line.shape_opt().as_ref().map(|shape| shape.rtl) else {
+            continue;
+        };
+        changed |= line.set_align(Some(align.resolve(rtl)));
+    }
+    changed
+}
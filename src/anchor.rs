@@ -0,0 +1,120 @@
+use crate::backend::AtlasBackend;
+use crate::{layout_bounds, Text, TextMesh, TextWare};
+
+// Point of the screen area a text is pinned to, and the matching point of the text
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Anchor {
+    #[default]
+    TopLeft,
+    Top,
+    TopRight,
+    Left,
+    Center,
+    Right,
+    BottomLeft,
+    Bottom,
+    BottomRight,
+}
+
+impl Anchor {
+    // 0 for the left or top edge, 0.5 for the middle, 1 for the right or bottom edge
+    fn factors(self) -> [f32; 2] {
+        match self {
+            Anchor::TopLeft => [0.0, 0.0],
+            Anchor::Top => [0.5, 0.0],
+            Anchor::TopRight => [1.0, 0.0],
+            Anchor::Left => [0.0, 0.5],
+            Anchor::Center => [0.5, 0.5],
+            Anchor::Right => [1.0, 0.5],
+            Anchor::BottomLeft => [0.0, 1.0],
+            Anchor::Bottom => [0.5, 1.0],
+            Anchor::BottomRight => [1.0, 1.0],
+        }
+    }
+}
+
+// Safe area insets in pixels, e.g. for notches and rounded corners
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct Insets {
+    pub left: f32,
+    pub top: f32,
+    pub right: f32,
+    pub bottom: f32,
+}
+
+impl Insets {
+    pub fn uniform(inset: f32) -> Self {
+        Self { left: inset, top: inset, right: inset, bottom: inset }
+    }
+}
+
+// Rectangle in screen pixels, y down
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct ScreenRect {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+impl ScreenRect {
+    pub fn new(x: f32, y: f32, width: f32, height: f32) -> Self {
+        Self { x, y, width, height }
+    }
+
+    // Largest rect of the given width / height ratio centered in this one, the area a
+    // letterboxed or pillarboxed game renders into
+    pub fn letterbox(&self, aspect: f32) -> ScreenRect {
+        if !aspect.is_finite() || aspect <= 0.0 || self.height <= 0.0 {
+            return *self;
+        }
+
+        let (width, height) = if self.width / self.height > aspect {
+            (self.height * aspect, self.height)
+        } else {
+            (self.width, self.width / aspect)
+        };
+        ScreenRect::new(self.x + (self.width - width) / 2.0, self.y + (self.height - height) / 2.0, width, height)
+    }
+
+    pub fn inset(&self, insets: Insets) -> ScreenRect {
+        ScreenRect::new(
+            self.x + insets.left,
+            self.y + insets.top,
+            (self.width - insets.left - insets.right).max(0.0),
+            (self.height - insets.top - insets.bottom).max(0.0),
+        )
+    }
+
+    // Top left position of a box of `size` pinned to the anchor point
+    pub fn anchor(&self, anchor: Anchor, size: [f32; 2]) -> [f32; 2] {
+        let [fx, fy] = anchor.factors();
+        [self.x + (self.width - size[0]) * fx, self.y + (self.height - size[1]) * fy]
+    }
+}
+
+impl TextMesh {
+    pub fn translate(&mut self, offset: [f32; 2]) {
+        for vertex in &mut self.vertices {
+            vertex.position[0] += offset[0];
+            vertex.position[1] += offset[1];
+        }
+    }
+}
+
+impl<B: AtlasBackend> TextWare<B> {
+    // Offset that pins the text's laid out bounds to the anchor point of `area`, rounded
+    // to whole pixels. Meant for screen space text; pass it to TextMesh::translate.
+    pub fn anchor_offset(&mut self, text: &mut Text, anchor: Anchor, area: ScreenRect) -> [f32; 2] {
+        self.shape(text);
+
+        let [min_x, min_y, max_x, max_y] = layout_bounds(text).unwrap_or_default();
+        let [x, y] = area.anchor(anchor, [max_x - min_x, max_y - min_y]);
+        [(x - min_x).round(), (y - min_y).round()]
+    }
+}
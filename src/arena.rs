@@ -0,0 +1,63 @@
+use crate::backend::AtlasBackend;
+use crate::{Text, TextMesh, TextWare};
+
+// Pool of meshes reused across frames, reset() keeps every buffer's capacity
+#[derive(Default)]
+pub struct FrameArena {
+    meshes: Vec<TextMesh>,
+    used: usize,
+}
+
+impl FrameArena {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_capacity(meshes: usize, quads_per_mesh: usize) -> Self {
+        Self {
+            meshes: (0..meshes).map(|_| TextMesh::with_capacity(quads_per_mesh)).collect(),
+            used: 0,
+        }
+    }
+
+    pub fn reset(&mut self) {
+        for mesh in self.meshes[..self.used].iter_mut() {
+            mesh.vertices.clear();
+            mesh.indices.clear();
+        }
+        self.used = 0;
+    }
+
+    pub fn alloc_mesh(&mut self) -> &mut TextMesh {
+        if self.used == self.meshes.len() {
+            self.meshes.push(TextMesh::default());
+        }
+
+        self.used += 1;
+        &mut self.meshes[self.used - 1]
+    }
+
+    pub fn meshes(&self) -> &[TextMesh] {
+        &self.meshes[..self.used]
+    }
+
+    pub fn len(&self) -> usize {
+        self.used
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.used == 0
+    }
+}
+
+impl<B: AtlasBackend> TextWare<B> {
+    pub fn generate_mesh_in<'a>(&mut self, text: &mut Text, arena: &'a mut FrameArena) -> &'a TextMesh {
+        let _span = trace_span!("textware::generate_mesh_in");
+
+        self.shape(text);
+
+        let mesh = arena.alloc_mesh();
+        self.append_glyphs(text, [0.0, 0.0], None, mesh);
+        mesh
+    }
+}
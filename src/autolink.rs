@@ -0,0 +1,173 @@
+use std::ops::Range;
+use crate::backend::AtlasBackend;
+#[cfg(feature = "regex")]
+use crate::error::TextError;
+use crate::hover::{InteractiveStyle, SpanInteraction};
+use crate::{Span, SpanStyle, Text, TextWare};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LinkKind {
+    Url,
+    Email,
+    // Index of a pattern added with add_pattern
+    Pattern(usize),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DetectedLink {
+    pub kind: LinkKind,
+    pub range: Range<usize>,
+}
+
+// Opt-in pass turning URLs, emails and custom patterns into link spans. A link
+// span carries `metadata == index + 1` into the detected links.
+#[derive(Debug, Clone)]
+pub struct LinkDetector {
+    pub urls: bool,
+    pub emails: bool,
+    pub style: SpanStyle,
+    #[cfg(feature = "regex")]
+    patterns: Vec<regex::Regex>,
+}
+
+impl Default for LinkDetector {
+    fn default() -> Self {
+        Self {
+            urls: true,
+            emails: true,
+            style: SpanStyle {
+                color: Some([0.35, 0.6, 1.0, 1.0]),
+                interaction: Some(SpanInteraction {
+                    hover: InteractiveStyle { underline: true, ..Default::default() },
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+            #[cfg(feature = "regex")]
+            patterns: Vec::new(),
+        }
+    }
+}
+
+impl LinkDetector {
+    // Returns the index reported as LinkKind::Pattern
+    #[cfg(feature = "regex")]
+    pub fn add_pattern(&mut self, pattern: &str) -> Result<usize, TextError> {
+        let regex = regex::Regex::new(pattern).map_err(|err| TextError::InvalidArgument(err.to_string()))?;
+        self.patterns.push(regex);
+        Ok(self.patterns.len() - 1)
+    }
+
+    // Sorted and non overlapping; where matches overlap the earlier, then longer one wins
+    pub fn detect(&self, content: &str) -> Vec<DetectedLink> {
+        let mut found = Vec::new();
+        if self.urls {
+            find_urls(content, &mut found);
+        }
+        if self.emails {
+            find_emails(content, &mut found);
+        }
+        #[cfg(feature = "regex")]
+        for (index, regex) in self.patterns.iter().enumerate() {
+            found.extend(regex.find_iter(content).filter(|m| !m.is_empty()).map(|m| DetectedLink { kind: LinkKind::Pattern(index), range: m.range() }));
+        }
+
+        found.sort_by_key(|link| (link.range.start, std::cmp::Reverse(link.range.end)));
+        let mut end = 0;
+        found.retain(|link| {
+            let keep = link.range.start >= end;
+            if keep {
+                end = link.range.end;
+            }
+            keep
+        });
+        found
+    }
+
+    pub fn spans(&self, content: &str) -> (Vec<Span>, Vec<DetectedLink>) {
+        let links = self.detect(content);
+        let mut spans = Vec::with_capacity(links.len() * 2 + 1);
+        let mut cursor = 0;
+
+        for (index, link) in links.iter().enumerate() {
+            if link.range.start > cursor {
+                spans.push(Span::new(&content[cursor..link.range.start], SpanStyle::default()));
+            }
+            let style = SpanStyle { metadata: index + 1, ..self.style.clone() };
+            spans.push(Span::new(&content[link.range.clone()], style));
+            cursor = link.range.end;
+        }
+        if cursor < content.len() || spans.is_empty() {
+            spans.push(Span::new(&content[cursor..], SpanStyle::default()));
+        }
+
+        (spans, links)
+    }
+}
+
+impl<B: AtlasBackend> TextWare<B> {
+    // Sets the content with its links as styled, interactive spans
+    pub fn update_text_linked(&mut self, text: &mut Text, content: &str, detector: &LinkDetector) -> Vec<DetectedLink> {
+        let (spans, links) = detector.spans(content);
+        self.update_rich_text(text, &spans);
+        links
+    }
+}
+
+fn find_urls(content: &str, found: &mut Vec<DetectedLink>) {
+    const PREFIXES: [&str; 3] = ["https://", "http://", "www."];
+
+    let mut i = 0;
+    while let Some(ch) = content[i..].chars().next() {
+        let rest = &content[i..];
+        let prefix = PREFIXES
+            .iter()
+            .find(|prefix| rest.len() >= prefix.len() && rest.as_bytes()[..prefix.len()].eq_ignore_ascii_case(prefix.as_bytes()));
+        let boundary = !content[..i].chars().next_back().is_some_and(char::is_alphanumeric);
+
+        if let (Some(prefix), true) = (prefix, boundary) {
+            let length = rest.find(|c: char| c.is_whitespace() || matches!(c, '<' | '>' | '"' | '`')).unwrap_or(rest.len());
+            let end = trim_url(content, i + prefix.len(), i + length);
+            if end > i + prefix.len() {
+                found.push(DetectedLink { kind: LinkKind::Url, range: i..end });
+                i = end;
+                continue;
+            }
+        }
+        i += ch.len_utf8();
+    }
+}
+
+// Drops sentence punctuation and closing brackets the URL never opened
+fn trim_url(content: &str, start: usize, mut end: usize) -> usize {
+    while let Some(last) = content[start..end].chars().next_back() {
+        let url = &content[start..end];
+        let unbalanced = |open: char, close: char| last == close && url.matches(open).count() < url.matches(close).count();
+        let trailing = matches!(last, '.' | ',' | ';' | ':' | '!' | '?' | '\'' | '*') || unbalanced('(', ')') || unbalanced('[', ']');
+        if !trailing {
+            break;
+        }
+        end -= last.len_utf8();
+    }
+    end
+}
+
+fn find_emails(content: &str, found: &mut Vec<DetectedLink>) {
+    let is_local = |c: char| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '%' | '+' | '-');
+    let is_domain = |c: char| c.is_ascii_alphanumeric() || matches!(c, '.' | '-');
+
+    for (at, _) in content.match_indices('@') {
+        let Some(start) = content[..at].char_indices().rev().take_while(|(_, c)| is_local(*c)).last().map(|(i, _)| i) else {
+            continue;
+        };
+
+        let tail = &content[at + 1..];
+        let domain = tail[..tail.find(|c: char| !is_domain(c)).unwrap_or(tail.len())].trim_end_matches(['.', '-']);
+        let local = &content[start..at];
+        if domain.contains('.') && !domain.starts_with(['.', '-']) && !local.starts_with('.') {
+            found.push(DetectedLink { kind: LinkKind::Email, range: start..at + 1 + domain.len() });
+        }
+    }
+}
@@ -0,0 +1,263 @@
+#[cfg(feature = "glow")]
+use crate::error::TextError;
+
+pub trait AtlasBackend {
+    type Uploader: ?Sized;
+
+    fn size(&self) -> u32;
+
+    fn write_region(&mut self, uploader: &Self::Uploader, x: u32, y: u32, width: u32, height: u32, data: &[u8]);
+
+    // Largest size `grow` can reach, the current size for backends that can't grow
+    fn max_size(&self) -> u32 {
+        self.size()
+    }
+
+    // Replaces the atlas with an empty one of `size`; the cache writes every entry again
+    // right after. Backends that hand out a bind group or texture replace it here, so
+    // callers must fetch it again after an upload that grew the atlas.
+    fn grow(&mut self, _uploader: &Self::Uploader, _size: u32) {}
+
+    // Side of the RGBA8 atlas for color glyphs, zero when the backend has none
+    fn color_size(&self) -> u32 {
+        0
+    }
+
+    fn write_color_region(&mut self, _uploader: &Self::Uploader, _x: u32, _y: u32, _width: u32, _height: u32, _data: &[u8]) {}
+}
+
+pub struct WgpuBackend {
+    device: wgpu::Device,
+    texture: wgpu::Texture,
+    bind_group: wgpu::BindGroup,
+    size: u32,
+    // RGBA atlas for emoji and other color glyphs, drawn from a separate mesh
+    color: Option<(wgpu::Texture, wgpu::BindGroup, u32)>,
+}
+
+impl WgpuBackend {
+    pub fn new(device: &wgpu::Device, size: u32) -> Self {
+        let (texture, bind_group) = create_atlas(device, size, wgpu::TextureFormat::R8Unorm);
+        Self { device: device.clone(), texture, bind_group, size, color: None }
+    }
+
+    // Same layout as the mask atlas, so one pipeline layout serves both bind groups
+    pub fn with_color_atlas(device: &wgpu::Device, size: u32, color_size: u32) -> Self {
+        let mut backend = Self::new(device, size);
+        if color_size > 0 {
+            let (texture, bind_group) = create_atlas(device, color_size, wgpu::TextureFormat::Rgba8UnormSrgb);
+            backend.color = Some((texture, bind_group, color_size));
+        }
+        backend
+    }
+
+    pub fn get_bind_group(&self) -> &wgpu::BindGroup {
+        &self.bind_group
+    }
+
+    pub fn get_color_bind_group(&self) -> Option<&wgpu::BindGroup> {
+        self.color.as_ref().map(|(_, bind_group, _)| bind_group)
+    }
+
+    pub fn texture(&self) -> &wgpu::Texture {
+        &self.texture
+    }
+}
+
+fn create_atlas(device: &wgpu::Device, size: u32, format: wgpu::TextureFormat) -> (wgpu::Texture, wgpu::BindGroup) {
+    let texture_size = wgpu::Extent3d {
+        width: size,
+        height: size,
+        depth_or_array_layers: 1,
+    };
+
+    let texture = device.create_texture(&wgpu::TextureDescriptor {
+        size: texture_size,
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format,
+        usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST | wgpu::TextureUsages::COPY_SRC,
+        label: None,
+        view_formats: &[],
+    });
+
+    let texture_view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+    let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+        address_mode_u: wgpu::AddressMode::ClampToEdge,
+        address_mode_v: wgpu::AddressMode::ClampToEdge,
+        address_mode_w: wgpu::AddressMode::ClampToEdge,
+        mag_filter: wgpu::FilterMode::Linear,
+        min_filter: wgpu::FilterMode::Linear,
+        ..Default::default()
+    });
+
+    let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+        entries: &[
+            wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Texture {
+                    multisampled: false,
+                    view_dimension: wgpu::TextureViewDimension::D2,
+                    sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                },
+                count: None,
+            },
+            wgpu::BindGroupLayoutEntry {
+                binding: 1,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                count: None,
+            },
+        ],
+        label: None,
+    });
+
+    let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+        layout: &bind_group_layout,
+        entries: &[
+            wgpu::BindGroupEntry {
+                binding: 0,
+                resource: wgpu::BindingResource::TextureView(&texture_view),
+            },
+            wgpu::BindGroupEntry {
+                binding: 1,
+                resource: wgpu::BindingResource::Sampler(&sampler),
+            },
+        ],
+        label: None,
+    });
+
+    (texture, bind_group)
+}
+
+impl AtlasBackend for WgpuBackend {
+    type Uploader = wgpu::Queue;
+
+    fn size(&self) -> u32 {
+        self.size
+    }
+
+    fn write_region(&mut self, queue: &wgpu::Queue, x: u32, y: u32, width: u32, height: u32, data: &[u8]) {
+        write_texture(queue, &self.texture, [x, y, width, height], width, data);
+    }
+
+    fn max_size(&self) -> u32 {
+        self.device.limits().max_texture_dimension_2d
+    }
+
+    // The bind group is replaced, so callers must fetch it again after an upload
+    fn grow(&mut self, _queue: &wgpu::Queue, size: u32) {
+        let (texture, bind_group) = create_atlas(&self.device, size, wgpu::TextureFormat::R8Unorm);
+        self.texture = texture;
+        self.bind_group = bind_group;
+        self.size = size;
+    }
+
+    fn color_size(&self) -> u32 {
+        self.color.as_ref().map_or(0, |(_, _, size)| *size)
+    }
+
+    fn write_color_region(&mut self, queue: &wgpu::Queue, x: u32, y: u32, width: u32, height: u32, data: &[u8]) {
+        if let Some((texture, _, _)) = &self.color {
+            write_texture(queue, texture, [x, y, width, height], width * 4, data);
+        }
+    }
+}
+
+fn write_texture(queue: &wgpu::Queue, texture: &wgpu::Texture, [x, y, width, height]: [u32; 4], bytes_per_row: u32, data: &[u8]) {
+    queue.write_texture(
+        wgpu::TexelCopyTextureInfo {
+            texture,
+            mip_level: 0,
+            origin: wgpu::Origin3d { x, y, z: 0 },
+            aspect: wgpu::TextureAspect::All,
+        },
+        data,
+        wgpu::TexelCopyBufferLayout {
+            offset: 0,
+            bytes_per_row: Some(bytes_per_row),
+            rows_per_image: None,
+        },
+        wgpu::Extent3d { width, height, depth_or_array_layers: 1 },
+    );
+}
+
+#[cfg(feature = "glow")]
+pub struct GlowBackend {
+    texture: glow::Texture,
+    size: u32,
+}
+
+#[cfg(feature = "glow")]
+impl GlowBackend {
+    pub fn new(gl: &glow::Context, size: u32) -> Result<Self, TextError> {
+        use glow::HasContext;
+
+        unsafe {
+            let texture = gl.create_texture().map_err(TextError::Backend)?;
+            gl.bind_texture(glow::TEXTURE_2D, Some(texture));
+            gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_WRAP_S, glow::CLAMP_TO_EDGE as i32);
+            gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_WRAP_T, glow::CLAMP_TO_EDGE as i32);
+            gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_MIN_FILTER, glow::LINEAR as i32);
+            gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_MAG_FILTER, glow::LINEAR as i32);
+            gl.pixel_store_i32(glow::UNPACK_ALIGNMENT, 1);
+            gl.tex_image_2d(
+                glow::TEXTURE_2D,
+                0,
+                glow::R8 as i32,
+                size as i32,
+                size as i32,
+                0,
+                glow::RED,
+                glow::UNSIGNED_BYTE,
+                glow::PixelUnpackData::Slice(None),
+            );
+            gl.bind_texture(glow::TEXTURE_2D, None);
+
+            Ok(Self { texture, size })
+        }
+    }
+
+    pub fn texture(&self) -> glow::Texture {
+        self.texture
+    }
+
+    pub fn destroy(self, gl: &glow::Context) {
+        use glow::HasContext;
+
+        unsafe { gl.delete_texture(self.texture) };
+    }
+}
+
+#[cfg(feature = "glow")]
+impl AtlasBackend for GlowBackend {
+    type Uploader = glow::Context;
+
+    fn size(&self) -> u32 {
+        self.size
+    }
+
+    fn write_region(&mut self, gl: &glow::Context, x: u32, y: u32, width: u32, height: u32, data: &[u8]) {
+        use glow::HasContext;
+
+        unsafe {
+            gl.bind_texture(glow::TEXTURE_2D, Some(self.texture));
+            gl.pixel_store_i32(glow::UNPACK_ALIGNMENT, 1);
+            gl.tex_sub_image_2d(
+                glow::TEXTURE_2D,
+                0,
+                x as i32,
+                y as i32,
+                width as i32,
+                height as i32,
+                glow::RED,
+                glow::UNSIGNED_BYTE,
+                glow::PixelUnpackData::Slice(Some(data)),
+            );
+            gl.bind_texture(glow::TEXTURE_2D, None);
+        }
+    }
+}
@@ -0,0 +1,328 @@
+use std::collections::HashMap;
+
+use crate::backend::AtlasBackend;
+use crate::error::TextError;
+use crate::{TextMesh, TextVertex, TextWare};
+
+const MESH_MAGIC: &[u8; 4] = b"TWM2";
+const BAKED_MAGIC: &[u8; 4] = b"TWB2";
+const SLICE_PADDING: u32 = 1;
+const SOLID_SIZE: u32 = 2;
+
+// R8 coverage texture holding only the glyphs a baked mesh uses. Its top left
+// texels are solid, for backgrounds and markers.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AtlasSlice {
+    pub width: u32,
+    pub height: u32,
+    pub data: Vec<u8>,
+}
+
+// Static text generated offline, drawable with the slice as its atlas and no fonts loaded
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BakedMesh {
+    pub mesh: TextMesh,
+    pub atlas: AtlasSlice,
+}
+
+impl TextMesh {
+    // Little endian: magic, vertex and index counts, then the raw vertex and index data
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(12 + self.vertices.len() * std::mem::size_of::<TextVertex>() + self.indices.len() * 4);
+        out.extend_from_slice(MESH_MAGIC);
+        out.extend_from_slice(&(self.vertices.len() as u32).to_le_bytes());
+        out.extend_from_slice(&(self.indices.len() as u32).to_le_bytes());
+        for vertex in &self.vertices {
+            for value in vertex.position.iter().chain(&vertex.uv).chain(&vertex.color) {
+                out.extend_from_slice(&value.to_le_bytes());
+            }
+        }
+        for index in &self.indices {
+            out.extend_from_slice(&index.to_le_bytes());
+        }
+        out
+    }
+
+    // FNV-1a over the serialized mesh with -0.0 folded into 0.0, stable across runs and
+    // platforms, for golden tests and replay checks
+    pub fn checksum(&self) -> u64 {
+        let mut hash = 0xcbf2_9ce4_8422_2325u64;
+        let mut feed = |bytes: &[u8]| {
+            for &byte in bytes {
+                hash = (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3);
+            }
+        };
+        feed(&(self.vertices.len() as u32).to_le_bytes());
+        feed(&(self.indices.len() as u32).to_le_bytes());
+        for vertex in &self.vertices {
+            for value in vertex.position.iter().chain(&vertex.uv).chain(&vertex.color) {
+                feed(&(value + 0.0).to_le_bytes());
+            }
+        }
+        for index in &self.indices {
+            feed(&index.to_le_bytes());
+        }
+        hash
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, TextError> {
+        let mut reader = Reader { bytes };
+        reader.magic(MESH_MAGIC)?;
+        reader.mesh()
+    }
+}
+
+impl BakedMesh {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(12 + self.atlas.data.len());
+        out.extend_from_slice(BAKED_MAGIC);
+        out.extend_from_slice(&self.atlas.width.to_le_bytes());
+        out.extend_from_slice(&self.atlas.height.to_le_bytes());
+        out.extend_from_slice(&self.atlas.data);
+        out.extend_from_slice(&self.mesh.to_bytes());
+        out
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, TextError> {
+        let mut reader = Reader { bytes };
+        reader.magic(BAKED_MAGIC)?;
+
+        let width = reader.u32()?;
+        let height = reader.u32()?;
+        let size = (width as usize).checked_mul(height as usize).ok_or_else(|| invalid("atlas slice too large"))?;
+        let data = reader.take(size)?.to_vec();
+
+        reader.magic(MESH_MAGIC)?;
+        let mesh = reader.mesh()?;
+        Ok(Self { mesh, atlas: AtlasSlice { width, height, data } })
+    }
+}
+
+enum Source {
+    Solid,
+    Empty,
+    Entry(usize),
+}
+
+fn invalid(reason: &str) -> TextError {
+    TextError::InvalidArgument(format!("malformed baked mesh: {}", reason))
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], TextError> {
+        if self.bytes.len() < len {
+            return Err(invalid("unexpected end of data"));
+        }
+        let (head, tail) = self.bytes.split_at(len);
+        self.bytes = tail;
+        Ok(head)
+    }
+
+    fn magic(&mut self, magic: &[u8; 4]) -> Result<(), TextError> {
+        if self.take(4)? != magic {
+            return Err(invalid("bad magic"));
+        }
+        Ok(())
+    }
+
+    fn u32(&mut self) -> Result<u32, TextError> {
+        let bytes = self.take(4)?;
+        Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn f32(&mut self) -> Result<f32, TextError> {
+        self.u32().map(f32::from_bits)
+    }
+
+    fn mesh(&mut self) -> Result<TextMesh, TextError> {
+        let vertex_count = self.u32()? as usize;
+        let index_count = self.u32()? as usize;
+        if self.bytes.len() < vertex_count.saturating_mul(36).saturating_add(index_count.saturating_mul(4)) {
+            return Err(invalid("counts exceed the data"));
+        }
+
+        let mut mesh = TextMesh::default();
+        mesh.vertices.reserve(vertex_count);
+        for _ in 0..vertex_count {
+            let mut values = [0.0f32; 9];
+            for value in &mut values {
+                *value = self.f32()?;
+            }
+            mesh.vertices.push(TextVertex {
+                position: [values[0], values[1], values[2]],
+                uv: [values[3], values[4]],
+                color: [values[5], values[6], values[7], values[8]],
+            });
+        }
+
+        mesh.indices.reserve(index_count);
+        for _ in 0..index_count {
+            let index = self.u32()?;
+            if index as usize >= vertex_count {
+                return Err(invalid("index out of range"));
+            }
+            mesh.indices.push(index);
+        }
+        Ok(mesh)
+    }
+}
+
+impl<B: AtlasBackend> TextWare<B> {
+    // Copies the glyph bitmaps a generated mesh samples into a fresh slice and
+    // remaps its UVs, e.g. in a build tool baking menus and credits
+    pub fn bake_mesh(&self, mesh: &TextMesh) -> Result<BakedMesh, TextError> {
+        let _span = trace_span!("textware::bake_mesh", vertices = mesh.vertices.len());
+
+        let atlas_size = self.glyph_cache.atlas_size() as f32;
+        let entries = self.glyph_cache.bitmap_entries();
+        let [sx, sy, sw, sh] = self.glyph_cache.solid_pixels().map(|v| v as f32);
+
+        // Atlas entry each quad samples. Whole quads start at their
+        // entry's origin; quads clip_to_bounds trimmed still lie inside it
+        if !mesh.vertices.len().is_multiple_of(4) {
+            return Err(TextError::InvalidArgument("mesh vertices are not whole quads".to_string()));
+        }
+        let origins: HashMap<[u32; 2], usize> = entries.iter().enumerate().map(|(i, (rect, _))| ([rect[0], rect[1]], i)).collect();
+        let contains = |rect: [u32; 4], point: [f32; 2]| {
+            let [x, y, w, h] = rect.map(|v| v as f32);
+            (x..=x + w).contains(&point[0]) && (y..=y + h).contains(&point[1])
+        };
+
+        let mut sources = Vec::with_capacity(mesh.vertices.len() / 4);
+        let mut used = vec![None; entries.len()];
+        for quad in mesh.vertices.chunks_exact(4) {
+            let mut min = [f32::MAX; 2];
+            let mut max = [f32::MIN; 2];
+            for vertex in quad {
+                for axis in 0..2 {
+                    min[axis] = min[axis].min(vertex.uv[axis] * atlas_size);
+                    max[axis] = max[axis].max(vertex.uv[axis] * atlas_size);
+                }
+            }
+            let center = [(min[0] + max[0]) / 2.0, (min[1] + max[1]) / 2.0];
+            if (sx..=sx + sw).contains(&center[0]) && (sy..=sy + sh).contains(&center[1]) {
+                sources.push(Source::Solid);
+                continue;
+            }
+            // Glyphs with an empty bitmap, e.g. spaces
+            if min[0] >= max[0] || min[1] >= max[1] {
+                sources.push(Source::Empty);
+                continue;
+            }
+
+            let entry = origins
+                .get(&[min[0].round() as u32, min[1].round() as u32])
+                .copied()
+                .filter(|&entry| contains(entries[entry].0, center))
+                .or_else(|| entries.iter().position(|(rect, _)| contains(*rect, center)))
+                .ok_or_else(|| TextError::InvalidArgument("mesh samples a region the glyph cache no longer holds".to_string()))?;
+
+            used[entry] = Some([0, 0]);
+            sources.push(Source::Entry(entry));
+        }
+
+        // Shelf pack the used bitmaps below the solid block
+        let area: u32 = entries.iter().zip(&used).filter(|(_, used)| used.is_some()).map(|((rect, _), _)| (rect[2] + SLICE_PADDING) * (rect[3] + SLICE_PADDING)).sum();
+        let widest = entries.iter().zip(&used).filter(|(_, used)| used.is_some()).map(|((rect, _), _)| rect[2]).max().unwrap_or(0);
+        let width = ((area as f32).sqrt().ceil() as u32).max(widest + SLICE_PADDING * 2).max(SOLID_SIZE + SLICE_PADDING * 2).next_power_of_two();
+
+        let (mut x, mut y, mut row) = (SLICE_PADDING, SOLID_SIZE + SLICE_PADDING * 2, 0);
+        for (slot, (rect, _)) in used.iter_mut().zip(&entries) {
+            let Some(origin) = slot else {
+                continue;
+            };
+            if x + rect[2] + SLICE_PADDING > width {
+                x = SLICE_PADDING;
+                y += row + SLICE_PADDING;
+                row = 0;
+            }
+            *origin = [x, y];
+            x += rect[2] + SLICE_PADDING;
+            row = row.max(rect[3]);
+        }
+        let height = y + row + SLICE_PADDING;
+
+        let mut data = vec![0u8; (width * height) as usize];
+        for row in 0..SOLID_SIZE {
+            let start = ((SLICE_PADDING + row) * width + SLICE_PADDING) as usize;
+            data[start..start + SOLID_SIZE as usize].fill(255);
+        }
+        for (slot, (rect, image)) in used.iter().zip(&entries) {
+            let Some([ox, oy]) = slot else {
+                continue;
+            };
+            let w = rect[2] as usize;
+            for (row, line) in image.data.chunks_exact(w.max(1)).take(rect[3] as usize).enumerate() {
+                let start = (oy + row as u32) as usize * width as usize + *ox as usize;
+                data[start..start + w].copy_from_slice(line);
+            }
+        }
+
+        let solid_uv = (SLICE_PADDING as f32 + SOLID_SIZE as f32 / 2.0) / width as f32;
+        let solid_uv = [solid_uv, (SLICE_PADDING as f32 + SOLID_SIZE as f32 / 2.0) / height as f32];
+        let mut baked = mesh.clone();
+        for (quad, source) in baked.vertices.chunks_exact_mut(4).zip(&sources) {
+            for vertex in quad {
+                vertex.uv = match source {
+                    Source::Entry(entry) => {
+                        let [ox, oy] = used[*entry].unwrap_or([0, 0]);
+                        let [x, y, _, _] = entries[*entry].0.map(|v| v as f32);
+                        [(ox as f32 + vertex.uv[0] * atlas_size - x) / width as f32, (oy as f32 + vertex.uv[1] * atlas_size - y) / height as f32]
+                    }
+                    Source::Solid => solid_uv,
+                    // The blank corner texel
+                    Source::Empty => [0.0, 0.0],
+                };
+            }
+        }
+
+        Ok(BakedMesh { mesh: baked, atlas: AtlasSlice { width, height, data } })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn quad() -> TextMesh {
+        let vertex = |x: f32, y: f32| TextVertex { position: [x, y, 0.0], uv: [x / 8.0, y / 8.0], color: [1.0, 0.5, 0.0, 1.0] };
+        TextMesh { vertices: vec![vertex(0.0, 0.0), vertex(4.0, 0.0), vertex(4.0, 6.0), vertex(0.0, 6.0)], indices: vec![0, 1, 2, 0, 2, 3] }
+    }
+
+    #[test]
+    fn meshes_round_trip() {
+        let mesh = quad();
+        let loaded = TextMesh::from_bytes(&mesh.to_bytes()).unwrap();
+        assert_eq!(bytemuck::cast_slice::<_, u8>(&loaded.vertices), bytemuck::cast_slice::<_, u8>(&mesh.vertices));
+        assert_eq!(loaded.indices, mesh.indices);
+
+        let baked = BakedMesh { mesh, atlas: AtlasSlice { width: 2, height: 3, data: vec![1, 2, 3, 4, 5, 6] } };
+        let loaded = BakedMesh::from_bytes(&baked.to_bytes()).unwrap();
+        assert_eq!(loaded.atlas, baked.atlas);
+        assert_eq!(loaded.mesh.checksum(), baked.mesh.checksum());
+    }
+
+    #[test]
+    fn malformed_data_is_rejected() {
+        let bytes = quad().to_bytes();
+        let mut bad_magic = bytes.clone();
+        bad_magic[0] = b'X';
+        let mut bad_index = bytes.clone();
+        let last = bad_index.len() - 4;
+        bad_index[last..].copy_from_slice(&4u32.to_le_bytes());
+        let mut huge_counts = bytes.clone();
+        huge_counts[4..8].copy_from_slice(&u32::MAX.to_le_bytes());
+
+        for bytes in [&bytes[..bytes.len() - 1], &bad_magic[..], &bad_index[..], &huge_counts[..], &[][..]] {
+            assert!(matches!(TextMesh::from_bytes(bytes), Err(TextError::InvalidArgument(_))));
+        }
+        // A mesh is not a baked mesh
+        assert!(BakedMesh::from_bytes(&bytes).is_err());
+    }
+}
@@ -0,0 +1,71 @@
+use crate::backend::AtlasBackend;
+use crate::error::TextError;
+use crate::font::FontId;
+use crate::{text_content, Text, TextMesh, TextStyle, TextWare};
+
+impl<B: AtlasBackend> TextWare<B> {
+    // For screens creating hundreds of labels at once. Attrs are resolved once per
+    // font and every text comes back shaped. Shaping needs the font system mutably
+    // so it stays sequential; with `parallel` the glyphs of the whole batch are
+    // then rasterized together on the rayon pool.
+    pub fn create_texts(&mut self, items: &[(&str, &TextStyle)]) -> Result<Vec<Text>, TextError> {
+        let _span = trace_span!("textware::create_texts", texts = items.len());
+
+        let mut attrs: Vec<(Option<FontId>, cosmic_text::AttrsOwned)> = Vec::new();
+        let mut texts = Vec::with_capacity(items.len());
+
+        for (content, style) in items {
+            let resolved = match attrs.iter().find(|(font_id, _)| *font_id == style.font_id) {
+                Some((_, resolved)) => resolved.clone(),
+                None => {
+                    let resolved = self.font_system.resolve_attrs(style.font_id);
+                    attrs.push((style.font_id, resolved.clone()));
+                    resolved
+                }
+            };
+
+            let mut text = self.font_system.create_text_with(content, style.font_id, resolved, style.font_size, style.line_height)?;
+            text.color = style.color;
+            self.shape(&mut text);
+            texts.push(text);
+        }
+
+        #[cfg(feature = "parallel")]
+        {
+            let batch: Vec<&Text> = texts.iter().collect();
+            self.glyph_cache.rasterize_parallel(&mut self.font_system, &batch);
+        }
+
+        Ok(texts)
+    }
+
+    // One mesh for many texts, each placed like generate_mesh_at, so a whole UI's text goes
+    // out in a single draw. Fails with IndexOverflow once the quads outgrow the index range.
+    pub fn generate_batch(&mut self, items: &mut [(&mut Text, [f32; 2])]) -> Result<TextMesh, TextError> {
+        let _span = trace_span!("textware::generate_batch", texts = items.len());
+
+        let mut glyphs = 0;
+        for (text, _) in items.iter_mut() {
+            self.shape(text);
+            glyphs += text.glyph_count();
+        }
+
+        #[cfg(feature = "parallel")]
+        {
+            let batch: Vec<&Text> = items.iter().map(|(text, _)| &**text).collect();
+            self.glyph_cache.rasterize_parallel(&mut self.font_system, &batch);
+        }
+
+        let mut mesh = TextMesh::with_capacity(glyphs);
+        for (text, position) in items.iter() {
+            if text.exclusions.is_empty() {
+                self.append_glyphs_with(text, *position, &mut mesh, |_, _, _| true)?;
+            } else {
+                let content = text_content(text);
+                self.append_flow(text, &content, &text.exclusions, *position, &mut mesh)?;
+            }
+        }
+
+        Ok(mesh)
+    }
+}
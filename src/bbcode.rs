@@ -0,0 +1,154 @@
+use crate::backend::AtlasBackend;
+use crate::color::parse_color;
+use crate::effects::SpanEffects;
+use crate::error::TextError;
+use crate::font::{FontId, FontSystem};
+use crate::rich::{Span, SpanStyle};
+use crate::{Text, TextWare};
+
+pub fn parse_bbcode(source: &str, fonts: &FontSystem) -> Result<Vec<Span>, TextError> {
+    let mut spans: Vec<Span> = Vec::new();
+    let mut stack: Vec<(String, SpanStyle)> = Vec::new();
+    let mut rest = source;
+
+    while !rest.is_empty() {
+        let Some(open) = rest.find('[') else {
+            push_span(&mut spans, rest, &stack);
+            break;
+        };
+
+        if open > 0 {
+            push_span(&mut spans, &rest[..open], &stack);
+        }
+
+        let Some(close) = rest[open..].find(']') else {
+            push_span(&mut spans, &rest[open..], &stack);
+            break;
+        };
+
+        let tag = &rest[open + 1..open + close];
+        rest = &rest[open + close + 1..];
+
+        match tag {
+            "lb" => {
+                push_span(&mut spans, "[", &stack);
+                continue;
+            }
+            "rb" => {
+                push_span(&mut spans, "]", &stack);
+                continue;
+            }
+            _ => {}
+        }
+
+        if let Some(name) = tag.strip_prefix('/') {
+            match stack.pop() {
+                Some((open_name, _)) if open_name == name => {}
+                Some((open_name, _)) => {
+                    return Err(TextError::Markup(format!("expected [/{}], found [/{}]", open_name, name)));
+                }
+                None => return Err(TextError::Markup(format!("unexpected [/{}]", name))),
+            }
+            continue;
+        }
+
+        let (name, value) = match tag.split_once('=') {
+            Some((name, value)) => (name.trim(), Some(value.trim().trim_matches('"'))),
+            None => (tag.trim(), None),
+        };
+
+        let overrides = tag_style(name, value, fonts)?;
+        let parent = stack.last().map(|(_, style)| style.clone()).unwrap_or_default();
+        stack.push((name.to_string(), parent.merge(&overrides)));
+    }
+
+    if let Some((name, _)) = stack.last() {
+        return Err(TextError::Markup(format!("unclosed [{}]", name)));
+    }
+
+    Ok(spans)
+}
+
+fn push_span(spans: &mut Vec<Span>, content: &str, stack: &[(String, SpanStyle)]) {
+    if content.is_empty() {
+        return;
+    }
+
+    let style = stack.last().map(|(_, style)| style.clone()).unwrap_or_default();
+    if let Some(last) = spans.last_mut() {
+        if last.style == style {
+            last.text.push_str(content);
+            return;
+        }
+    }
+
+    spans.push(Span::new(content, style));
+}
+
+fn tag_style(name: &str, value: Option<&str>, fonts: &FontSystem) -> Result<SpanStyle, TextError> {
+    let mut style = SpanStyle::default();
+    let required = || value.ok_or_else(|| TextError::Markup(format!("[{}] requires a value", name)));
+
+    match name {
+        "b" => style.weight = Some(700),
+        "i" => style.italic = true,
+        "code" | "tt" => style.monospace = true,
+        "u" => style.underline = true,
+        "s" => style.strikethrough = true,
+        "color" => {
+            let value = required()?;
+            style.color = Some(parse_color(value)
+                .ok_or_else(|| TextError::Markup(format!("invalid color '{}'", value)))?);
+        }
+        "font" => {
+            let value = required()?;
+            style.font_id = Some(fonts
+                .find_font(value)
+                .ok_or_else(|| TextError::Markup(format!("unknown font '{}'", value)))?);
+        }
+        "wave" => style.effects = SpanEffects::WAVE,
+        "shake" => style.effects = SpanEffects::SHAKE,
+        "rainbow" => style.effects = SpanEffects::RAINBOW,
+        "pulse" => style.effects = SpanEffects::PULSE,
+        _ => return Err(TextError::Markup(format!("unsupported tag [{}]", name))),
+    }
+
+    Ok(style)
+}
+
+impl<B: AtlasBackend> TextWare<B> {
+    pub fn create_text_bbcode(&mut self, bbcode: &str, font_id: Option<FontId>, font_size: f32, line_height: Option<f32>) -> Result<Text, TextError> {
+        let spans = parse_bbcode(bbcode, &self.font_system)?;
+        self.create_rich_text(&spans, font_id, font_size, line_height)
+    }
+
+    pub fn update_text_bbcode(&mut self, text: &mut Text, bbcode: &str) -> Result<(), TextError> {
+        let spans = parse_bbcode(bbcode, &self.font_system)?;
+        self.update_rich_text(text, &spans);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tags_set_styles() {
+        let fonts = FontSystem::new();
+        let spans = parse_bbcode("[b]a[u]b[/u][/b][s][color=#00ff00]c[/color][/s]", &fonts).unwrap();
+        let texts: Vec<_> = spans.iter().map(|span| span.text.as_str()).collect();
+        assert_eq!(texts, ["a", "b", "c"]);
+        assert!(spans[1].style.underline && spans[1].style.weight == Some(700));
+        assert!(spans[2].style.strikethrough && !spans[2].style.underline);
+        assert_eq!(spans[2].style.color, Some([0.0, 1.0, 0.0, 1.0]));
+    }
+
+    #[test]
+    fn size_and_unknown_tags_are_rejected() {
+        let fonts = FontSystem::new();
+        for source in ["[size=20]a[/size]", "[blink]a[/blink]", "[color]a[/color]"] {
+            assert!(matches!(parse_bbcode(source, &fonts), Err(TextError::Markup(_))), "{source}");
+        }
+    }
+}
@@ -0,0 +1,35 @@
+use unicode_linebreak::BreakOpportunity;
+use crate::Text;
+
+// UAX #14 break before `index`, the same rules cosmic wraps with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LineBreak {
+    pub line: usize,
+    pub index: usize,
+    // Forced, e.g. by a line or paragraph separator inside the line
+    pub mandatory: bool,
+}
+
+// Break opportunities of any string as (byte index, mandatory), the end of the string left out
+pub fn line_breaks(content: &str) -> Vec<(usize, bool)> {
+    unicode_linebreak::linebreaks(content)
+        .filter(|(index, _)| *index < content.len())
+        .map(|(index, opportunity)| (index, opportunity == BreakOpportunity::Mandatory))
+        .collect()
+}
+
+impl Text {
+    // Where the buffer lines may wrap, for layout code doing its own wrapping.
+    // Indexes are into the buffer lines, after input filtering.
+    pub fn break_opportunities(&self) -> Vec<LineBreak> {
+        self.buffer
+            .lines
+            .iter()
+            .enumerate()
+            .flat_map(|(line, buffer_line)| {
+                line_breaks(buffer_line.text()).into_iter().map(move |(index, mandatory)| LineBreak { line, index, mandatory })
+            })
+            .collect()
+    }
+}
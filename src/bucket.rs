@@ -0,0 +1,54 @@
+use cosmic_text::CacheKey;
+use crate::backend::AtlasBackend;
+use crate::error::TextError;
+use crate::TextWare;
+
+// Raster sizes glyphs are snapped to, so animated sizes reuse a handful of atlas entries
+// instead of adding one per frame. Quads keep the requested size and stretch the bitmap.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SizeBuckets {
+    // Nearest multiple of the step, in pixels
+    Step(f32),
+    // Nearest of the listed sizes
+    Sizes(Vec<f32>),
+}
+
+impl SizeBuckets {
+    pub(crate) fn validate(&self) -> Result<(), TextError> {
+        let valid = |size: &f32| size.is_finite() && *size > 0.0;
+        match self {
+            Self::Step(step) if !valid(step) => Err(TextError::InvalidArgument(format!("size bucket step {} must be positive", step))),
+            Self::Sizes(sizes) if sizes.is_empty() || !sizes.iter().all(valid) => {
+                Err(TextError::InvalidArgument("size buckets must be a non empty list of positive sizes".into()))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    pub fn snap(&self, size: f32) -> f32 {
+        match self {
+            // Never down to zero, tiny text keeps the smallest bucket
+            Self::Step(step) => ((size / step).round() * step).max(*step),
+            Self::Sizes(sizes) => sizes
+                .iter()
+                .copied()
+                .min_by(|a, b| (a - size).abs().total_cmp(&(b - size).abs()))
+                .unwrap_or(size),
+        }
+    }
+
+    // Key rasterized instead of `key`, and the factor its bitmap is stretched by
+    pub(crate) fn bucket(&self, key: CacheKey) -> (CacheKey, f32) {
+        let size = f32::from_bits(key.font_size_bits);
+        let snapped = self.snap(size);
+        (CacheKey { font_size_bits: snapped.to_bits(), ..key }, size / snapped)
+    }
+}
+
+impl<B: AtlasBackend> TextWare<B> {
+    // Snaps the raster size of text glyphs before they hit the atlas, None rasterizes every size exactly
+    pub fn set_size_buckets(&mut self, buckets: Option<SizeBuckets>) -> Result<(), TextError> {
+        self.glyph_cache.set_size_buckets(buckets)
+    }
+}
@@ -1,184 +1,1087 @@
 use cosmic_text::{CacheKey, SwashCache};
 use swash::scale::image::{Content, Image as SwashImage};
+#[cfg(feature = "parallel")]
+use swash::scale::ScaleContext;
+use swash::zeno::Placement;
 use std::collections::HashMap;
 use crate::font::FontSystem;
+use crate::error::TextError;
+use crate::filter::INVISIBLE_METADATA;
+use crate::features::{self, FontFeatures};
+use crate::space;
+use crate::hover;
+use crate::overflow;
+use crate::fade::EdgeFade;
+use crate::emoji::ColorAtlas;
+use crate::bucket::SizeBuckets;
+use crate::sdf::{self, DistanceField};
+use crate::outline::{GlyphOutline, OutlineCache};
+use crate::backend::{AtlasBackend, WgpuBackend};
+use crate::{rich, GlyphContext, GlyphQuad, Text, TextMesh};
 
-const ATLAS_SIZE: u32 = 2048;
-const PADDING: u32 = 1;
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct CacheConfig {
+    pub atlas_size: u32,
+    // The atlas doubles up to this size when it fills, if the backend can grow
+    pub max_atlas_size: u32,
+    pub padding: u32,
+    // Expected number of distinct glyphs, reserved upfront so the first busy frames don't rehash
+    pub glyph_capacity: usize,
+    // Side of the opaque block kept in the atlas corner for untextured quads
+    pub solid_size: u32,
+    // Side of the RGBA atlas for emoji and other color glyphs, zero to drop them
+    pub color_atlas_size: u32,
+    // Places glyphs strictly in layout order, so UVs don't depend on the parallel feature or on batch thresholds
+    pub deterministic: bool,
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self {
+            atlas_size: 2048,
+            max_atlas_size: 8192,
+            padding: 1,
+            glyph_capacity: 0,
+            solid_size: 4,
+            color_atlas_size: 1024,
+            deterministic: false,
+        }
+    }
+}
+
+pub type UvRect = (f32, f32, f32, f32);
+
+// Cosmic's key only covers font, glyph, size and subpixel bin, so the fingerprint of the
+// feature set the glyph was shaped with is kept next to it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) struct GlyphKey {
+    pub cache_key: CacheKey,
+    pub features: u64,
+}
+
+impl GlyphKey {
+    pub fn new(cache_key: CacheKey, features: FontFeatures) -> Self {
+        Self { cache_key, features: features.fingerprint() }
+    }
+
+    // Glyphs laid out outside of a Text, such as labels and terminal cells
+    pub fn plain(cache_key: CacheKey) -> Self {
+        Self::new(cache_key, FontFeatures::default())
+    }
+}
+
+#[cfg(feature = "parallel")]
+const PARALLEL_THRESHOLD: usize = 32;
+
+// Where the glyph loop writes: a mesh, or a visitor that only wants the glyph quads
+pub(crate) trait QuadSink {
+    fn quad_count(&self) -> usize;
+    // Underlines and placeholder frames, skipped when None
+    fn decorations(&mut self) -> Option<&mut TextMesh>;
+    fn push_glyph(&mut self, quad: GlyphQuad) -> Result<(), TextError>;
+    // Called as soon as the atlas grows, before any quad with the new UVs is written
+    fn rescale_uvs(&mut self, _factor: f32) {}
+}
+
+impl QuadSink for TextMesh {
+    fn quad_count(&self) -> usize {
+        self.vertices.len() / 4
+    }
+
+    fn decorations(&mut self) -> Option<&mut TextMesh> {
+        Some(self)
+    }
+
+    fn push_glyph(&mut self, quad: GlyphQuad) -> Result<(), TextError> {
+        if !self.has_capacity(1) {
+            return Err(TextError::IndexOverflow);
+        }
+        self.push_quad(quad.rect, quad.uv, quad.color);
+        Ok(())
+    }
+
+    fn rescale_uvs(&mut self, factor: f32) {
+        for vertex in &mut self.vertices {
+            vertex.uv = vertex.uv.map(|v| v * factor);
+        }
+    }
+}
+
+fn clip_to_bounds(text: &Text, offset: [f32; 2], scale: f32, mesh: &mut TextMesh, first_vertex: usize) {
+    if text.clip {
+        let (width, height) = text.buffer.size();
+        let rect = [offset[0] * scale, offset[1] * scale, (offset[0] + width) * scale, (offset[1] + height) * scale];
+        overflow::clip_quads(mesh, first_vertex, rect);
+    }
+}
 
-pub struct GlyphCache {
+#[derive(Clone, Copy)]
+pub(crate) struct Raster {
+    pub scale: f32,
+    pub blur: f32,
+    // Emits only color glyphs, from the RGBA atlas
+    pub color: bool,
+}
+
+impl Raster {
+    pub const PLAIN: Raster = Raster { scale: 1.0, blur: 0.0, color: false };
+}
+
+// The glyph atlas and mesh building. Usable on its own next to a FontSystem, which it
+// only borrows while rasterizing, so one FontSystem can feed several caches
+pub struct GlyphCache<B: AtlasBackend = WgpuBackend> {
     swash_cache: SwashCache,
-    texture: wgpu::Texture,
-    bind_group: wgpu::BindGroup,
+    backend: B,
+    // Logical atlas size, ahead of the backend's until the next upload after a growth
+    size: u32,
+    max_size: u32,
+    generation: u32,
+    padding: u32,
     next_x: u32,
     next_y: u32,
     row_height: u32,
-    glyphs: HashMap<CacheKey, (SwashImage, (f32, f32, f32, f32))>,
-    pending_uploads: Vec<(CacheKey, u32, u32, SwashImage)>,
+    solid_size: u32,
+    solid_pending: bool,
+    show_missing: bool,
+    buckets: Option<SizeBuckets>,
+    #[cfg(feature = "parallel")]
+    deterministic: bool,
+    glyphs: HashMap<GlyphKey, Entry>,
+    // Faux bold and blurred copies of glyphs, each a separate atlas entry
+    variants: HashMap<(GlyphKey, Variant), Entry>,
+    // Counted by upload_pending, for trim
+    frame: u64,
+    // Rows holding bitmaps from before a trim, zeroed on the next upload
+    clear_rows: u32,
+    // Shared with TextWare::glyph_outline; faux bold, hollow and distance field glyphs are drawn from it
+    pub(crate) outlines: OutlineCache,
+    pending_uploads: Vec<(u32, u32, SwashImage)>,
+    color: ColorAtlas,
 }
 
-impl GlyphCache {
-    pub fn new(device: &wgpu::Device, _queue: &wgpu::Queue) -> Self {
-        let texture_size = wgpu::Extent3d {
-            width: ATLAS_SIZE,
-            height: ATLAS_SIZE,
-            depth_or_array_layers: 1,
-        };
+impl GlyphCache<WgpuBackend> {
+    pub fn new(device: &wgpu::Device, queue: &wgpu::Queue) -> Self {
+        Self::with_config(device, queue, CacheConfig::default())
+    }
 
-        let texture = device.create_texture(&wgpu::TextureDescriptor {
-            size: texture_size,
-            mip_level_count: 1,
-            sample_count: 1,
-            dimension: wgpu::TextureDimension::D2,
-            format: wgpu::TextureFormat::R8Unorm,
-            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
-            label: None,
-            view_formats: &[],
-        });
+    pub fn with_config(device: &wgpu::Device, _queue: &wgpu::Queue, config: CacheConfig) -> Self {
+        Self::with_backend_config(WgpuBackend::with_color_atlas(device, config.atlas_size, config.color_atlas_size), config)
+    }
 
-        let texture_view = texture.create_view(&wgpu::TextureViewDescriptor::default());
-        
-        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
-            address_mode_u: wgpu::AddressMode::ClampToEdge,
-            address_mode_v: wgpu::AddressMode::ClampToEdge,
-            address_mode_w: wgpu::AddressMode::ClampToEdge,
-            mag_filter: wgpu::FilterMode::Linear,
-            min_filter: wgpu::FilterMode::Linear,
-            ..Default::default()
-        });
+    pub fn get_bind_group(&self) -> &wgpu::BindGroup {
+        self.backend.get_bind_group()
+    }
 
-        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
-            entries: &[
-                wgpu::BindGroupLayoutEntry {
-                    binding: 0,
-                    visibility: wgpu::ShaderStages::FRAGMENT,
-                    ty: wgpu::BindingType::Texture {
-                        multisampled: false,
-                        view_dimension: wgpu::TextureViewDimension::D2,
-                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
-                    },
-                    count: None,
-                },
-                wgpu::BindGroupLayoutEntry {
-                    binding: 1,
-                    visibility: wgpu::ShaderStages::FRAGMENT,
-                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
-                    count: None,
-                },
-            ],
-            label: None,
-        });
+    pub fn get_color_bind_group(&self) -> Option<&wgpu::BindGroup> {
+        self.backend.get_color_bind_group()
+    }
+}
 
-        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
-            layout: &bind_group_layout,
-            entries: &[
-                wgpu::BindGroupEntry {
-                    binding: 0,
-                    resource: wgpu::BindingResource::TextureView(&texture_view),
-                },
-                wgpu::BindGroupEntry {
-                    binding: 1,
-                    resource: wgpu::BindingResource::Sampler(&sampler),
-                },
-            ],
-            label: None,
-        });
+impl<B: AtlasBackend> GlyphCache<B> {
+    pub fn with_backend(backend: B, padding: u32) -> Self {
+        Self::with_backend_config(backend, CacheConfig { padding, ..Default::default() })
+    }
+
+    // The backend owns the atlas textures, so config.atlas_size and config.color_atlas_size are not used here
+    pub fn with_backend_config(backend: B, config: CacheConfig) -> Self {
+        let padding = config.padding;
+        let color = ColorAtlas::new(backend.color_size(), padding);
+        // Two texels at least, so the inset region stays non empty
+        let solid_size = config.solid_size.max(2);
 
         Self {
             swash_cache: SwashCache::new(),
-            texture,
-            bind_group,
-            next_x: PADDING,
-            next_y: PADDING,
-            row_height: 0,
-            glyphs: HashMap::new(),
-            pending_uploads: Vec::new(),
+            size: backend.size(),
+            max_size: config.max_atlas_size.min(backend.max_size()).max(backend.size()),
+            generation: 0,
+            backend,
+            padding,
+            next_x: padding + solid_size + padding,
+            next_y: padding,
+            row_height: solid_size,
+            solid_size,
+            solid_pending: true,
+            show_missing: false,
+            buckets: None,
+            #[cfg(feature = "parallel")]
+            deterministic: config.deterministic,
+            glyphs: HashMap::with_capacity(config.glyph_capacity),
+            variants: HashMap::new(),
+            frame: 0,
+            clear_rows: 0,
+            outlines: OutlineCache::default(),
+            pending_uploads: Vec::with_capacity(config.glyph_capacity),
+            color,
         }
     }
 
-    pub fn get_bind_group(&self) -> &wgpu::BindGroup {
-        &self.bind_group
+    pub fn backend(&self) -> &B {
+        &self.backend
+    }
+
+    pub fn set_show_missing(&mut self, show_missing: bool) {
+        self.show_missing = show_missing;
     }
 
-    pub fn upload_pending(&mut self, queue: &wgpu::Queue) {
+    pub fn set_size_buckets(&mut self, buckets: Option<SizeBuckets>) -> Result<(), TextError> {
+        if let Some(buckets) = &buckets {
+            buckets.validate()?;
+        }
+        self.buckets = buckets;
+        Ok(())
+    }
+
+    // Raster key for a laid out glyph, and how much its bitmap has to be stretched
+    pub(crate) fn bucketed(&self, key: CacheKey) -> (CacheKey, f32) {
+        self.buckets.as_ref().map_or((key, 1.0), |buckets| buckets.bucket(key))
+    }
+
+    pub fn upload_pending(&mut self, uploader: &B::Uploader) -> bool {
+        let mut uploaded = false;
+
+        self.frame += 1;
+
+        // The grown atlas starts empty, every entry is written again at its pixel position
+        if self.size != self.backend.size() {
+            let _span = trace_span!("textware::grow_atlas", size = self.size);
+            self.backend.grow(uploader, self.size);
+            let size = self.size as f32;
+            for entry in self.glyphs.values().chain(self.variants.values()) {
+                let Placement { width, height, .. } = entry.image.placement;
+                if width > 0 && height > 0 {
+                    let (x, y) = ((entry.rect.0 * size).round() as u32, (entry.rect.1 * size).round() as u32);
+                    self.backend.write_region(uploader, x, y, width, height, &entry.image.data);
+                }
+            }
+            self.pending_uploads.clear();
+            self.solid_pending = true;
+            self.clear_rows = 0;
+            uploaded = true;
+        }
+
+        // After a trim the old bitmaps would bleed into the new padding
+        if self.clear_rows > 0 {
+            let size = self.backend.size();
+            self.backend.write_region(uploader, 0, 0, size, self.clear_rows, &vec![0u8; (size * self.clear_rows) as usize]);
+            self.clear_rows = 0;
+            uploaded = true;
+        }
+
+        if self.solid_pending {
+            let data = vec![255u8; (self.solid_size * self.solid_size) as usize];
+            self.backend.write_region(uploader, self.padding, self.padding, self.solid_size, self.solid_size, &data);
+            self.solid_pending = false;
+            uploaded = true;
+        }
+
+        for (x, y, image) in self.color.take_pending() {
+            self.backend.write_color_region(uploader, x, y, image.placement.width, image.placement.height, &image.data);
+            uploaded = true;
+        }
+
         if self.pending_uploads.is_empty() {
-            return;
+            return uploaded;
         }
 
-        for (key, x, y, image) in self.pending_uploads.drain(..) {
+        let _span = trace_span!("textware::upload_pending", glyphs = self.pending_uploads.len());
+
+        for (x, y, image) in self.pending_uploads.drain(..) {
             let w = image.placement.width;
             let h = image.placement.height;
             if w == 0 || h == 0 { continue; }
 
-            queue.write_texture(
-                wgpu::TexelCopyTextureInfo {
-                    texture: &self.texture,
-                    mip_level: 0,
-                    origin: wgpu::Origin3d { x, y, z: 0 },
-                    aspect: wgpu::TextureAspect::All,
-                },
-                &image.data,
-                wgpu::TexelCopyBufferLayout {
-                    offset: 0,
-                    bytes_per_row: Some(w),
-                    rows_per_image: None,
-                },
-                wgpu::Extent3d { width: w, height: h, depth_or_array_layers: 1 },
-            );
+            self.backend.write_region(uploader, x, y, w, h, &image.data);
+            uploaded = true;
+        }
+
+        uploaded
+    }
+
+    pub(crate) fn bitmap_usage(&self) -> (usize, usize) {
+        let bytes = self.glyphs.values().chain(self.variants.values()).map(|entry| entry.image.data.capacity()).sum::<usize>()
+            + self.pending_uploads.iter().map(|(_, _, image)| image.data.capacity()).sum::<usize>()
+            + self.color.pending_bytes();
+        (self.glyphs.len() + self.variants.len() + self.color.len(), bytes)
+    }
+
+    pub fn atlas_size(&self) -> u32 {
+        self.size
+    }
+
+    // Bumped whenever the atlas grows or is trimmed, which moves every UV; meshes built before need regenerating
+    pub fn atlas_generation(&self) -> u32 {
+        self.generation
+    }
+
+    pub(crate) fn fill(&self) -> f32 {
+        let size = self.size as f32;
+        ((self.next_y + self.row_height) as f32 / size).min(1.0)
+    }
+
+    pub(crate) fn contains(&self, key: &GlyphKey) -> bool {
+        self.glyphs.contains_key(key)
+    }
+
+    // Pixel rects and bitmaps of every rasterized entry, plain and variant alike,
+    // in atlas order rather than hash order
+    pub(crate) fn bitmap_entries(&self) -> Vec<([u32; 4], &SwashImage)> {
+        let size = self.size as f32;
+        let mut entries: Vec<_> = self
+            .glyphs
+            .values()
+            .chain(self.variants.values())
+            .map(|entry| (&entry.image, entry.rect))
+            .filter(|(image, _)| image.placement.width > 0 && image.placement.height > 0)
+            .map(|(image, uv)| ([(uv.0 * size).round() as u32, (uv.1 * size).round() as u32, image.placement.width, image.placement.height], image))
+            .collect();
+        entries.sort_unstable_by_key(|(rect, _)| (rect[1], rect[0]));
+        entries
+    }
+
+    // Opaque texels for carets, selections and backgrounds drawn with the text pipeline,
+    // inset by half a texel so linear filtering never reaches the padding
+    pub fn solid_region(&self) -> UvRect {
+        let size = self.size as f32;
+        let start = (self.padding as f32 + 0.5) / size;
+        let extent = (self.solid_size as f32 - 1.0) / size;
+        (start, start, extent, extent)
+    }
+
+    pub(crate) fn solid_pixels(&self) -> [u32; 4] {
+        [self.padding, self.padding, self.solid_size, self.solid_size]
+    }
+
+    pub(crate) fn solid_uv(&self) -> UvRect {
+        let center = (self.padding + self.solid_size / 2) as f32 / self.size as f32;
+        (center, center, 0.0, 0.0)
+    }
+
+    // Looks the glyph up under the default feature set
+    pub fn get_glyph(&mut self, key: CacheKey, font_system: &mut FontSystem) -> Result<Option<(SwashImage, UvRect)>, TextError> {
+        self.get_keyed(GlyphKey::plain(key), font_system)
+    }
+
+    fn get_keyed(&mut self, glyph_key: GlyphKey, font_system: &mut FontSystem) -> Result<Option<(SwashImage, UvRect)>, TextError> {
+        if let Some(entry) = self.glyphs.get_mut(&glyph_key) {
+            entry.used = self.frame;
+            return Ok(Some((entry.image.clone(), entry.rect)));
+        }
+
+        let key = glyph_key.cache_key;
+        let image = {
+            let _span = trace_span!("textware::rasterize", glyph_id = key.glyph_id);
+            self.swash_cache
+                .get_image(&mut font_system.sys, key)
+                .clone()
+                .ok_or(TextError::GlyphRasterization { cache_key: key })?
+        };
 
-            let uv_rect = (
-                x as f32 / ATLAS_SIZE as f32,
-                y as f32 / ATLAS_SIZE as f32,
-                w as f32 / ATLAS_SIZE as f32,
-                h as f32 / ATLAS_SIZE as f32,
-            );
-            self.glyphs.insert(key, (image, uv_rect));
+        // Color glyphs go to the RGBA atlas while we have them; swash renders alpha
+        // masks, so subpixel masks never come up
+        if image.content == Content::Color {
+            if let Err(err) = self.color.insert(glyph_key, image) {
+                log::debug!("textware: {}", err);
+            }
+            return Ok(None);
         }
+        if image.content != Content::Mask { return Ok(None); }
+
+        let rect = self.place_glyph(glyph_key, image.clone())?;
+        Ok(Some((image, rect)))
+    }
+
+    // Expects the text to be shaped already, see FontSystem::shape
+    pub fn generate_mesh(&mut self, font_system: &mut FontSystem, text: &Text) -> TextMesh {
+        let mut mesh = TextMesh::with_capacity(text.glyph_count());
+        self.append_glyphs(font_system, text, [0.0, 0.0], None, &mut mesh);
+        mesh
     }
 
-    pub fn get_glyph(&mut self, key: CacheKey, font_system: &mut FontSystem) -> Option<(SwashImage, (f32, f32, f32, f32))> {
-        if let Some((image, rect)) = self.glyphs.get(&key) {
-            return Some((image.clone(), *rect));
+    pub(crate) fn append_glyphs(&mut self, font_system: &mut FontSystem, text: &Text, offset: [f32; 2], color_override: Option<[f32; 4]>, mesh: &mut TextMesh) {
+        let result = self.append_glyphs_with(font_system, text, offset, mesh, |_, _, color| {
+            if let Some(override_color) = color_override {
+                *color = override_color;
+            }
+            true
+        });
+
+        if let Err(err) = result {
+            log::debug!("textware: {}", err);
         }
+    }
 
-        let image = self.swash_cache.get_image(&mut font_system.sys, key).clone()?;
-        
-        if image.content != Content::Mask { return None; }
+    pub(crate) fn append_glyphs_with<F>(&mut self, font_system: &mut FontSystem, text: &Text, offset: [f32; 2], mesh: &mut TextMesh, adjust: F) -> Result<(), TextError>
+    where
+        F: FnMut(&GlyphContext, &mut [f32; 2], &mut [f32; 4]) -> bool,
+    {
+        let first_vertex = mesh.vertices.len();
+        let first_index = mesh.indices.len();
 
-        let rect = self.place_glyph(key, image.clone())?;
-        Some((image, rect))
+        let scale = text.scale_factor;
+        let result = self.append_layout_glyphs(font_system, text, offset, mesh, Raster { scale, ..Raster::PLAIN }, adjust);
+        clip_to_bounds(text, offset, scale, mesh, first_vertex);
+        space::apply_space(text.space, mesh, first_vertex, first_index, scale);
+        result
     }
 
-    fn place_glyph(&mut self, key: CacheKey, image: SwashImage) -> Option<(f32, f32, f32, f32)> {
+    // Glyphs rasterized at `scale` times their size, positions scaled to match
+    pub(crate) fn append_scaled<F>(&mut self, font_system: &mut FontSystem, text: &Text, scale: f32, mesh: &mut TextMesh, adjust: F) -> Result<(), TextError>
+    where
+        F: FnMut(&GlyphContext, &mut [f32; 2], &mut [f32; 4]) -> bool,
+    {
+        let first_vertex = mesh.vertices.len();
+        let first_index = mesh.indices.len();
+
+        let raster_scale = scale * text.scale_factor;
+        let result = self.append_layout_glyphs(font_system, text, [0.0, 0.0], mesh, Raster { scale: raster_scale, ..Raster::PLAIN }, adjust);
+        clip_to_bounds(text, [0.0, 0.0], raster_scale, mesh, first_vertex);
+        space::apply_space(text.space, mesh, first_vertex, first_index, text.scale_factor);
+        result
+    }
+
+    // Color glyph pass, sampled from the RGBA atlas with its own bind group
+    pub(crate) fn append_color_glyphs(&mut self, font_system: &mut FontSystem, text: &Text, offset: [f32; 2], mesh: &mut TextMesh) -> Result<(), TextError> {
+        let first_vertex = mesh.vertices.len();
+        let first_index = mesh.indices.len();
+
+        let scale = text.scale_factor;
+        let result = self.append_layout_glyphs(font_system, text, offset, mesh, Raster { scale, color: true, ..Raster::PLAIN }, |_, _, _| true);
+        clip_to_bounds(text, offset, scale, mesh, first_vertex);
+        space::apply_space(text.space, mesh, first_vertex, first_index, scale);
+        result
+    }
+
+    // Shadow pass drawing blurred copies of the glyphs in a single color
+    pub(crate) fn append_blurred(&mut self, font_system: &mut FontSystem, text: &Text, offset: [f32; 2], mesh: &mut TextMesh, radius: f32, shadow_color: [f32; 4]) -> Result<(), TextError> {
+        let first_vertex = mesh.vertices.len();
+        let first_index = mesh.indices.len();
+
+        let scale = text.scale_factor;
+        let result = self.append_layout_glyphs(font_system, text, offset, mesh, Raster { scale, blur: radius, ..Raster::PLAIN }, |_, _, color| {
+            *color = shadow_color;
+            true
+        });
+        clip_to_bounds(text, offset, scale, mesh, first_vertex);
+        space::apply_space(text.space, mesh, first_vertex, first_index, scale);
+        result
+    }
+
+    // Quads written so far, decorations included, carry UVs of the atlas at `atlas_size`
+    fn follow_growth<S: QuadSink>(&self, sink: &mut S, atlas_size: &mut u32) {
+        if self.size != *atlas_size {
+            sink.rescale_uvs(*atlas_size as f32 / self.size as f32);
+            *atlas_size = self.size;
+        }
+    }
+
+    pub(crate) fn append_layout_glyphs<S, F>(&mut self, font_system: &mut FontSystem, text: &Text, offset: [f32; 2], sink: &mut S, raster: Raster, mut adjust: F) -> Result<(), TextError>
+    where
+        S: QuadSink,
+        F: FnMut(&GlyphContext, &mut [f32; 2], &mut [f32; 4]) -> bool,
+    {
+        let Raster { scale, blur, color: color_only } = raster;
+        let mut atlas_size = self.size;
+
+        #[cfg(feature = "parallel")]
+        if text.embolden == 0.0 && text.hollow.is_none() && text.distance_field.is_none() && scale == 1.0 {
+            self.rasterize_parallel(font_system, &[text]);
+        }
+        if !color_only {
+            self.follow_growth(sink, &mut atlas_size);
+        }
+
+        let mut first_glyph = 0;
+        let mut first_error = None;
+
+        let edge_fade = EdgeFade::new(text);
+
+        for (run_index, run) in text.buffer.layout_runs().enumerate() {
+            let mut advance_shift = 0.0;
+            let line_alpha = edge_fade.as_ref().map_or(1.0, |fade| fade.alpha(run.line_top));
+
+            let split = features::split_ligatures(font_system, text, &run);
+            let plain = split.is_none().then(|| run.glyphs.iter().enumerate());
+            let glyphs = split.iter().flatten().map(|(glyph_index, glyph)| (*glyph_index, glyph)).chain(plain.into_iter().flatten());
+
+            for (glyph_index, glyph) in glyphs {
+                let index = first_glyph + glyph_index;
+                let physical = glyph.physical((0., 0.), scale);
+                let line_y = run.line_y * scale;
+                let font_size = glyph.font_size * scale;
+                let width = glyph.w * scale;
+
+                // Shadows and color glyphs keep plain masks under a distance field
+                let distance_field = text.distance_field.filter(|_| blur == 0.0 && !color_only);
+                let (key, stretch) = match distance_field {
+                    Some(field) => field.key(get_cache_key(&physical)),
+                    None => self.bucketed(get_cache_key(&physical)),
+                };
+
+                let context = GlyphContext {
+                    index,
+                    font_size: glyph.font_size,
+                    line: run.line_i,
+                    run: run_index,
+                    cluster: glyph.start..glyph.end,
+                    quad: sink.quad_count(),
+                    style: text.spans.get(glyph.metadata & !INVISIBLE_METADATA),
+                };
+
+                let mut glyph_offset = offset;
+                // Lines were broken with the shaped widths, feature fallbacks only move glyphs
+                glyph_offset[0] += advance_shift;
+                let glyph_features = features::glyph_features(text, glyph);
+                if !glyph_features.kerning || glyph_features.tabular_figures {
+                    let (lead, advance) = features::adjusted_advance(font_system, glyph, &run.text[glyph.start..glyph.end], glyph_features);
+                    glyph_offset[0] += lead;
+                    advance_shift += advance - glyph.w;
+                }
+
+                let mut color = glyph.color_opt.map(rich::from_cosmic_color).unwrap_or(text.color);
+                let variant = hover::span_variant(text, glyph.metadata & !INVISIBLE_METADATA);
+                if let Some(hover_color) = variant.and_then(|variant| variant.color) {
+                    color = hover_color;
+                }
+                if !adjust(&context, &mut glyph_offset, &mut color) {
+                    continue;
+                }
+                color[3] *= line_alpha;
+                let glyph_offset = [glyph_offset[0] * scale, glyph_offset[1] * scale];
+
+                let underline = variant.is_some_and(|variant| variant.underline) || context.style.is_some_and(|style| style.underline);
+                let strikethrough = context.style.is_some_and(|style| style.strikethrough);
+                if let Some(mesh) = sink.decorations().filter(|_| (underline || strikethrough) && blur == 0.0 && !color_only) {
+                    if !mesh.has_capacity(2) {
+                        return Err(TextError::IndexOverflow);
+                    }
+                    let thickness = (font_size / 14.0).max(1.0);
+                    let x = glyph.x * scale + glyph_offset[0];
+                    if underline {
+                        mesh.push_quad([x, line_y + thickness + glyph_offset[1], width, thickness], self.solid_uv(), color);
+                    }
+                    // Around half the x-height above the baseline
+                    if strikethrough {
+                        mesh.push_quad([x, line_y - font_size * 0.3 - thickness / 2.0 + glyph_offset[1], width, thickness], self.solid_uv(), color);
+                    }
+                }
+
+                if glyph.metadata & INVISIBLE_METADATA != 0 {
+                    let stroke = (font_size / 16.0).round().max(1.0);
+                    let h = font_size * 0.7;
+                    let rect = [
+                        physical.x as f32 + glyph_offset[0],
+                        line_y + physical.y as f32 - h + glyph_offset[1],
+                        width.max(stroke * 3.0),
+                        h,
+                    ];
+                    // Zero width characters keep their box clear of the next glyph
+                    advance_shift += (rect[2] - width) / scale;
+
+                    if let Some(mesh) = sink.decorations().filter(|_| !color_only) {
+                        if !mesh.has_capacity(TextMesh::hatch_quads(rect, stroke)) {
+                            return Err(TextError::IndexOverflow);
+                        }
+                        mesh.push_hatch(rect, stroke, self.solid_uv(), color);
+                    }
+
+                    continue;
+                }
+
+                if self.show_missing && glyph.glyph_id == 0 {
+                    let stroke = (font_size / 16.0).round().max(1.0);
+                    let h = font_size * 0.7;
+                    let rect = [
+                        physical.x as f32 + glyph_offset[0] + stroke,
+                        line_y + physical.y as f32 - h + glyph_offset[1],
+                        (width - stroke * 2.0).max(stroke * 3.0),
+                        h,
+                    ];
+                    if let Some(mesh) = sink.decorations().filter(|_| !color_only) {
+                        if !mesh.has_capacity(4) {
+                            return Err(TextError::IndexOverflow);
+                        }
+                        mesh.push_frame(rect, stroke, self.solid_uv(), color);
+                    }
+
+                    continue;
+                }
+
+                let glyph_key = GlyphKey::new(key, glyph_features);
+                let placement = if color_only {
+                    // Color glyphs keep their own colors, only the alpha fades them
+                    color = [1.0, 1.0, 1.0, color[3]];
+                    self.color_placement(glyph_key, font_system)
+                } else if blur > 0.0 {
+                    self.blurred_placement(glyph_key, blur * scale, font_system)
+                } else if let Some(field) = distance_field {
+                    self.distance_placement(glyph_key, field, font_system)
+                } else if let Some(width) = text.hollow {
+                    self.outline_placement(glyph_key, Variant::Stroke((width * font_size).to_bits()), font_system)
+                } else if text.embolden == 0.0 {
+                    self.glyph_placement(glyph_key, font_system)
+                } else {
+                    self.outline_placement(glyph_key, Variant::Embolden((text.embolden * font_size).to_bits()), font_system)
+                };
+
+                if !color_only {
+                    self.follow_growth(sink, &mut atlas_size);
+                }
+
+                match placement {
+                    Ok(Some((placement, uv_rect))) => {
+                        let left = placement.left as f32 * stretch;
+                        let top = placement.top as f32 * stretch;
+                        let w = placement.width as f32 * stretch;
+                        let h = placement.height as f32 * stretch;
+
+                        // Distance fields drop the subpixel bin, the quad takes it instead
+                        let bin = if distance_field.is_some() { physical.cache_key.x_bin.as_float() } else { 0.0 };
+                        let x = physical.x as f32 + bin + left + glyph_offset[0];
+                        let y = line_y + physical.y as f32 - top + glyph_offset[1];
+
+                        sink.push_glyph(GlyphQuad {
+                            rect: [x, y, w, h],
+                            uv: uv_rect,
+                            color,
+                            line: run.line_i,
+                            run: run_index,
+                            cluster: glyph.start..glyph.end,
+                            index,
+                            font_size,
+                        })?;
+                    }
+                    Ok(None) => {}
+                    Err(err) => {
+                        first_error.get_or_insert(err);
+                    }
+                }
+            }
+            first_glyph += run.glyphs.len();
+        }
+
+        match first_error {
+            Some(err) => Err(err),
+            None => Ok(()),
+        }
+    }
+
+    // Same as get_glyph but without cloning the bitmap, for the per-frame mesh paths
+    pub(crate) fn glyph_placement(&mut self, key: GlyphKey, font_system: &mut FontSystem) -> Result<Option<(Placement, UvRect)>, TextError> {
+        if let Some(entry) = self.glyphs.get_mut(&key) {
+            entry.used = self.frame;
+            return Ok(Some((entry.image.placement, entry.rect)));
+        }
+        if self.color.get(&key).is_some() {
+            return Ok(None);
+        }
+
+        Ok(self.get_keyed(key, font_system)?.map(|(image, rect)| (image.placement, rect)))
+    }
+
+    fn color_placement(&mut self, key: GlyphKey, font_system: &mut FontSystem) -> Result<Option<(Placement, UvRect)>, TextError> {
+        if let Some(entry) = self.color.get(&key) {
+            return Ok(Some(entry));
+        }
+        if !self.color.enabled() || self.glyphs.contains_key(&key) {
+            return Ok(None);
+        }
+
+        // Rasterizing through the mask path files color glyphs into the RGBA atlas
+        self.get_keyed(key, font_system)?;
+        Ok(self.color.get(&key))
+    }
+
+    // Faux bold and hollow glyphs, rasterized from the outline with their own settings
+    fn outline_placement(&mut self, glyph_key: GlyphKey, variant: Variant, font_system: &mut FontSystem) -> Result<Option<(Placement, UvRect)>, TextError> {
+        let variant_key = (glyph_key, variant);
+        if let Some(entry) = self.variants.get_mut(&variant_key) {
+            entry.used = self.frame;
+            return Ok(Some((entry.image.placement, entry.rect)));
+        }
+
+        let key = glyph_key.cache_key;
+        let _span = trace_span!("textware::rasterize", glyph_id = key.glyph_id);
+        let Some(outline) = self.outlines.mask(font_system, key.font_id, key.glyph_id) else {
+            return Ok(None);
+        };
+        let Some(image) = render_outline(&outline, key, Some(variant)) else {
+            return Ok(None);
+        };
+
+        let rect = self.allocate(image.clone())?;
+        let placement = image.placement;
+        self.variants.insert(variant_key, Entry { image, rect, used: self.frame });
+        Ok(Some((placement, rect)))
+    }
+
+    fn distance_placement(&mut self, glyph_key: GlyphKey, field: DistanceField, font_system: &mut FontSystem) -> Result<Option<(Placement, UvRect)>, TextError> {
+        let variant_key = (glyph_key, Variant::Distance(field.spread.to_bits()));
+        if let Some(entry) = self.variants.get_mut(&variant_key) {
+            entry.used = self.frame;
+            return Ok(Some((entry.image.placement, entry.rect)));
+        }
+
+        let key = glyph_key.cache_key;
+        let _span = trace_span!("textware::distance_field", glyph_id = key.glyph_id);
+        let Some(outline) = self.outlines.mask(font_system, key.font_id, key.glyph_id) else {
+            return Ok(None);
+        };
+        let Some(image) = render_outline(&outline, key, None) else {
+            return Ok(None);
+        };
+
+        let field = sdf::distance_field(&image, field.spread);
+        let placement = field.placement;
+        let rect = self.allocate(field.clone())?;
+        self.variants.insert(variant_key, Entry { image: field, rect, used: self.frame });
+        Ok(Some((placement, rect)))
+    }
+
+    fn blurred_placement(&mut self, key: GlyphKey, radius: f32, font_system: &mut FontSystem) -> Result<Option<(Placement, UvRect)>, TextError> {
+        let blur_key = (key, Variant::Blur(radius.to_bits()));
+        if let Some(entry) = self.variants.get_mut(&blur_key) {
+            entry.used = self.frame;
+            return Ok(Some((entry.image.placement, entry.rect)));
+        }
+
+        if self.glyph_placement(key, font_system)?.is_none() {
+            return Ok(None);
+        }
+        let Some(entry) = self.glyphs.get(&key) else {
+            return Ok(None);
+        };
+
+        let _span = trace_span!("textware::blur_glyph", glyph_id = key.cache_key.glyph_id, radius);
+        let blurred = blur_mask(&entry.image, radius);
+        let placement = blurred.placement;
+        let rect = self.allocate(blurred.clone())?;
+        self.variants.insert(blur_key, Entry { image: blurred, rect, used: self.frame });
+        Ok(Some((placement, rect)))
+    }
+
+    // Rasterizes every uncached glyph of the texts up front on the rayon pool, worthwhile on first frames and font switches
+    #[cfg(feature = "parallel")]
+    pub(crate) fn rasterize_parallel(&mut self, font_system: &mut FontSystem, texts: &[&Text]) {
+        use rayon::prelude::*;
+
+        if self.deterministic {
+            return;
+        }
+
+        let mut keys: Vec<GlyphKey> = texts
+            .iter()
+            .flat_map(|text| {
+                text.buffer
+                    .layout_runs()
+                    .flat_map(|run| run.glyphs.iter())
+                    .map(|glyph| GlyphKey::new(self.bucketed(glyph.physical((0., 0.), 1.0).cache_key).0, features::glyph_features(text, glyph)))
+            })
+            .filter(|key| !self.glyphs.contains_key(key))
+            .collect();
+
+        if keys.len() < PARALLEL_THRESHOLD {
+            return;
+        }
+
+        keys.sort_unstable_by_key(|key| (key.cache_key.font_id, key.cache_key.glyph_id, key.cache_key.font_size_bits, key.features));
+        keys.dedup();
+
+        let jobs: Vec<_> = keys
+            .into_iter()
+            .filter_map(|key| font_system.sys.get_font(key.cache_key.font_id).map(|font| (key, font)))
+            .collect();
+
+        let _span = trace_span!("textware::rasterize_parallel", glyphs = jobs.len());
+
+        let images: Vec<_> = jobs
+            .par_iter()
+            .map_init(ScaleContext::new, |context, (key, font)| (*key, rasterize(context, font, key.cache_key)))
+            .collect();
+
+        // Glyphs that don't fit are left to the sequential path, which reports the error
+        for (key, image) in images {
+            let Some(image) = image.filter(|image| image.content == Content::Mask) else {
+                continue;
+            };
+
+            if self.place_glyph(key, image).is_err() {
+                break;
+            }
+        }
+    }
+
+    fn place_glyph(&mut self, key: GlyphKey, image: SwashImage) -> Result<UvRect, TextError> {
+        let uv_rect = self.allocate(image.clone())?;
+        self.glyphs.insert(key, Entry { image, rect: uv_rect, used: self.frame });
+        Ok(uv_rect)
+    }
+
+    // Doubles the atlas, keeping every entry at its pixel position so only the UVs change.
+    // The backend texture follows on the next upload.
+    fn grow(&mut self) {
+        trace_event!(size = self.size * 2, "atlas grown");
+        self.size *= 2;
+        self.generation += 1;
+        for Entry { rect, .. } in self.glyphs.values_mut().chain(self.variants.values_mut()) {
+            *rect = (rect.0 / 2.0, rect.1 / 2.0, rect.2 / 2.0, rect.3 / 2.0);
+        }
+    }
+
+    // Evicts entries not used in the last `max_age` frames (upload_pending calls, TextWare::prepare) and repacks
+    // the rest from the top, so the freed rows become usable again. Every UV moves, which
+    // bumps atlas_generation. Returns how many entries were evicted.
+    pub fn trim(&mut self, max_age: u64) -> usize {
+        let _span = trace_span!("textware::trim", max_age);
+
+        let before = self.glyphs.len() + self.variants.len();
+        let frame = self.frame;
+        self.glyphs.retain(|_, entry| frame - entry.used <= max_age);
+        self.variants.retain(|_, entry| frame - entry.used <= max_age);
+        let evicted = before - self.glyphs.len() - self.variants.len();
+        if evicted == 0 {
+            return 0;
+        }
+
+        // Tallest first packs the shelves tighter; atlas order breaks ties so the result is deterministic
+        let mut entries: Vec<_> = self.glyphs.drain().map(|(key, entry)| (Slot::Glyph(key), entry)).collect();
+        entries.extend(self.variants.drain().map(|(key, entry)| (Slot::Variant(key), entry)));
+        entries.sort_unstable_by(|(_, a), (_, b)| {
+            b.image.placement.height.cmp(&a.image.placement.height).then(a.rect.1.total_cmp(&b.rect.1)).then(a.rect.0.total_cmp(&b.rect.0))
+        });
+
+        let used_rows = (self.next_y + self.row_height + self.padding).min(self.size);
+        self.pending_uploads.clear();
+        self.next_x = self.padding + self.solid_size + self.padding;
+        self.next_y = self.padding;
+        self.row_height = self.solid_size;
+        self.solid_pending = true;
+        self.clear_rows = self.clear_rows.max(used_rows);
+        self.generation += 1;
+
+        for (slot, mut entry) in entries {
+            // Dropped entries are rasterized again on their next use
+            let Ok(rect) = self.allocate(entry.image.clone()) else {
+                continue;
+            };
+            entry.rect = rect;
+            match slot {
+                Slot::Glyph(key) => self.glyphs.insert(key, entry),
+                Slot::Variant(key) => self.variants.insert(key, entry),
+            };
+        }
+
+        trace_event!(evicted, "glyph atlas trimmed");
+        evicted
+    }
+
+    // Packs the bitmap into the atlas and queues its upload
+    fn allocate(&mut self, image: SwashImage) -> Result<UvRect, TextError> {
+        let padding = self.padding;
         let w = image.placement.width;
         let h = image.placement.height;
 
-        if self.next_x + w + PADDING > ATLAS_SIZE {
-            self.next_x = PADDING;
-            self.next_y += self.row_height + PADDING;
+        while (w + padding * 2 > self.size || h + padding * 2 > self.size) && self.size * 2 <= self.max_size {
+            self.grow();
+        }
+        let atlas_size = self.size;
+
+        // Checked before wrapping the row so an oversized glyph doesn't waste the rest of it
+        if w + padding * 2 > atlas_size || h + padding * 2 > atlas_size {
+            trace_event!(width = w, height = h, "glyph larger than atlas, dropped");
+            return Err(TextError::GlyphTooLarge { width: w, height: h });
+        }
+
+        if self.next_x + w + padding > atlas_size {
+            self.next_x = padding;
+            self.next_y += self.row_height + padding;
             self.row_height = 0;
         }
 
-        if self.next_y + h + PADDING > ATLAS_SIZE {
-            return None;
+        if self.next_y + h + padding > atlas_size {
+            if self.size * 2 > self.max_size {
+                trace_event!(width = w, height = h, "atlas full, glyph dropped");
+                return Err(TextError::AtlasFull);
+            }
+            self.grow();
+            return self.allocate(image);
         }
 
         let x = self.next_x;
         let y = self.next_y;
 
-        self.pending_uploads.push((key, x, y, image));
-        self.next_x += w + PADDING;
+        trace_event!(x, y, width = w, height = h, "glyph placed");
+
+        let uv_rect = (
+            x as f32 / atlas_size as f32,
+            y as f32 / atlas_size as f32,
+            w as f32 / atlas_size as f32,
+            h as f32 / atlas_size as f32,
+        );
+
+        self.pending_uploads.push((x, y, image));
+        self.next_x += w + padding;
         self.row_height = self.row_height.max(h);
 
-        Some((
-            x as f32 / ATLAS_SIZE as f32,
-            y as f32 / ATLAS_SIZE as f32,
-            w as f32 / ATLAS_SIZE as f32,
-            h as f32 / ATLAS_SIZE as f32,
-        ))
+        Ok(uv_rect)
     }
 }
 
+enum Slot {
+    Glyph(GlyphKey),
+    Variant((GlyphKey, Variant)),
+}
+
+struct Entry {
+    image: SwashImage,
+    rect: UvRect,
+    // Frame of the last lookup
+    used: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Variant {
+    Embolden(u32),
+    Blur(u32),
+    // Outline stroke width in pixels, no fill
+    Stroke(u32),
+    // Signed distances with this spread, rasterized at the key's size
+    Distance(u32),
+}
+
+// Separable gaussian blur of a coverage mask, grown by the blur radius on every side
+fn blur_mask(image: &SwashImage, radius: f32) -> SwashImage {
+    let pad = radius.ceil() as usize;
+    let sigma = (radius / 2.0).max(0.5);
+    let kernel: Vec<f32> = (0..=pad * 2).map(|i| (-((i as f32 - pad as f32).powi(2)) / (2.0 * sigma * sigma)).exp()).collect();
+    let total: f32 = kernel.iter().sum();
+
+    let (w, h) = (image.placement.width as usize, image.placement.height as usize);
+    let (out_w, out_h) = (w + pad * 2, h + pad * 2);
+
+    let mut padded = vec![0.0f32; out_w * out_h];
+    for (row, line) in image.data.chunks_exact(w.max(1)).take(h).enumerate() {
+        for (column, &coverage) in line.iter().enumerate() {
+            padded[(row + pad) * out_w + column + pad] = coverage as f32;
+        }
+    }
+
+    let mut horizontal = vec![0.0f32; out_w * out_h];
+    for y in 0..out_h {
+        for x in 0..out_w {
+            horizontal[y * out_w + x] = kernel
+                .iter()
+                .enumerate()
+                .filter_map(|(k, weight)| (x + k).checked_sub(pad).filter(|sx| *sx < out_w).map(|sx| padded[y * out_w + sx] * weight))
+                .sum::<f32>() / total;
+        }
+    }
+
+    let mut data = vec![0u8; out_w * out_h];
+    for y in 0..out_h {
+        for x in 0..out_w {
+            let value: f32 = kernel
+                .iter()
+                .enumerate()
+                .filter_map(|(k, weight)| (y + k).checked_sub(pad).filter(|sy| *sy < out_h).map(|sy| horizontal[sy * out_w + x] * weight))
+                .sum::<f32>() / total;
+            data[y * out_w + x] = value.round().clamp(0.0, 255.0) as u8;
+        }
+    }
+
+    let mut placement = image.placement;
+    placement.left -= pad as i32;
+    placement.top += pad as i32;
+    placement.width = out_w as u32;
+    placement.height = out_h as u32;
+
+    SwashImage { source: image.source, content: Content::Mask, placement, data }
+}
+
 pub fn get_cache_key(glyph: &cosmic_text::PhysicalGlyph) -> CacheKey {
     glyph.cache_key
-}
\ No newline at end of file
+}
+
+// Mirrors cosmic-text's SwashCache rendering so results match the sequential path
+#[cfg(feature = "parallel")]
+fn rasterize(context: &mut ScaleContext, font: &cosmic_text::Font, key: CacheKey) -> Option<SwashImage> {
+    use swash::scale::{Render, Source, StrikeWith};
+    use swash::zeno::{Angle, Format, Transform, Vector};
+
+    let mut scaler = context
+        .builder(font.as_swash())
+        .size(f32::from_bits(key.font_size_bits))
+        .hint(true)
+        .build();
+
+    let transform = key.flags
+        .contains(cosmic_text::CacheKeyFlags::FAKE_ITALIC)
+        .then(|| Transform::skew(Angle::from_degrees(14.0), Angle::from_degrees(0.0)));
+
+    Render::new(&[
+        Source::ColorOutline(0),
+        Source::ColorBitmap(StrikeWith::BestFit),
+        Source::Outline,
+    ])
+    .format(Format::Alpha)
+    .offset(Vector::new(key.x_bin.as_float(), key.y_bin.as_float()))
+    .transform(transform)
+    .render(&mut scaler, key.glyph_id)
+}
+
+// Draws a cached outline at the key's size, placed like swash places its renders. The
+// outline is unhinted, which these variants hide anyway. Faux bold follows swash: the
+// glyph grows up and to the right by twice the strength, or shrinks for a negative one.
+fn render_outline(outline: &GlyphOutline, key: CacheKey, variant: Option<Variant>) -> Option<SwashImage> {
+    use swash::scale::Source;
+    use swash::zeno::{Angle, Format, Mask, Origin, Stroke, Style, Transform, Vector};
+
+    if outline.commands.is_empty() {
+        return None;
+    }
+    let commands = outline.scaled(f32::from_bits(key.font_size_bits));
+
+    let embolden = match variant {
+        Some(Variant::Embolden(strength)) => f32::from_bits(strength),
+        _ => 0.0,
+    };
+    let mut transform = Transform::translation(embolden, embolden);
+    if key.flags.contains(cosmic_text::CacheKeyFlags::FAKE_ITALIC) {
+        transform = transform.then(&Transform::skew(Angle::from_degrees(14.0), Angle::from_degrees(0.0)));
+    }
+
+    // `within` renders into the box of an earlier mask, texel for texel
+    let render = |style: Style, within: Option<Placement>| {
+        let mut mask = Mask::new(&commands);
+        mask.format(Format::Alpha)
+            .origin(Origin::BottomLeft)
+            .style(style)
+            .transform(Some(transform))
+            .render_offset(Vector::new(key.x_bin.as_float(), key.y_bin.as_float()));
+        if let Some(at) = within {
+            mask.size(at.width, at.height).offset(Vector::new(-at.left as f32, at.height as f32 - at.top as f32));
+        }
+        // Sized through inspect like swash does, render() alone misplaces the top
+        let mut data = Vec::new();
+        let placement = mask.inspect(|format, w, h| data.resize(format.buffer_size(w, h), 0)).render_into(&mut data, None);
+        (data, placement)
+    };
+
+    let (data, placement) = match variant {
+        Some(Variant::Stroke(width)) => render(Stroke::new(f32::from_bits(width)).into(), None),
+        // A stroke twice the strength wide reaches that far past the outline on both sides,
+        // so it covers the grown glyph or, taken away from the fill, the shrunk one
+        Some(Variant::Embolden(_)) => {
+            let (mut stroke, placement) = render(Stroke::new(embolden.abs() * 2.0).into(), None);
+            let (fill, _) = render(Style::default(), Some(placement));
+            for (texel, &coverage) in stroke.iter_mut().zip(&fill) {
+                *texel = if embolden > 0.0 { (*texel).max(coverage) } else { coverage.min(255 - *texel) };
+            }
+            (stroke, placement)
+        }
+        _ => render(Style::default(), None),
+    };
+
+    Some(SwashImage { source: Source::Outline, content: Content::Mask, placement, data })
+}
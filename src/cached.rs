@@ -0,0 +1,44 @@
+use crate::backend::AtlasBackend;
+use crate::{Text, TextMesh, TextWare};
+
+// Mesh kept by mesh_for, with the inputs it was built from that reshaping doesn't cover
+pub(crate) struct CachedMesh {
+    mesh: TextMesh,
+    // Text::color is a public field, so writes to it can't drop the cache themselves
+    color: [f32; 4],
+    atlas_generation: u32,
+}
+
+impl Text {
+    // Drops the mesh kept by mesh_for. Text changes do this on their own; TextWare wide
+    // settings such as set_show_missing or set_size_buckets need it called by hand.
+    pub fn invalidate_mesh(&mut self) {
+        self.cached_mesh = None;
+    }
+
+    pub fn has_cached_mesh(&self) -> bool {
+        self.cached_mesh.is_some()
+    }
+}
+
+impl<B: AtlasBackend> TextWare<B> {
+    // generate_mesh for texts that rarely change, e.g. static labels drawn every frame. The
+    // mesh is rebuilt only after the text is reshaped, recolored or restyled, or the atlas
+    // moved its UVs by growing or trimming.
+    pub fn mesh_for<'t>(&mut self, text: &'t mut Text) -> &'t TextMesh {
+        // Reshaping drops the cached mesh
+        self.shape(text);
+
+        let generation = self.glyph_cache.atlas_generation();
+        let cached = match text.cached_mesh.take().filter(|cached| cached.color == text.color && cached.atlas_generation == generation) {
+            Some(cached) => cached,
+            None => {
+                let _span = trace_span!("textware::mesh_for");
+                let mesh = self.generate_mesh(text);
+                CachedMesh { mesh, color: text.color, atlas_generation: self.glyph_cache.atlas_generation() }
+            }
+        };
+
+        &text.cached_mesh.insert(cached).mesh
+    }
+}
@@ -0,0 +1,160 @@
+use crate::backend::AtlasBackend;
+use crate::error::TextError;
+use crate::style::TextStyle;
+use crate::{layout_bounds, TextMesh, TextWare};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AxisOrientation {
+    Horizontal,
+    Vertical,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct AxisLabel<'a> {
+    pub content: &'a str,
+    pub position: f32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AxisLabelOptions {
+    pub style: TextStyle,
+    pub orientation: AxisOrientation,
+    pub origin: [f32; 2],
+    pub offset: f32,
+    pub min_gap: f32,
+    pub rotation: f32,
+}
+
+impl Default for AxisLabelOptions {
+    fn default() -> Self {
+        Self {
+            style: TextStyle {
+                font_size: 12.0,
+                ..Default::default()
+            },
+            orientation: AxisOrientation::Horizontal,
+            origin: [0.0, 0.0],
+            offset: 4.0,
+            min_gap: 4.0,
+            rotation: std::f32::consts::FRAC_PI_4,
+        }
+    }
+}
+
+pub struct PlacedLabel {
+    pub index: usize,
+    pub anchor: [f32; 2],
+    pub size: [f32; 2],
+    pub mesh: TextMesh,
+}
+
+pub struct AxisLayout {
+    pub labels: Vec<PlacedLabel>,
+    pub rotated: bool,
+    pub skipped: usize,
+}
+
+impl<B: AtlasBackend> TextWare<B> {
+    pub fn layout_axis_labels(&mut self, labels: &[AxisLabel], options: &AxisLabelOptions) -> Result<AxisLayout, TextError> {
+        let finite = options.origin.iter().all(|v| v.is_finite())
+            && options.offset.is_finite()
+            && options.min_gap.is_finite()
+            && options.rotation.is_finite()
+            && labels.iter().all(|label| label.position.is_finite());
+        if !finite {
+            return Err(TextError::InvalidArgument("axis label layout contains a non-finite value".to_string()));
+        }
+
+        let mut measured = Vec::with_capacity(labels.len());
+        for label in labels {
+            let mut text = self.create_text_styled(label.content, &options.style)?;
+            self.shape(&mut text);
+            let bounds = layout_bounds(&text).unwrap_or([0.0; 4]);
+            measured.push((text, bounds));
+        }
+
+        let extent = |bounds: &[f32; 4]| match options.orientation {
+            AxisOrientation::Horizontal => bounds[2] - bounds[0],
+            AxisOrientation::Vertical => bounds[3] - bounds[1],
+        };
+
+        let overlaps = |step: usize, rotated: bool| {
+            let mut previous: Option<(f32, f32)> = None;
+            for (i, label) in labels.iter().enumerate().step_by(step) {
+                let size = if rotated {
+                    let height = measured[i].1[3] - measured[i].1[1];
+                    height / options.rotation.sin().abs().max(f32::EPSILON)
+                } else {
+                    extent(&measured[i].1)
+                };
+
+                let (start, end) = (label.position - size / 2.0, label.position + size / 2.0);
+                if let Some((_, prev_end)) = previous {
+                    if start < prev_end + options.min_gap {
+                        return true;
+                    }
+                }
+                previous = Some((start, end));
+            }
+            false
+        };
+
+        let can_rotate = options.orientation == AxisOrientation::Horizontal && options.rotation != 0.0;
+        let rotated = can_rotate && overlaps(1, false);
+
+        let mut step = 1;
+        while step < labels.len() && overlaps(step, rotated) {
+            step += 1;
+        }
+
+        let mut placed = Vec::new();
+        for (i, label) in labels.iter().enumerate().step_by(step.max(1)) {
+            let (text, bounds) = &measured[i];
+            let width = bounds[2] - bounds[0];
+            let height = bounds[3] - bounds[1];
+            let [ox, oy] = options.origin;
+
+            let (anchor, offset) = match options.orientation {
+                AxisOrientation::Horizontal => {
+                    let anchor = [ox + label.position, oy];
+                    if rotated {
+                        (anchor, [anchor[0] - width, anchor[1] + options.offset])
+                    } else {
+                        (anchor, [anchor[0] - width / 2.0, anchor[1] + options.offset])
+                    }
+                }
+                AxisOrientation::Vertical => {
+                    let anchor = [ox, oy + label.position];
+                    (anchor, [anchor[0] - options.offset - width, anchor[1] - height / 2.0])
+                }
+            };
+
+            let mut mesh = TextMesh::default();
+            self.append_glyphs(text, [offset[0] - bounds[0], offset[1] - bounds[1]], None, &mut mesh);
+
+            if rotated {
+                let pivot = [anchor[0], anchor[1] + options.offset];
+                let (sin, cos) = (-options.rotation).sin_cos();
+                for vertex in mesh.vertices.iter_mut() {
+                    let dx = vertex.position[0] - pivot[0];
+                    let dy = vertex.position[1] - pivot[1];
+                    vertex.position[0] = pivot[0] + dx * cos - dy * sin;
+                    vertex.position[1] = pivot[1] + dx * sin + dy * cos;
+                }
+            }
+
+            placed.push(PlacedLabel {
+                index: i,
+                anchor,
+                size: [width, height],
+                mesh,
+            });
+        }
+
+        Ok(AxisLayout {
+            skipped: labels.len() - placed.len(),
+            labels: placed,
+            rotated,
+        })
+    }
+}
@@ -0,0 +1,47 @@
+use crate::backend::AtlasBackend;
+use crate::error::TextError;
+use crate::{Cursor, Text, TextMesh, TextWare};
+
+pub struct ClampedMesh {
+    pub mesh: TextMesh,
+    pub visible_lines: usize,
+    pub hidden_lines: usize,
+    // Characters past the cutoff, line breaks not counted
+    pub hidden_chars: usize,
+    // Start of the first hidden line
+    pub cutoff: Option<Cursor>,
+}
+
+impl<B: AtlasBackend> TextWare<B> {
+    // Draws at most `max_lines` layout lines and measures what was left out,
+    // so "show more" buttons can say how much is behind them
+    pub fn generate_clamped_mesh(&mut self, text: &mut Text, max_lines: usize) -> Result<ClampedMesh, TextError> {
+        let _span = trace_span!("textware::generate_clamped_mesh", max_lines);
+
+        self.shape(text);
+
+        let total = text.buffer.layout_runs().count();
+        let cutoff = text
+            .buffer
+            .layout_runs()
+            .nth(max_lines)
+            .map(|run| Cursor::new(run.line_i, run.glyphs.first().map_or(0, |glyph| glyph.start)));
+
+        let hidden_chars = cutoff.map_or(0, |cursor| {
+            let lines = &text.buffer.lines;
+            let rest = lines[cursor.line].text().get(cursor.index..).map_or(0, |rest| rest.chars().count());
+            rest + lines[cursor.line + 1..].iter().map(|line| line.text().chars().count()).sum::<usize>()
+        });
+
+        let mut mesh = TextMesh::default();
+        self.append_glyphs_with(text, [0.0, 0.0], &mut mesh, |glyph, _, _| glyph.run < max_lines)?;
+
+        Ok(ClampedMesh {
+            mesh,
+            visible_lines: total.min(max_lines),
+            hidden_lines: total.saturating_sub(max_lines),
+            hidden_chars,
+            cutoff,
+        })
+    }
+}
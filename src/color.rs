@@ -0,0 +1,163 @@
+// Texts and spans store sRGB encoded RGBA, the layout of the atlas shader's vertex colors
+pub trait ToRgba {
+    fn to_rgba(&self) -> [f32; 4];
+}
+
+impl ToRgba for [f32; 4] {
+    fn to_rgba(&self) -> [f32; 4] {
+        *self
+    }
+}
+
+impl ToRgba for [f32; 3] {
+    fn to_rgba(&self) -> [f32; 4] {
+        [self[0], self[1], self[2], 1.0]
+    }
+}
+
+impl ToRgba for [u8; 4] {
+    fn to_rgba(&self) -> [f32; 4] {
+        self.map(|channel| channel as f32 / 255.0)
+    }
+}
+
+#[cfg(feature = "palette")]
+macro_rules! impl_palette_rgba {
+    ($($color:ty),*) => {
+        $(
+            impl ToRgba for $color {
+                fn to_rgba(&self) -> [f32; 4] {
+                    use palette::IntoColor;
+                    let srgba: palette::Srgba = (*self).into_color();
+                    [srgba.red, srgba.green, srgba.blue, srgba.alpha]
+                }
+            }
+        )*
+    };
+}
+
+// Linear and perceptual spaces are encoded to sRGB on the way in
+#[cfg(feature = "palette")]
+impl_palette_rgba!(
+    palette::Srgb,
+    palette::Srgba,
+    palette::LinSrgb,
+    palette::LinSrgba,
+    palette::Hsl,
+    palette::Hsla,
+    palette::Hsv,
+    palette::Hsva,
+    palette::Oklab,
+    palette::Oklaba,
+    palette::Oklch,
+    palette::Oklcha
+);
+
+#[cfg(feature = "palette")]
+impl ToRgba for palette::Srgba<u8> {
+    fn to_rgba(&self) -> [f32; 4] {
+        self.into_format::<f32, f32>().to_rgba()
+    }
+}
+
+#[cfg(feature = "csscolorparser")]
+impl ToRgba for csscolorparser::Color {
+    fn to_rgba(&self) -> [f32; 4] {
+        self.to_array()
+    }
+}
+
+impl crate::Text {
+    pub fn set_color(&mut self, color: impl ToRgba) {
+        self.color = color.to_rgba();
+    }
+}
+
+pub fn parse_color(value: &str) -> Option<[f32; 4]> {
+    let value = value.trim();
+
+    if let Some(hex) = value.strip_prefix('#') {
+        return parse_hex(hex);
+    }
+
+    let named = match value.to_ascii_lowercase().as_str() {
+        "black" => [0, 0, 0],
+        "white" => [255, 255, 255],
+        "red" => [255, 0, 0],
+        "green" => [0, 128, 0],
+        "lime" => [0, 255, 0],
+        "blue" => [0, 0, 255],
+        "yellow" => [255, 255, 0],
+        "cyan" | "aqua" => [0, 255, 255],
+        "magenta" | "fuchsia" => [255, 0, 255],
+        "orange" => [255, 165, 0],
+        "purple" => [128, 0, 128],
+        "pink" => [255, 192, 203],
+        "gray" | "grey" => [128, 128, 128],
+        "silver" => [192, 192, 192],
+        "brown" => [165, 42, 42],
+        "transparent" => return Some([0.0, 0.0, 0.0, 0.0]),
+        _ => return parse_css(value),
+    };
+
+    Some([named[0] as f32 / 255.0, named[1] as f32 / 255.0, named[2] as f32 / 255.0, 1.0])
+}
+
+// Full CSS color syntax: rgb(), hsl(), hwb(), lab() and every named color
+#[cfg(feature = "csscolorparser")]
+fn parse_css(value: &str) -> Option<[f32; 4]> {
+    csscolorparser::parse(value).ok().map(|color| color.to_array())
+}
+
+#[cfg(not(feature = "csscolorparser"))]
+fn parse_css(_value: &str) -> Option<[f32; 4]> {
+    None
+}
+
+fn parse_hex(hex: &str) -> Option<[f32; 4]> {
+    if !hex.is_ascii() {
+        return None;
+    }
+
+    let digit = |i: usize| u8::from_str_radix(&hex[i..i + 1], 16).ok().map(|d| d * 17);
+    let pair = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+
+    let [r, g, b, a] = match hex.len() {
+        3 => [digit(0)?, digit(1)?, digit(2)?, 255],
+        4 => [digit(0)?, digit(1)?, digit(2)?, digit(3)?],
+        6 => [pair(0)?, pair(2)?, pair(4)?, 255],
+        8 => [pair(0)?, pair(2)?, pair(4)?, pair(6)?],
+        _ => return None,
+    };
+
+    Some([r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0, a as f32 / 255.0])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hex_and_named_colors() {
+        assert_eq!(parse_color("#f00"), Some([1.0, 0.0, 0.0, 1.0]));
+        assert_eq!(parse_color("#0f08"), Some([0.0, 1.0, 0.0, 136.0 / 255.0]));
+        assert_eq!(parse_color("#0000ff"), Some([0.0, 0.0, 1.0, 1.0]));
+        assert_eq!(parse_color("#ffffff00"), Some([1.0, 1.0, 1.0, 0.0]));
+        assert_eq!(parse_color("  Lime "), Some([0.0, 1.0, 0.0, 1.0]));
+        assert_eq!(parse_color("transparent"), Some([0.0; 4]));
+    }
+
+    #[test]
+    fn malformed_colors_are_rejected() {
+        for value in ["", "#", "#ff", "#fffff", "#ggg", "#ééé", "not a color"] {
+            assert_eq!(parse_color(value), None, "{value}");
+        }
+    }
+
+    #[cfg(feature = "csscolorparser")]
+    #[test]
+    fn css_syntax_with_csscolorparser() {
+        assert_eq!(parse_color("rgb(255 0 0 / 50%)"), Some([1.0, 0.0, 0.0, 0.5]));
+        assert_eq!(parse_color("rebeccapurple").map(|color| (color[0] * 255.0).round()), Some(102.0));
+    }
+}
@@ -0,0 +1,112 @@
+use std::ops::Range;
+use crate::backend::AtlasBackend;
+use crate::error::TextError;
+use crate::{Cursor, Text, TextMesh, TextWare};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct ColumnLayout {
+    pub count: usize,
+    // Width of the whole layout box, columns split what's left after the gaps
+    pub width: f32,
+    // None lets the columns grow and always balances them
+    pub height: Option<f32>,
+    pub gap: f32,
+    pub balance: bool,
+}
+
+impl Default for ColumnLayout {
+    fn default() -> Self {
+        Self {
+            count: 2,
+            width: 600.0,
+            height: None,
+            gap: 24.0,
+            balance: true,
+        }
+    }
+}
+
+pub struct ColumnFlow {
+    pub mesh: TextMesh,
+    // Layout runs placed in each column
+    pub columns: Vec<Range<usize>>,
+    // First line that didn't fit, for continuing on the next page
+    pub overflow: Option<Cursor>,
+}
+
+impl<B: AtlasBackend> TextWare<B> {
+    pub fn generate_columns(&mut self, text: &mut Text, layout: &ColumnLayout) -> Result<ColumnFlow, TextError> {
+        let _span = trace_span!("textware::generate_columns", count = layout.count);
+
+        let count = layout.count.max(1);
+        let column_width = (layout.width - layout.gap * (count - 1) as f32) / count as f32;
+        if !column_width.is_finite() || column_width <= 0.0 || !layout.gap.is_finite() {
+            return Err(TextError::InvalidArgument(format!("column width {} is not positive", column_width)));
+        }
+
+        if text.buffer.size().0 != column_width {
+            self.set_size(text, Some(column_width), None)?;
+        }
+        self.shape(text);
+
+        let line_height = text.buffer.metrics().line_height;
+        let runs: Vec<(f32, Cursor)> = text.buffer
+            .layout_runs()
+            .map(|run| (run.line_top, Cursor::new(run.line_i, run.glyphs.first().map_or(0, |glyph| glyph.start))))
+            .collect();
+
+        let total = runs.last().map_or(0.0, |(top, _)| top + line_height);
+        let limit = layout.height.unwrap_or(f32::MAX);
+        let target = if layout.balance || layout.height.is_none() {
+            ((total / count as f32 / line_height).ceil() * line_height).clamp(line_height, limit)
+        } else {
+            limit
+        };
+
+        let first_top = runs.first().map_or(0.0, |(top, _)| *top);
+        let mut offsets: Vec<[f32; 2]> = Vec::with_capacity(runs.len());
+        let mut columns = Vec::new();
+        let mut overflow = None;
+        let mut column = 0;
+        let mut column_start = 0;
+        let mut column_top = first_top;
+
+        for (i, (top, cursor)) in runs.iter().enumerate() {
+            let height = top + line_height - column_top;
+            let last_column = column + 1 == count;
+
+            if i > column_start && height > target + 0.5 && !last_column {
+                columns.push(column_start..i);
+                column += 1;
+                column_start = i;
+                column_top = *top;
+            } else if i > column_start && height > limit + 0.5 {
+                overflow = Some(*cursor);
+                break;
+            }
+
+            offsets.push([column as f32 * (column_width + layout.gap), first_top - column_top]);
+        }
+        if column_start < offsets.len() {
+            columns.push(column_start..offsets.len());
+        }
+
+        let mut mesh = TextMesh::with_capacity(text.glyph_count());
+        let result = self.append_glyphs_with(text, [0.0, 0.0], &mut mesh, |glyph, offset, _| match offsets.get(glyph.run) {
+            Some([dx, dy]) => {
+                offset[0] += dx;
+                offset[1] += dy;
+                true
+            }
+            None => false,
+        });
+
+        if let Err(err) = result {
+            log::debug!("textware: {}", err);
+        }
+
+        Ok(ColumnFlow { mesh, columns, overflow })
+    }
+}
@@ -0,0 +1,131 @@
+use crate::backend::AtlasBackend;
+use crate::error::TextError;
+use crate::{Text, TextMesh, TextWare};
+
+// Steps tried towards white and towards black when searching for a readable color
+const ADJUST_STEPS: usize = 32;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct ContrastStyle {
+    // WCAG contrast ratio to reach, 4.5 for AA body text, 3 for large text, 7 for AAA
+    pub min_ratio: f32,
+    // Lightens or darkens the text color as little as needed
+    pub adjust_color: bool,
+    // Black or white outline around glyphs still below the ratio, 0 disables it
+    pub outline_width: f32,
+}
+
+impl Default for ContrastStyle {
+    fn default() -> Self {
+        Self { min_ratio: 4.5, adjust_color: true, outline_width: 1.5 }
+    }
+}
+
+// WCAG relative luminance of an sRGB color
+fn luminance(color: [f32; 4]) -> f32 {
+    let linear = |c: f32| {
+        let c = c.clamp(0.0, 1.0);
+        if c <= 0.04045 { c / 12.92 } else { ((c + 0.055) / 1.055).powf(2.4) }
+    };
+    0.2126 * linear(color[0]) + 0.7152 * linear(color[1]) + 0.0722 * linear(color[2])
+}
+
+// WCAG contrast ratio from 1 to 21, the foreground blended over the background by its
+// alpha and the background taken as opaque
+pub fn contrast_ratio(foreground: [f32; 4], background: [f32; 4]) -> f32 {
+    let alpha = foreground[3].clamp(0.0, 1.0);
+    let blended = std::array::from_fn(|i| if i < 3 { foreground[i] * alpha + background[i] * (1.0 - alpha) } else { 1.0 });
+    let (a, b) = (luminance(blended), luminance(background));
+    (a.max(b) + 0.05) / (a.min(b) + 0.05)
+}
+
+fn worst_ratio(color: [f32; 4], backgrounds: &[[f32; 4]]) -> f32 {
+    backgrounds.iter().map(|background| contrast_ratio(color, *background)).fold(f32::INFINITY, f32::min)
+}
+
+// `color` moved the least distance towards white or black that reaches `min_ratio` against
+// every background sample. When neither gets there the better of pure white and black wins.
+pub fn contrasting_color(color: [f32; 4], backgrounds: &[[f32; 4]], min_ratio: f32) -> [f32; 4] {
+    if backgrounds.is_empty() || worst_ratio(color, backgrounds) >= min_ratio {
+        return color;
+    }
+
+    let mix = |target: f32, t: f32| -> [f32; 4] { std::array::from_fn(|i| if i < 3 { color[i] + (target - color[i]) * t } else { color[3] }) };
+    for step in 1..=ADJUST_STEPS {
+        let t = step as f32 / ADJUST_STEPS as f32;
+        let (lighter, darker) = (mix(1.0, t), mix(0.0, t));
+        let (lighter_ratio, darker_ratio) = (worst_ratio(lighter, backgrounds), worst_ratio(darker, backgrounds));
+        if lighter_ratio >= min_ratio || darker_ratio >= min_ratio {
+            return if lighter_ratio >= darker_ratio { lighter } else { darker };
+        }
+    }
+
+    let (white, black) = (mix(1.0, 1.0), mix(0.0, 1.0));
+    if worst_ratio(white, backgrounds) >= worst_ratio(black, backgrounds) { white } else { black }
+}
+
+impl<B: AtlasBackend> TextWare<B> {
+    // Keeps the text readable over a busy background such as a map or video. `background`
+    // samples the color under a glyph's [x, y, w, h] box in layout space; a solid backdrop
+    // can return the same color every time.
+    pub fn generate_contrast_mesh<F>(&mut self, text: &mut Text, style: &ContrastStyle, mut background: F) -> Result<TextMesh, TextError>
+    where
+        F: FnMut([f32; 4]) -> [f32; 4],
+    {
+        let _span = trace_span!("textware::generate_contrast_mesh", min_ratio = style.min_ratio);
+
+        if !(1.0..=21.0).contains(&style.min_ratio) {
+            return Err(TextError::InvalidArgument(format!("contrast ratio {} is outside 1..=21", style.min_ratio)));
+        }
+        if !style.outline_width.is_finite() || style.outline_width < 0.0 {
+            return Err(TextError::InvalidArgument(format!("outline width {} is invalid", style.outline_width)));
+        }
+
+        self.shape(text);
+
+        let line_height = text.buffer.metrics().line_height;
+        let boxes: Vec<[f32; 4]> = text.buffer
+            .layout_runs()
+            .flat_map(|run| run.glyphs.iter().map(move |glyph| [glyph.x, run.line_top, glyph.w, line_height]))
+            .collect();
+
+        // Glyphs first so each one's color and outline are known before the outlines go under them
+        let mut glyphs = TextMesh::with_capacity(text.glyph_count());
+        let mut outlines: Vec<Option<[f32; 4]>> = vec![None; boxes.len()];
+        self.append_glyphs_with(text, [0.0, 0.0], &mut glyphs, |glyph, _, color| {
+            let Some(rect) = boxes.get(glyph.index) else {
+                return true;
+            };
+            let sample = background(*rect);
+            if style.adjust_color {
+                *color = contrasting_color(*color, &[sample], style.min_ratio);
+            }
+            if style.outline_width > 0.0 && contrast_ratio(*color, sample) < style.min_ratio {
+                let (black, white) = ([0.0, 0.0, 0.0, 1.0], [1.0, 1.0, 1.0, 1.0]);
+                let outline = if contrast_ratio(black, *color) >= contrast_ratio(white, *color) { black } else { white };
+                outlines[glyph.index] = Some([outline[0], outline[1], outline[2], color[3]]);
+            }
+            true
+        })?;
+
+        let mut mesh = TextMesh::default();
+        if outlines.iter().any(Option::is_some) {
+            for i in 0..8 {
+                let angle = i as f32 * std::f32::consts::FRAC_PI_4;
+                let offset = [angle.cos() * style.outline_width, angle.sin() * style.outline_width];
+                self.append_glyphs_with(text, offset, &mut mesh, |glyph, _, color| match outlines.get(glyph.index).copied().flatten() {
+                    Some(outline) => {
+                        *color = outline;
+                        true
+                    }
+                    None => false,
+                })?;
+            }
+        }
+        mesh.append(&glyphs)?;
+
+        Ok(mesh)
+    }
+}
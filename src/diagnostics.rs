@@ -0,0 +1,81 @@
+use std::ops::Range;
+use cosmic_text::fontdb;
+use crate::backend::AtlasBackend;
+use crate::font::FontId;
+use crate::{Text, TextWare, Weight};
+
+// Glyphs of one layout run shaped by the same face
+#[derive(Debug, Clone, PartialEq)]
+pub struct FaceRun {
+    pub line: usize,
+    pub run: usize,
+    pub range: Range<usize>,
+    pub face: fontdb::ID,
+    pub family: String,
+    // Loaded font the face came from, None for system fonts
+    pub font_id: Option<FontId>,
+    pub weight: Weight,
+    pub italic: bool,
+    // Face came from fallback instead of the requested family
+    pub fallback: bool,
+    // Requested weight or slant the face doesn't have. cosmic doesn't synthesize
+    // these, the glyphs render in the face's own style.
+    pub weight_mismatch: bool,
+    pub italic_mismatch: bool,
+    // Strokes thickened by set_embolden
+    pub emboldened: bool,
+    pub missing_glyphs: usize,
+}
+
+impl<B: AtlasBackend> TextWare<B> {
+    // Which face shaped each stretch of text, e.g. to catch a serif sneaking into a sans UI
+    pub fn face_runs(&mut self, text: &mut Text) -> Vec<FaceRun> {
+        let _span = trace_span!("textware::face_runs");
+
+        self.shape(text);
+
+        let mut runs: Vec<FaceRun> = Vec::new();
+        for (run_index, run) in text.buffer.layout_runs().enumerate() {
+            let run_start = runs.len();
+            let attrs_list = text.buffer.lines[run.line_i].attrs_list();
+
+            for glyph in run.glyphs.iter() {
+                let missing = usize::from(glyph.glyph_id == 0);
+                if let Some(face_run) = runs[run_start..].last_mut().filter(|face_run| face_run.face == glyph.font_id) {
+                    face_run.range = face_run.range.start.min(glyph.start)..face_run.range.end.max(glyph.end);
+                    face_run.missing_glyphs += missing;
+                    continue;
+                }
+
+                let requested = attrs_list.get_span(glyph.start);
+                let db = self.font_system.sys.db();
+                let Some(face) = db.face(glyph.font_id) else {
+                    continue;
+                };
+
+                let requested_family = db.family_name(&requested.family);
+                let family = face.families.first().map(|(name, _)| name.clone()).unwrap_or_default();
+                let fallback = !face.families.iter().any(|(name, _)| name.eq_ignore_ascii_case(requested_family));
+                let italic = face.style != fontdb::Style::Normal;
+
+                runs.push(FaceRun {
+                    line: run.line_i,
+                    run: run_index,
+                    range: glyph.start..glyph.end,
+                    face: glyph.font_id,
+                    font_id: self.font_system.font_for_face(glyph.font_id),
+                    weight: face.weight,
+                    italic,
+                    fallback,
+                    weight_mismatch: face.weight != requested.weight,
+                    italic_mismatch: italic != (requested.style != fontdb::Style::Normal),
+                    emboldened: text.embolden != 0.0,
+                    missing_glyphs: missing,
+                    family,
+                });
+            }
+        }
+
+        runs
+    }
+}
@@ -0,0 +1,99 @@
+use std::ops::Range;
+use crate::backend::AtlasBackend;
+use crate::font::FontSystem;
+use crate::{Text, TextWare};
+
+// Separators cosmic may also break paragraphs on, where a '\n' split wouldn't match its lines
+fn is_other_separator(c: char) -> bool {
+    matches!(c, '\r' | '\u{0b}' | '\u{0c}' | '\u{1c}'..='\u{1e}' | '\u{85}' | '\u{2028}' | '\u{2029}')
+}
+
+impl FontSystem {
+    // Replaces only the lines that differ from the current content, so the rest keep
+    // their shaping. Returns the replaced line range in the new content. Rich texts and
+    // contents cosmic would split differently fall back to update_text.
+    pub fn update_text_diff(&mut self, text: &mut Text, raw: &str) -> Range<usize> {
+        let _span = trace_span!("textware::update_text_diff", bytes = raw.len());
+
+        let content = self.input_filter().apply(raw);
+        let full = !text.spans.is_empty() || content.chars().any(is_other_separator);
+        if full {
+            self.update_text(text, raw);
+            return 0..text.buffer.lines.len();
+        }
+
+        let new_lines: Vec<&str> = content.split('\n').collect();
+        let old_lines = &text.buffer.lines;
+
+        let prefix = old_lines.iter().zip(&new_lines).take_while(|(old, new)| old.text() == **new).count();
+        let suffix = old_lines[prefix..]
+            .iter()
+            .rev()
+            .zip(new_lines[prefix..].iter().rev())
+            .take_while(|(old, new)| old.text() == **new)
+            .count();
+
+        let old_changed = prefix..old_lines.len() - suffix;
+        let new_changed = prefix..new_lines.len() - suffix;
+        if old_changed.is_empty() && new_changed.is_empty() {
+            return new_changed;
+        }
+
+        trace_event!(removed = old_changed.len(), inserted = new_changed.len(), "lines diffed");
+
+        let mut replacement = Vec::new();
+        if !new_changed.is_empty() {
+            let mut scratch = cosmic_text::Buffer::new(&mut self.sys, text.buffer.metrics());
+            self.fill_buffer(&mut scratch, &new_lines[new_changed.clone()].join("\n"), text.attrs.as_attrs(), text.shaping);
+            replacement = std::mem::take(&mut scratch.lines);
+            // Shaping carries over, the layout was done at the scratch buffer's width
+            for line in replacement.iter_mut() {
+                line.reset_layout();
+            }
+        }
+
+        text.buffer.lines.splice(old_changed, replacement);
+        text.dirty = true;
+        new_changed
+    }
+}
+
+impl<B: AtlasBackend> TextWare<B> {
+    pub fn update_text_diff(&mut self, text: &mut Text, content: &str) -> Range<usize> {
+        self.font_system.update_text_diff(text, content)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(text: &Text) -> Vec<&str> {
+        text.buffer.lines.iter().map(|line| line.text()).collect()
+    }
+
+    #[test]
+    fn only_changed_lines_are_replaced() {
+        let mut fonts = FontSystem::new();
+        let mut text = fonts.create_text("one\ntwo\nthree", None, 16.0, None).unwrap();
+        text.buffer.shape_until_scroll(&mut fonts.sys, false);
+
+        assert_eq!(fonts.update_text_diff(&mut text, "one\n2\nnew\nthree"), 1..3);
+        assert_eq!(lines(&text), ["one", "2", "new", "three"]);
+        // The untouched lines keep their layout, the new ones need laying out at the text's width
+        assert!(text.buffer.lines[0].layout_opt().is_some() && text.buffer.lines[3].layout_opt().is_some());
+        assert!(text.buffer.lines[1].layout_opt().is_none() && text.buffer.lines[2].layout_opt().is_none());
+
+        assert!(fonts.update_text_diff(&mut text, "one\n2\nnew\nthree").is_empty());
+        assert_eq!(fonts.update_text_diff(&mut text, "one\nthree"), 1..1);
+        assert_eq!(lines(&text), ["one", "three"]);
+    }
+
+    #[test]
+    fn other_separators_replace_everything() {
+        let mut fonts = FontSystem::new();
+        let mut text = fonts.create_text("one\ntwo", None, 16.0, None).unwrap();
+        let changed = fonts.update_text_diff(&mut text, "one\ntwo\u{2029}three");
+        assert_eq!(changed, 0..text.buffer.lines.len());
+    }
+}
@@ -0,0 +1,96 @@
+use unicode_segmentation::UnicodeSegmentation;
+use crate::backend::AtlasBackend;
+use crate::error::TextError;
+use crate::font::FontId;
+use crate::{text_content, Text, TextMesh, TextWare};
+
+// Used when the cap glyph has no outline to measure, e.g. bitmap emoji
+const FALLBACK_CAP_HEIGHT: f32 = 0.7;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct DropCap {
+    // Number of body lines the enlarged letter spans
+    pub lines: usize,
+    // Space between the letter and the text wrapping around it
+    pub gap: f32,
+    // None uses the font of the text
+    pub font_id: Option<FontId>,
+    pub color: Option<[f32; 4]>,
+}
+
+impl Default for DropCap {
+    fn default() -> Self {
+        Self {
+            lines: 3,
+            gap: 4.0,
+            font_id: None,
+            color: None,
+        }
+    }
+}
+
+impl<B: AtlasBackend> TextWare<B> {
+    // Enlarges the first letter of the text's first paragraph over `lines` lines,
+    // the letter's box becomes an exclusion the body flows around.
+    pub fn generate_drop_cap_mesh(&mut self, text: &mut Text, drop_cap: &DropCap) -> Result<TextMesh, TextError> {
+        let _span = trace_span!("textware::generate_drop_cap_mesh", lines = drop_cap.lines);
+
+        if !drop_cap.gap.is_finite() {
+            return Err(TextError::InvalidArgument(format!("drop cap gap {} is not finite", drop_cap.gap)));
+        }
+
+        let content = text_content(text);
+        let cap = match content.graphemes(true).next() {
+            Some(cap) if drop_cap.lines > 1 && !cap.trim().is_empty() => cap,
+            _ => return self.try_generate_mesh(text),
+        };
+
+        self.shape(text);
+        let metrics = text.buffer.metrics();
+        let baseline = text.buffer.layout_runs().next().map_or(metrics.font_size, |run| run.line_y - run.line_top);
+
+        let font_id = drop_cap.font_id.or(text.font_id);
+        let mut cap_text = self.font_system.create_text(cap, font_id, metrics.font_size, None)?;
+        if drop_cap.font_id.is_none() {
+            cap_text.attrs = text.attrs.clone();
+            self.font_system.update_text(&mut cap_text, cap);
+        }
+        cap_text.color = drop_cap.color.unwrap_or(text.color);
+        self.shape(&mut cap_text);
+
+        // Top of the letter lines up with the first line's letters, its baseline with the last line's
+        let cap_height = cap_text
+            .buffer
+            .layout_runs()
+            .next()
+            .and_then(|run| run.glyphs.first().map(|glyph| (glyph.font_id, glyph.glyph_id)))
+            .and_then(|(font, glyph)| self.glyph_outline(font, glyph))
+            .map(|outline| outline.bounds[3] / outline.units_per_em)
+            .filter(|height| *height > 0.0)
+            .unwrap_or(FALLBACK_CAP_HEIGHT);
+        let span = (drop_cap.lines - 1) as f32 * metrics.line_height;
+        let cap_size = metrics.font_size + span / cap_height;
+
+        self.font_system.resize_text(&mut cap_text, cap_size, None)?;
+        self.shape(&mut cap_text);
+
+        let (cap_width, cap_baseline) = cap_text
+            .buffer
+            .layout_runs()
+            .next()
+            .map_or((0.0, cap_size), |run| (run.line_w, run.line_y - run.line_top));
+
+        let mut mesh = TextMesh::with_capacity(text.glyph_count());
+        if let Err(err) = self.append_glyphs_with(&cap_text, [0.0, span + baseline - cap_baseline], &mut mesh, |_, _, _| true) {
+            log::debug!("textware: {}", err);
+        }
+
+        let mut exclusions = text.exclusions.clone();
+        exclusions.push([0.0, 0.0, cap_width + drop_cap.gap, drop_cap.lines as f32 * metrics.line_height]);
+        self.append_flow(text, &content[cap.len()..], &exclusions, [0.0, 0.0], &mut mesh)?;
+
+        Ok(mesh)
+    }
+}
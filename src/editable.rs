@@ -0,0 +1,210 @@
+use cosmic_text::{Action, Edit, Editor, Motion, Selection};
+use crate::backend::AtlasBackend;
+use crate::error::TextError;
+use crate::font::FontId;
+use crate::{Cursor, Text, TextMesh, TextWare};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CursorMotion {
+    Left,
+    Right,
+    Up,
+    Down,
+    Home,
+    End,
+    PreviousWord,
+    NextWord,
+    BufferStart,
+    BufferEnd,
+}
+
+impl From<CursorMotion> for Motion {
+    fn from(motion: CursorMotion) -> Self {
+        match motion {
+            CursorMotion::Left => Motion::Left,
+            CursorMotion::Right => Motion::Right,
+            CursorMotion::Up => Motion::Up,
+            CursorMotion::Down => Motion::Down,
+            CursorMotion::Home => Motion::Home,
+            CursorMotion::End => Motion::End,
+            CursorMotion::PreviousWord => Motion::PreviousWord,
+            CursorMotion::NextWord => Motion::NextWord,
+            CursorMotion::BufferStart => Motion::BufferStart,
+            CursorMotion::BufferEnd => Motion::BufferEnd,
+        }
+    }
+}
+
+// A Text with a caret and selection, edited through cosmic-text's Editor. The buffer
+// stays in the Text, so it draws and measures like any other.
+pub struct EditableText {
+    text: Text,
+    cursor: Cursor,
+    // Where the selection started, the caret being its other end
+    anchor: Option<Cursor>,
+    // Column kept while moving up and down through shorter lines
+    cursor_x: Option<i32>,
+}
+
+impl EditableText {
+    pub fn text(&self) -> &Text {
+        &self.text
+    }
+
+    pub fn text_mut(&mut self) -> &mut Text {
+        &mut self.text
+    }
+
+    pub fn into_text(self) -> Text {
+        self.text
+    }
+
+    pub fn cursor(&self) -> Cursor {
+        self.cursor
+    }
+
+    // Ordered start and end of the selection, None when nothing is selected
+    pub fn selection_bounds(&self) -> Option<(Cursor, Cursor)> {
+        let anchor = self.anchor.filter(|anchor| *anchor != self.cursor)?;
+        if (anchor.line, anchor.index) < (self.cursor.line, self.cursor.index) {
+            Some((anchor, self.cursor))
+        } else {
+            Some((self.cursor, anchor))
+        }
+    }
+
+    pub fn selected_text(&self) -> Option<String> {
+        let (start, end) = self.selection_bounds()?;
+        let lines = self.text.buffer.lines.get(start.line..=end.line)?;
+        let selected = lines
+            .iter()
+            .enumerate()
+            .map(|(offset, line)| {
+                let content = line.text();
+                let from = if offset == 0 { start.index } else { 0 };
+                let to = if start.line + offset == end.line { end.index } else { content.len() };
+                content.get(from..to).unwrap_or_default()
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        Some(selected)
+    }
+
+    pub fn content(&self) -> String {
+        crate::text_content(&self.text)
+    }
+
+    fn move_to(&mut self, cursor: Cursor, select: bool) {
+        if !select {
+            self.anchor = None;
+        } else if self.anchor.is_none() {
+            self.anchor = Some(self.cursor);
+        }
+        self.cursor = cursor;
+        self.text.mark_dirty();
+    }
+
+    fn end(&self) -> Cursor {
+        let line = self.text.buffer.lines.len().saturating_sub(1);
+        Cursor::new(line, self.text.buffer.lines.get(line).map_or(0, |line| line.text().len()))
+    }
+}
+
+impl<B: AtlasBackend> TextWare<B> {
+    // Plain text with the caret at the end
+    pub fn create_editable_text(&mut self, content: &str, font_id: Option<FontId>, font_size: f32, line_height: Option<f32>) -> Result<EditableText, TextError> {
+        let text = self.create_text(content, font_id, font_size, line_height)?;
+        let mut editable = EditableText { text, cursor: Cursor::new(0, 0), anchor: None, cursor_x: None };
+        editable.cursor = editable.end();
+        Ok(editable)
+    }
+
+    fn edit<R>(&mut self, editable: &mut EditableText, f: impl FnOnce(&mut Editor, &mut cosmic_text::FontSystem) -> R) -> R {
+        let mut editor = Editor::new(&mut editable.text.buffer);
+        editor.set_cursor(editable.cursor);
+        editor.set_selection(editable.anchor.map_or(Selection::None, Selection::Normal));
+
+        let result = f(&mut editor, &mut self.font_system.sys);
+
+        editable.cursor = editor.cursor();
+        editable.anchor = match editor.selection() {
+            Selection::Normal(anchor) => Some(anchor),
+            _ => None,
+        };
+        editable.cursor_x = None;
+        editable.text.mark_dirty();
+        result
+    }
+
+    // Replaces the selection, if any, with `content` after the input filter
+    pub fn insert(&mut self, editable: &mut EditableText, content: &str) {
+        let content = self.font_system.input_filter().apply(content);
+        self.edit(editable, |editor, _| editor.insert_string(&content, None));
+    }
+
+    // Deletes the selection, or the grapheme before the caret
+    pub fn backspace(&mut self, editable: &mut EditableText) {
+        self.edit(editable, |editor, font_system| editor.action(font_system, Action::Backspace));
+    }
+
+    // Deletes the selection, or the grapheme after the caret
+    pub fn delete(&mut self, editable: &mut EditableText) {
+        self.edit(editable, |editor, font_system| editor.action(font_system, Action::Delete));
+    }
+
+    // Extends the selection from the old caret when `select` is set, otherwise drops it
+    pub fn move_cursor(&mut self, editable: &mut EditableText, motion: CursorMotion, select: bool) {
+        self.shape(&mut editable.text);
+
+        let moved = editable.text.buffer.cursor_motion(&mut self.font_system.sys, editable.cursor, editable.cursor_x, motion.into());
+        if let Some((cursor, cursor_x)) = moved {
+            editable.move_to(cursor, select);
+            editable.cursor_x = cursor_x;
+        }
+    }
+
+    // E.g. from hit_test on a click, or a drag with `select` set
+    pub fn set_cursor(&mut self, editable: &mut EditableText, cursor: Cursor, select: bool) {
+        editable.move_to(cursor, select);
+        editable.cursor_x = None;
+    }
+
+    // Selects the word around `cursor`, e.g. on a double click
+    pub fn select_word(&mut self, editable: &mut EditableText, cursor: Cursor) {
+        editable.cursor = cursor;
+        editable.anchor = None;
+        let bounds = self.edit(editable, |editor, _| {
+            editor.set_selection(Selection::Word(editor.cursor()));
+            editor.selection_bounds()
+        });
+
+        if let Some((start, end)) = bounds {
+            editable.anchor = Some(start);
+            editable.cursor = end;
+        }
+    }
+
+    pub fn select_all(&mut self, editable: &mut EditableText) {
+        editable.anchor = Some(Cursor::new(0, 0));
+        editable.cursor = editable.end();
+        editable.cursor_x = None;
+        editable.text.mark_dirty();
+    }
+
+    pub fn generate_editable_mesh(&mut self, editable: &mut EditableText) -> TextMesh {
+        self.generate_mesh(&mut editable.text)
+    }
+
+    // Caret as [x, y, w, h] in layout space, `width` pixels wide
+    pub fn caret_rect(&mut self, editable: &mut EditableText, width: f32) -> Option<[f32; 4]> {
+        self.cursor_position(&mut editable.text, editable.cursor).map(|(x, top, height)| [x, top, width, height])
+    }
+
+    pub fn editable_selection_rects(&mut self, editable: &mut EditableText) -> Vec<[f32; 4]> {
+        match editable.selection_bounds() {
+            Some((start, end)) => self.selection_rects(&mut editable.text, start, end),
+            None => Vec::new(),
+        }
+    }
+}
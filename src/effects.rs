@@ -0,0 +1,136 @@
+use std::ops::Range;
+use crate::backend::AtlasBackend;
+use crate::{Text, TextMesh, TextWare};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SpanEffects(pub u8);
+
+impl SpanEffects {
+    pub const NONE: SpanEffects = SpanEffects(0);
+    pub const WAVE: SpanEffects = SpanEffects(1 << 0);
+    pub const SHAKE: SpanEffects = SpanEffects(1 << 1);
+    pub const RAINBOW: SpanEffects = SpanEffects(1 << 2);
+    pub const PULSE: SpanEffects = SpanEffects(1 << 3);
+
+    pub fn contains(self, other: SpanEffects) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+}
+
+impl std::ops::BitOr for SpanEffects {
+    type Output = SpanEffects;
+
+    fn bitor(self, rhs: SpanEffects) -> SpanEffects {
+        SpanEffects(self.0 | rhs.0)
+    }
+}
+
+// What a color callback gets to know about the glyph being colored
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClusterInfo {
+    // Glyph index across the whole text
+    pub index: usize,
+    // Buffer line, layout run and the cluster's byte range in the buffer line
+    pub line: usize,
+    pub run: usize,
+    pub cluster: Range<usize>,
+    // Color the glyph would get without the callback, from the text or its span
+    pub color: [f32; 4],
+}
+
+impl<B: AtlasBackend> TextWare<B> {
+    // Colors each glyph with `color_of`, for per-character coloring that changes every
+    // frame without rebuilding spans. Underlines take the color of their glyph.
+    pub fn generate_mesh_with_colors<F>(&mut self, text: &mut Text, mut color_of: F) -> TextMesh
+    where
+        F: FnMut(&ClusterInfo) -> [f32; 4],
+    {
+        let _span = trace_span!("textware::generate_mesh_with_colors");
+
+        self.shape(text);
+
+        let mut mesh = TextMesh::with_capacity(text.glyph_count());
+        let result = self.append_glyphs_with(text, [0.0, 0.0], &mut mesh, |glyph, _, color| {
+            let info = ClusterInfo { index: glyph.index, line: glyph.line, run: glyph.run, cluster: glyph.cluster.clone(), color: *color };
+            *color = color_of(&info);
+            true
+        });
+
+        if let Err(err) = result {
+            log::debug!("textware: {}", err);
+        }
+
+        mesh
+    }
+
+    pub fn generate_mesh_animated(&mut self, text: &mut Text, time: f32) -> TextMesh {
+        let _span = trace_span!("textware::generate_mesh_animated");
+        let time = if time.is_finite() { time } else { 0.0 };
+
+        self.shape(text);
+
+        let mut mesh = TextMesh::default();
+        let result = self.append_glyphs_with(text, [0.0, 0.0], &mut mesh, |glyph, offset, color| {
+            let Some(style) = glyph.style else {
+                return true;
+            };
+            let effects = style.effects;
+            let phase = glyph.index as f32;
+
+            if effects.contains(SpanEffects::WAVE) {
+                offset[1] += (time * 6.0 + phase * 0.6).sin() * glyph.font_size * 0.15;
+            }
+
+            if effects.contains(SpanEffects::SHAKE) {
+                let frame = (time * 30.0) as u32;
+                let amount = glyph.font_size * 0.06;
+                offset[0] += (jitter(glyph.index as u32, frame) - 0.5) * 2.0 * amount;
+                offset[1] += (jitter(glyph.index as u32 ^ 0x9e37, frame) - 0.5) * 2.0 * amount;
+            }
+
+            if effects.contains(SpanEffects::RAINBOW) {
+                let hue = (time * 0.5 + phase * 0.05).fract();
+                let [r, g, b] = hue_to_rgb(hue);
+                *color = [r, g, b, color[3]];
+            }
+
+            if effects.contains(SpanEffects::PULSE) {
+                color[3] *= 0.6 + 0.4 * (time * 4.0).sin();
+            }
+
+            true
+        });
+
+        if let Err(err) = result {
+            log::debug!("textware: {}", err);
+        }
+
+        mesh
+    }
+}
+
+fn jitter(index: u32, frame: u32) -> f32 {
+    let mut x = index.wrapping_mul(0x85eb_ca6b) ^ frame.wrapping_mul(0xc2b2_ae35);
+    x ^= x >> 16;
+    x = x.wrapping_mul(0x7feb_352d);
+    x ^= x >> 15;
+    (x & 0xffff) as f32 / 65535.0
+}
+
+fn hue_to_rgb(hue: f32) -> [f32; 3] {
+    let h = hue * 6.0;
+    let x = 1.0 - (h % 2.0 - 1.0).abs();
+    match h as u32 {
+        0 => [1.0, x, 0.0],
+        1 => [x, 1.0, 0.0],
+        2 => [0.0, 1.0, x],
+        3 => [0.0, x, 1.0],
+        4 => [x, 0.0, 1.0],
+        _ => [1.0, 0.0, x],
+    }
+}
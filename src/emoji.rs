@@ -0,0 +1,101 @@
+use std::collections::HashMap;
+use swash::scale::image::Image as SwashImage;
+use swash::zeno::Placement;
+use crate::backend::AtlasBackend;
+use crate::cache::{GlyphKey, UvRect};
+use crate::error::TextError;
+use crate::{Text, TextMesh, TextWare};
+
+// Shelf packed RGBA atlas for emoji and other color glyphs. Only the placement is kept
+// once a bitmap is uploaded, color bitmaps being four times the size of masks.
+pub(crate) struct ColorAtlas {
+    size: u32,
+    padding: u32,
+    next_x: u32,
+    next_y: u32,
+    row_height: u32,
+    glyphs: HashMap<GlyphKey, (Placement, UvRect)>,
+    pending: Vec<(u32, u32, SwashImage)>,
+}
+
+impl ColorAtlas {
+    pub fn new(size: u32, padding: u32) -> Self {
+        Self {
+            size,
+            padding,
+            next_x: padding,
+            next_y: padding,
+            row_height: 0,
+            glyphs: HashMap::new(),
+            pending: Vec::new(),
+        }
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.size > 0
+    }
+
+    pub fn len(&self) -> usize {
+        self.glyphs.len()
+    }
+
+    pub fn get(&self, key: &GlyphKey) -> Option<(Placement, UvRect)> {
+        self.glyphs.get(key).copied()
+    }
+
+    pub fn insert(&mut self, key: GlyphKey, image: SwashImage) -> Result<UvRect, TextError> {
+        if !self.enabled() {
+            return Err(TextError::AtlasFull);
+        }
+
+        let (w, h) = (image.placement.width, image.placement.height);
+        if w + self.padding * 2 > self.size || h + self.padding * 2 > self.size {
+            return Err(TextError::GlyphTooLarge { width: w, height: h });
+        }
+
+        if self.next_x + w + self.padding > self.size {
+            self.next_x = self.padding;
+            self.next_y += self.row_height + self.padding;
+            self.row_height = 0;
+        }
+        if self.next_y + h + self.padding > self.size {
+            trace_event!(width = w, height = h, "color atlas full, glyph dropped");
+            return Err(TextError::AtlasFull);
+        }
+
+        let size = self.size as f32;
+        let rect = (self.next_x as f32 / size, self.next_y as f32 / size, w as f32 / size, h as f32 / size);
+        self.glyphs.insert(key, (image.placement, rect));
+        if w > 0 && h > 0 {
+            self.pending.push((self.next_x, self.next_y, image));
+        }
+
+        self.next_x += w + self.padding;
+        self.row_height = self.row_height.max(h);
+        Ok(rect)
+    }
+
+    pub fn take_pending(&mut self) -> Vec<(u32, u32, SwashImage)> {
+        std::mem::take(&mut self.pending)
+    }
+
+    pub fn pending_bytes(&self) -> usize {
+        self.pending.iter().map(|(_, _, image)| image.data.capacity()).sum()
+    }
+}
+
+impl<B: AtlasBackend> TextWare<B> {
+    // Emoji and other color glyphs of the text, which `generate_mesh` leaves out. Draw it
+    // after the mask mesh with the color atlas bound; vertex colors are white with the text's alpha.
+    pub fn generate_color_mesh(&mut self, text: &mut Text) -> TextMesh {
+        let _span = trace_span!("textware::generate_color_mesh");
+
+        self.shape(text);
+
+        let mut mesh = TextMesh::default();
+        if let Err(err) = self.glyph_cache.append_color_glyphs(&mut self.font_system, text, [0.0, 0.0], &mut mesh) {
+            log::debug!("textware: {}", err);
+        }
+        mesh
+    }
+}
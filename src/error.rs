@@ -1,9 +1,22 @@
 use std::fmt;
+use cosmic_text::CacheKey;
+use crate::font::FontId;
 
 #[derive(Debug)]
 pub enum TextError {
     FontLoading(String),
     Io(std::io::Error),
+    Backend(String),
+    Markup(String),
+    AtlasFull,
+    GlyphTooLarge { width: u32, height: u32 },
+    GlyphRasterization { cache_key: CacheKey },
+    InvalidFont { reason: String },
+    IndexOverflow,
+    FontNotFound(FontId),
+    InvalidMetrics { font_size: f32, line_height: f32 },
+    InvalidSize { width: f32, height: f32 },
+    InvalidArgument(String),
 }
 
 impl fmt::Display for TextError {
@@ -11,6 +24,17 @@ impl fmt::Display for TextError {
         match self {
             TextError::FontLoading(msg) => write!(f, "Font loading error: {}", msg),
             TextError::Io(err) => write!(f, "IO error: {}", err),
+            TextError::Backend(msg) => write!(f, "Backend error: {}", msg),
+            TextError::Markup(msg) => write!(f, "Markup error: {}", msg),
+            TextError::AtlasFull => write!(f, "Glyph atlas is full"),
+            TextError::GlyphTooLarge { width, height } => write!(f, "Glyph of {} x {} can never fit the atlas", width, height),
+            TextError::GlyphRasterization { cache_key } => write!(f, "Failed to rasterize glyph {} of font {:?}", cache_key.glyph_id, cache_key.font_id),
+            TextError::InvalidFont { reason } => write!(f, "Invalid font: {}", reason),
+            TextError::IndexOverflow => write!(f, "Mesh exceeds the u32 index range"),
+            TextError::FontNotFound(id) => write!(f, "Font {:?} is not loaded", id),
+            TextError::InvalidMetrics { font_size, line_height } => write!(f, "Invalid metrics: font size {}, line height {}", font_size, line_height),
+            TextError::InvalidSize { width, height } => write!(f, "Invalid size: {} x {}", width, height),
+            TextError::InvalidArgument(msg) => write!(f, "Invalid argument: {}", msg),
         }
     }
 }
@@ -0,0 +1,53 @@
+use crate::Text;
+
+impl Text {
+    pub fn edge_fade(&self) -> Option<f32> {
+        self.edge_fade
+    }
+
+    // Lines within `distance` pixels of the top or bottom of the text's bounds fade out
+    // towards that edge while there is more content past it, the soft scroll edge of
+    // mobile lists. The bottom edge needs a height from set_size.
+    pub fn set_edge_fade(&mut self, distance: Option<f32>) {
+        self.edge_fade = distance.filter(|distance| distance.is_finite() && *distance > 0.0);
+        self.invalidate_mesh();
+    }
+}
+
+pub(crate) struct EdgeFade {
+    distance: f32,
+    line_height: f32,
+    // Edges with content beyond them, the others stay opaque
+    top: Option<f32>,
+    bottom: Option<f32>,
+}
+
+impl EdgeFade {
+    pub fn new(text: &Text) -> Option<Self> {
+        let distance = text.edge_fade?;
+        let line_height = text.buffer.metrics().line_height;
+        let height = text.buffer.size().1;
+
+        let mut runs = text.buffer.layout_runs();
+        let first = runs.next()?;
+        let (last_line, last_top) = runs.last().map_or((first.line_i, first.line_top), |run| (run.line_i, run.line_top));
+
+        let scrolled = first.line_i > 0 || first.line_top < 0.0;
+        let more_below = last_line + 1 < text.buffer.lines.len() || last_top + line_height > height;
+
+        Some(Self {
+            distance,
+            line_height,
+            top: scrolled.then_some(0.0),
+            bottom: (more_below && height.is_finite()).then_some(height),
+        })
+    }
+
+    // Measured from the middle of the line, so a line cut by the edge is nearly transparent
+    pub fn alpha(&self, line_top: f32) -> f32 {
+        let middle = line_top + self.line_height / 2.0;
+        let top = self.top.map_or(1.0, |edge| ((middle - edge) / self.distance).clamp(0.0, 1.0));
+        let bottom = self.bottom.map_or(1.0, |edge| ((edge - middle) / self.distance).clamp(0.0, 1.0));
+        top * bottom
+    }
+}
@@ -0,0 +1,173 @@
+use cosmic_text::rustybuzz::{self, ttf_parser::Tag, Direction, Feature, UnicodeBuffer};
+use cosmic_text::{LayoutGlyph, LayoutRun};
+use unicode_segmentation::UnicodeSegmentation;
+use crate::backend::AtlasBackend;
+use crate::font::FontSystem;
+use crate::filter::INVISIBLE_METADATA;
+use crate::{Text, TextWare};
+
+// The layout engine takes no OpenType feature list, so these apply when meshing: ligature
+// glyphs are reshaped with the ligature features off, while kerning and tabular figures
+// are emulated from the plain advances. Line breaks keep the shaped widths.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct FontFeatures {
+    pub kerning: bool,
+    pub ligatures: bool,
+    // Every digit gets the advance of the widest one, so live numbers don't jitter
+    pub tabular_figures: bool,
+}
+
+impl Default for FontFeatures {
+    fn default() -> Self {
+        Self { kerning: true, ligatures: true, tabular_figures: false }
+    }
+}
+
+impl FontFeatures {
+    // Folded into glyph cache keys next to cosmic's key, so glyphs shaped under different
+    // feature sets never alias to one atlas entry
+    pub(crate) fn fingerprint(&self) -> u64 {
+        self.kerning as u64 | (self.ligatures as u64) << 1 | (self.tabular_figures as u64) << 2
+    }
+}
+
+impl Text {
+    pub fn font_features(&self) -> FontFeatures {
+        self.features
+    }
+}
+
+impl<B: AtlasBackend> TextWare<B> {
+    // Changes show on the next mesh, the content and its layout stay as they are
+    pub fn set_font_features(&mut self, text: &mut Text, features: FontFeatures) {
+        text.features = features;
+        text.invalidate_mesh();
+    }
+}
+
+// Span features override the text's
+pub(crate) fn glyph_features(text: &Text, glyph: &LayoutGlyph) -> FontFeatures {
+    text.spans.get(glyph.metadata & !INVISIBLE_METADATA).and_then(|style| style.features).unwrap_or(text.features)
+}
+
+pub(crate) fn natural_advance(font_system: &mut FontSystem, glyph: &LayoutGlyph) -> f32 {
+    font_system
+        .sys
+        .get_font(glyph.font_id)
+        .map_or(glyph.w, |font| font.as_swash().glyph_metrics(&[]).scale(glyph.font_size).advance_width(glyph.glyph_id))
+}
+
+// Lead in front of the glyph and the advance it should take
+pub(crate) fn adjusted_advance(font_system: &mut FontSystem, glyph: &LayoutGlyph, cluster: &str, features: FontFeatures) -> (f32, f32) {
+    let advance = if features.kerning { glyph.w } else { natural_advance(font_system, glyph) };
+
+    if features.tabular_figures && cluster.len() == 1 && cluster.as_bytes()[0].is_ascii_digit() {
+        let figure = figure_advance(font_system, glyph);
+        return ((figure - advance) / 2.0, figure);
+    }
+    (0.0, advance)
+}
+
+fn figure_advance(font_system: &mut FontSystem, glyph: &LayoutGlyph) -> f32 {
+    font_system.sys.get_font(glyph.font_id).map_or(glyph.w, |font| {
+        let font = font.as_swash();
+        let metrics = font.glyph_metrics(&[]).scale(glyph.font_size);
+        let charmap = font.charmap();
+        ('0'..='9').map(|digit| metrics.advance_width(charmap.map(digit))).fold(0.0, f32::max)
+    })
+}
+
+// Glyphs covering several graphemes of one cluster, with ligatures off for them, are replaced
+// by the cluster reshaped on its own without ligatures. Later glyphs on the line move by the
+// width this adds. Each glyph comes with the index of the layout glyph it was drawn for.
+pub(crate) fn split_ligatures(font_system: &mut FontSystem, text: &Text, run: &LayoutRun) -> Option<Vec<(usize, LayoutGlyph)>> {
+    if text.features.ligatures && !text.spans.iter().any(|style| style.features.is_some_and(|features| !features.ligatures)) {
+        return None;
+    }
+
+    let mut glyphs = Vec::with_capacity(run.glyphs.len());
+    let mut shift = 0.0;
+    for (index, glyph) in run.glyphs.iter().enumerate() {
+        let shares_cluster = |other: Option<&LayoutGlyph>| other.is_some_and(|other| other.start == glyph.start);
+        let cluster = &run.text[glyph.start..glyph.end];
+        let features = glyph_features(text, glyph);
+        let ligature = !features.ligatures
+            && !shares_cluster(index.checked_sub(1).and_then(|prev| run.glyphs.get(prev)))
+            && !shares_cluster(run.glyphs.get(index + 1))
+            && cluster.graphemes(true).nth(1).is_some()
+            && cluster.graphemes(true).all(breakable);
+
+        match ligature.then(|| reshape(font_system, glyph, cluster, features)).flatten() {
+            Some(pieces) => {
+                let start = glyph.x + shift;
+                for mut piece in pieces {
+                    piece.x += start;
+                    shift += piece.w;
+                    glyphs.push((index, piece));
+                }
+                shift -= glyph.w;
+            }
+            None => {
+                let mut glyph = glyph.clone();
+                glyph.x += shift;
+                glyphs.push((index, glyph));
+            }
+        }
+    }
+    Some(glyphs)
+}
+
+// Pieces are positioned from x 0 and keep the byte ranges of their own characters
+fn reshape(font_system: &mut FontSystem, glyph: &LayoutGlyph, cluster: &str, features: FontFeatures) -> Option<Vec<LayoutGlyph>> {
+    let font = font_system.sys.get_font(glyph.font_id)?;
+    let face = font.rustybuzz();
+
+    let mut buffer = UnicodeBuffer::new();
+    buffer.push_str(cluster);
+    buffer.guess_segment_properties();
+    buffer.set_direction(if glyph.level.is_rtl() { Direction::RightToLeft } else { Direction::LeftToRight });
+    let off = |tag: &[u8; 4]| Feature::new(Tag::from_bytes(tag), 0, ..);
+    let mut toggles = vec![off(b"liga"), off(b"clig")];
+    if !features.kerning {
+        toggles.push(off(b"kern"));
+    }
+    let shaped = rustybuzz::shape(face, &toggles, buffer);
+
+    let units = glyph.font_size / face.units_per_em() as f32;
+    let mut ends: Vec<usize> = shaped.glyph_infos().iter().map(|info| info.cluster as usize).collect();
+    ends.push(cluster.len());
+    ends.sort_unstable();
+    ends.dedup();
+
+    let mut x = 0.0;
+    let pieces = shaped
+        .glyph_infos()
+        .iter()
+        .zip(shaped.glyph_positions())
+        .map(|(info, position)| {
+            let start = info.cluster as usize;
+            let end = ends.iter().copied().find(|&end| end > start).unwrap_or(cluster.len());
+            let mut piece = glyph.clone();
+            piece.glyph_id = info.glyph_id as u16;
+            piece.start = glyph.start + start;
+            piece.end = glyph.start + end;
+            piece.x = x;
+            piece.w = position.x_advance as f32 * units;
+            piece.x_offset = position.x_offset as f32 / face.units_per_em() as f32;
+            piece.y_offset = position.y_offset as f32 / face.units_per_em() as f32;
+            x += piece.w;
+            piece
+        })
+        .collect();
+    Some(pieces)
+}
+
+// Reshaping a cluster on its own would break cursive joining, so those scripts keep their ligatures
+fn breakable(grapheme: &str) -> bool {
+    grapheme.chars().all(|ch| {
+        !ch.is_whitespace()
+            && !matches!(ch, '\u{0600}'..='\u{08FF}' | '\u{1800}'..='\u{18AF}' | '\u{A840}'..='\u{A87F}' | '\u{FB50}'..='\u{FDFF}' | '\u{FE70}'..='\u{FEFF}')
+    })
+}
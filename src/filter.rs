@@ -0,0 +1,201 @@
+use std::borrow::Cow;
+
+const TEXT_SELECTOR: char = '\u{FE0E}';
+const EMOJI_SELECTOR: char = '\u{FE0F}';
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum EmojiPresentation {
+    #[default]
+    Default,
+    Text,
+    Emoji,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct InputFilter {
+    #[cfg(feature = "normalize")]
+    pub normalize: bool,
+    pub strip_bidi_controls: bool,
+    pub strip_zero_width: bool,
+    pub emoji_presentation: EmojiPresentation,
+    // Replace variation selectors already present in the input with the chosen presentation
+    pub override_emoji_selectors: bool,
+    // Combining marks kept per base character, extra ones are dropped. Tames "Zalgo"
+    // text whose mark stacks would otherwise tower far outside the line box.
+    pub max_stacked_marks: Option<usize>,
+}
+
+impl InputFilter {
+    pub fn is_identity(&self) -> bool {
+        *self == Self::default()
+    }
+
+    pub fn apply<'a>(&self, input: &'a str) -> Cow<'a, str> {
+        if self.is_identity() {
+            return Cow::Borrowed(input);
+        }
+
+        let stripped: Cow<str> = if input.chars().any(|ch| self.strips(ch)) {
+            Cow::Owned(input.chars().filter(|ch| !self.strips(*ch)).collect())
+        } else {
+            Cow::Borrowed(input)
+        };
+
+        let stripped = match self.clamp_marks(&stripped) {
+            Some(clamped) => Cow::Owned(clamped),
+            None => stripped,
+        };
+
+        let stripped = match self.apply_emoji_presentation(&stripped) {
+            Some(presented) => Cow::Owned(presented),
+            None => stripped,
+        };
+
+        #[cfg(feature = "normalize")]
+        if self.normalize {
+            use unicode_normalization::{is_nfc_quick, IsNormalized, UnicodeNormalization};
+
+            if is_nfc_quick(stripped.chars()) != IsNormalized::Yes {
+                return Cow::Owned(stripped.nfc().collect());
+            }
+        }
+
+        stripped
+    }
+
+    fn clamp_marks(&self, input: &str) -> Option<String> {
+        let max = self.max_stacked_marks?;
+
+        let mut run = 0;
+        let exceeds = input.chars().any(|ch| {
+            run = if is_stacking_mark(ch) { run + 1 } else { 0 };
+            run > max
+        });
+        if !exceeds {
+            return None;
+        }
+
+        let mut run = 0;
+        Some(
+            input
+                .chars()
+                .filter(|ch| {
+                    run = if is_stacking_mark(*ch) { run + 1 } else { 0 };
+                    run <= max
+                })
+                .collect(),
+        )
+    }
+
+    fn apply_emoji_presentation(&self, input: &str) -> Option<String> {
+        let selector = match self.emoji_presentation {
+            EmojiPresentation::Default => return None,
+            EmojiPresentation::Text => TEXT_SELECTOR,
+            EmojiPresentation::Emoji => EMOJI_SELECTOR,
+        };
+
+        if !input.chars().any(is_ambiguous_emoji) {
+            return None;
+        }
+
+        let mut out = String::with_capacity(input.len() + 8);
+        let mut chars = input.chars().peekable();
+
+        while let Some(ch) = chars.next() {
+            out.push(ch);
+            if !is_ambiguous_emoji(ch) {
+                continue;
+            }
+
+            match chars.peek() {
+                Some(&next) if next == TEXT_SELECTOR || next == EMOJI_SELECTOR => {
+                    if self.override_emoji_selectors {
+                        chars.next();
+                        out.push(selector);
+                    }
+                }
+                _ => out.push(selector),
+            }
+        }
+
+        Some(out)
+    }
+
+    fn strips(&self, ch: char) -> bool {
+        (self.strip_bidi_controls && is_bidi_control(ch)) || (self.strip_zero_width && is_zero_width(ch))
+    }
+}
+
+// Set on the cosmic metadata of invisible characters drawn as a placeholder, the low bits keep the span index
+pub(crate) const INVISIBLE_METADATA: usize = 1 << (usize::BITS - 1);
+
+pub(crate) fn is_invisible(ch: char) -> bool {
+    match ch {
+        '\t' | '\n' | '\r' => false,
+        '\u{0}'..='\u{1F}' | '\u{7F}'..='\u{9F}' | '\u{A0}' => true,
+        _ => is_zero_width(ch) || is_bidi_control(ch),
+    }
+}
+
+// Splits content into runs, each invisible character getting its own single-character run
+pub(crate) fn split_invisibles(content: &str) -> Vec<(&str, bool)> {
+    let mut pieces = Vec::new();
+    let mut start = 0;
+
+    for (i, ch) in content.char_indices() {
+        if is_invisible(ch) {
+            if start < i {
+                pieces.push((&content[start..i], false));
+            }
+            pieces.push((&content[i..i + ch.len_utf8()], true));
+            start = i + ch.len_utf8();
+        }
+    }
+
+    if start < content.len() {
+        pieces.push((&content[start..], false));
+    }
+
+    pieces
+}
+
+pub(crate) fn is_bidi_control(ch: char) -> bool {
+    matches!(ch, '\u{061C}' | '\u{200E}' | '\u{200F}' | '\u{202A}'..='\u{202E}' | '\u{2066}'..='\u{2069}')
+}
+
+// ZWJ and ZWNJ are left alone since emoji sequences and several scripts depend on them
+pub(crate) fn is_zero_width(ch: char) -> bool {
+    matches!(ch, '\u{200B}' | '\u{2060}' | '\u{FEFF}' | '\u{180E}')
+}
+
+// Combining diacritical mark blocks, the ones Zalgo generators pile up
+fn is_stacking_mark(ch: char) -> bool {
+    matches!(ch, '\u{0300}'..='\u{036F}' | '\u{0483}'..='\u{0489}' | '\u{1AB0}'..='\u{1AFF}' | '\u{1DC0}'..='\u{1DFF}' | '\u{20D0}'..='\u{20FF}' | '\u{FE20}'..='\u{FE2F}')
+}
+
+// Emoji=Yes, Emoji_Presentation=No codepoints outside ASCII, which default to text presentation
+fn is_ambiguous_emoji(ch: char) -> bool {
+    matches!(ch as u32,
+        0x00A9 | 0x00AE | 0x203C | 0x2049 | 0x2122 | 0x2139 | 0x2194..=0x2199 | 0x21A9..=0x21AA
+        | 0x2328 | 0x23CF | 0x23ED..=0x23EF | 0x23F1..=0x23F2 | 0x23F8..=0x23FA | 0x24C2
+        | 0x25AA..=0x25AB | 0x25B6 | 0x25C0 | 0x25FB..=0x25FC | 0x2600..=0x2604 | 0x260E | 0x2611
+        | 0x2618 | 0x261D | 0x2620 | 0x2622..=0x2623 | 0x2626 | 0x262A | 0x262E..=0x262F
+        | 0x2638..=0x263A | 0x2640 | 0x2642 | 0x265F..=0x2660 | 0x2663 | 0x2665..=0x2666 | 0x2668
+        | 0x267B | 0x267E | 0x2692 | 0x2694..=0x2697 | 0x2699 | 0x269B..=0x269C | 0x26A0 | 0x26A7
+        | 0x26B0..=0x26B1 | 0x26C8 | 0x26CF | 0x26D1 | 0x26D3 | 0x26E9 | 0x26F0..=0x26F1 | 0x26F4
+        | 0x26F7..=0x26F9 | 0x2702 | 0x2708..=0x2709 | 0x270C..=0x270D | 0x270F | 0x2712 | 0x2714
+        | 0x2716 | 0x271D | 0x2721 | 0x2733..=0x2734 | 0x2744 | 0x2747 | 0x2763..=0x2764 | 0x27A1
+        | 0x2934..=0x2935 | 0x2B05..=0x2B07 | 0x3030 | 0x303D | 0x3297 | 0x3299
+        | 0x1F170..=0x1F171 | 0x1F17E..=0x1F17F | 0x1F202 | 0x1F237 | 0x1F321 | 0x1F324..=0x1F32C
+        | 0x1F336 | 0x1F37D | 0x1F396..=0x1F397 | 0x1F399..=0x1F39B | 0x1F39E..=0x1F39F
+        | 0x1F3CB..=0x1F3CE | 0x1F3D4..=0x1F3DF | 0x1F3F3 | 0x1F3F5 | 0x1F3F7 | 0x1F43F | 0x1F441
+        | 0x1F4FD | 0x1F549..=0x1F54A | 0x1F56F..=0x1F570 | 0x1F573..=0x1F579 | 0x1F587
+        | 0x1F58A..=0x1F58D | 0x1F590 | 0x1F5A5 | 0x1F5A8 | 0x1F5B1..=0x1F5B2 | 0x1F5BC
+        | 0x1F5C2..=0x1F5C4 | 0x1F5D1..=0x1F5D3 | 0x1F5DC..=0x1F5DE | 0x1F5E1 | 0x1F5E3 | 0x1F5E8
+        | 0x1F5EF | 0x1F5F3 | 0x1F5FA | 0x1F6CB | 0x1F6CD..=0x1F6CF | 0x1F6E0..=0x1F6E5 | 0x1F6E9
+        | 0x1F6F0 | 0x1F6F3
+    )
+}
@@ -0,0 +1,124 @@
+use crate::backend::AtlasBackend;
+use crate::error::TextError;
+use crate::{text_content, Text, TextMesh, TextWare};
+
+// Widest horizontal gap left free by the exclusions overlapping [top, bottom)
+fn free_span(width: f32, top: f32, bottom: f32, exclusions: &[[f32; 4]]) -> (f32, f32) {
+    let mut blocked: Vec<(f32, f32)> = exclusions
+        .iter()
+        .filter(|[_, y, _, h]| *y < bottom && y + h > top)
+        .map(|[x, _, w, _]| (*x, x + w))
+        .collect();
+    blocked.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    let mut best = (0.0, 0.0);
+    let mut start: f32 = 0.0;
+    for (left, right) in blocked {
+        let end = left.min(width);
+        if end - start > best.1 - best.0 {
+            best = (start, end);
+        }
+        start = start.max(right);
+    }
+    if width - start > best.1 - best.0 {
+        best = (start, width);
+    }
+    best
+}
+
+impl Text {
+    pub fn exclusions(&self) -> &[[f32; 4]] {
+        &self.exclusions
+    }
+}
+
+impl<B: AtlasBackend> TextWare<B> {
+    // Rectangles as [x, y, width, height] relative to the text origin, e.g. a
+    // floated image. Lines beside them are narrowed to the widest free gap.
+    pub fn set_exclusions(&mut self, text: &mut Text, exclusions: &[[f32; 4]]) -> Result<(), TextError> {
+        if let Some(rect) = exclusions.iter().find(|rect| rect.iter().any(|v| !v.is_finite()) || rect[2] < 0.0 || rect[3] < 0.0) {
+            return Err(TextError::InvalidArgument(format!("invalid exclusion rect {:?}", rect)));
+        }
+
+        text.exclusions = exclusions.to_vec();
+        text.dirty = true;
+        Ok(())
+    }
+
+    // Flowed lines are reshaped from the plain content, so span styles are not kept
+    pub(crate) fn generate_flow_mesh(&mut self, text: &mut Text, offset: [f32; 2]) -> Result<TextMesh, TextError> {
+        let content = text_content(text);
+
+        let mut mesh = TextMesh::with_capacity(content.len());
+        self.append_flow(text, &content, &text.exclusions, offset, &mut mesh)?;
+
+        Ok(mesh)
+    }
+
+    // Cosmic wraps a buffer at a single width, so lines next to an exclusion are
+    // laid out one at a time in the free gap. Once every exclusion is behind us
+    // the rest of the paragraph wraps normally. Returns the height used.
+    pub(crate) fn append_flow(
+        &mut self,
+        text: &Text,
+        content: &str,
+        exclusions: &[[f32; 4]],
+        offset: [f32; 2],
+        mesh: &mut TextMesh,
+    ) -> Result<f32, TextError> {
+        let _span = trace_span!("textware::append_flow", exclusions = exclusions.len());
+
+        let metrics = text.buffer.metrics();
+        let line_height = metrics.line_height;
+        let width = text.buffer.size().0;
+        let clear_below = exclusions.iter().map(|[_, y, _, h]| y + h).fold(0.0, f32::max);
+
+        let mut line = self.font_system.create_text("", text.font_id, metrics.font_size, Some(line_height))?;
+        line.attrs = text.attrs.clone();
+        line.color = text.color;
+        line.align = text.align;
+        self.font_system.set_wrap(&mut line, text.buffer.wrap());
+
+        let mut y = 0.0;
+        for paragraph in content.split('\n') {
+            let mut rest = paragraph;
+            loop {
+                if y >= clear_below {
+                    self.font_system.set_size(&mut line, Some(width), None)?;
+                    self.font_system.update_text(&mut line, rest);
+                    self.shape(&mut line);
+
+                    let lines = line.buffer.layout_runs().count().max(1);
+                    if let Err(err) = self.append_glyphs_with(&line, [offset[0], offset[1] + y], mesh, |_, _, _| true) {
+                        log::debug!("textware: {}", err);
+                    }
+                    y += lines as f32 * line_height;
+                    break;
+                }
+
+                let (left, right) = free_span(width, y, y + line_height, exclusions);
+                if right - left < metrics.font_size {
+                    y += line_height;
+                    continue;
+                }
+
+                self.font_system.set_size(&mut line, Some(right - left), None)?;
+                self.font_system.update_text(&mut line, rest);
+                self.shape(&mut line);
+
+                let next = line.buffer.layout_runs().nth(1).and_then(|run| run.glyphs.first()).map(|glyph| glyph.start);
+                if let Err(err) = self.append_glyphs_with(&line, [offset[0] + left, offset[1] + y], mesh, |glyph, _, _| glyph.run == 0) {
+                    log::debug!("textware: {}", err);
+                }
+                y += line_height;
+
+                match next {
+                    Some(start) if start > 0 && start < rest.len() => rest = &rest[start..],
+                    _ => break,
+                }
+            }
+        }
+
+        Ok(y)
+    }
+}
@@ -1,19 +1,44 @@
 use std::collections::HashMap;
 use std::path::Path;
+use std::sync::Arc;
 use crate::error::TextError;
+use crate::features::FontFeatures;
+use crate::space::CoordinateSpace;
+use crate::id::TextId;
+use crate::limit::BufferLimit;
+use crate::shaping::ShapingMode;
+use crate::hover::PointerState;
+use crate::filter::{self, InputFilter, INVISIBLE_METADATA};
+use cosmic_text::AttrsOwned;
+
+use crate::{Attrs, Family, Metrics, Text, Wrap};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FontId(pub u64);
 
+// Loaded fonts plus shaping and layout. Usable on its own: create, update and shape texts
+// here, then mesh them with any GlyphCache, or hand both to TextWare::from_parts
 pub struct FontSystem {
     pub(crate) sys: cosmic_text::FontSystem,
     next_id: u64,
-    families: HashMap<FontId, String>,
+    families: HashMap<FontId, Arc<str>>,
+    faces: HashMap<cosmic_text::fontdb::ID, FontId>,
+    input_filter: InputFilter,
+    show_invisibles: bool,
+    min_font_size: Option<f32>,
 
     #[cfg(target_os = "android")]
     asset_manager: ndk::asset::AssetManager,
 }
 
+#[cfg(not(target_os = "android"))]
+impl Default for FontSystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl FontSystem {
     #[cfg(not(target_os = "android"))]
     pub fn new() -> Self {
@@ -21,6 +46,10 @@ impl FontSystem {
             sys: cosmic_text::FontSystem::new(),
             next_id: 1,
             families: HashMap::new(),
+            faces: HashMap::new(),
+            input_filter: InputFilter::default(),
+            show_invisibles: false,
+            min_font_size: None,
         }
     }
 
@@ -30,6 +59,10 @@ impl FontSystem {
             sys: cosmic_text::FontSystem::new(),
             next_id: 1,
             families: HashMap::new(),
+            faces: HashMap::new(),
+            input_filter: InputFilter::default(),
+            show_invisibles: false,
+            min_font_size: None,
             asset_manager,
         }
     }
@@ -50,34 +83,236 @@ impl FontSystem {
             }
         }?;
 
-        self.sys.db_mut().load_font_data(font_data);
-
-        let family_name = Path::new(path)
+        let name = Path::new(path)
             .file_stem()
             .and_then(|s| s.to_str())
             .unwrap_or("unknown")
             .to_string();
 
+        self.register_font(font_data, name)
+    }
+
+    pub fn load_font_from_bytes(&mut self, data: &[u8], name: &str) -> Result<FontId, TextError> {
+        self.register_font(data.to_vec(), name.to_string())
+    }
+
+    fn register_font(&mut self, data: Vec<u8>, name: String) -> Result<FontId, TextError> {
+        let faces = self.sys.db_mut().load_font_source(cosmic_text::fontdb::Source::Binary(Arc::new(data)));
+
+        if faces.is_empty() {
+            return Err(TextError::InvalidFont { reason: format!("no font faces found in '{}'", name) });
+        }
+
         let id = FontId(self.next_id);
         self.next_id += 1;
 
-        self.families.insert(id, family_name);
+        // Fonts loaded under one name share a single allocation
+        let family = self.families
+            .values()
+            .find(|family| ***family == *name)
+            .cloned()
+            .unwrap_or_else(|| Arc::from(name));
+
+        self.families.insert(id, family);
+        for face_id in faces {
+            self.faces.entry(face_id).or_insert(id);
+        }
 
         Ok(id)
     }
 
-    pub fn load_font_from_bytes(&mut self, data: &[u8], name: &str) -> Result<FontId, TextError> {
-        self.sys.db_mut().load_font_data(data.to_vec());
-        
-        let id = FontId(self.next_id);
-        self.next_id += 1;
-        
-        self.families.insert(id, name.to_string());
-        
-        Ok(id)
+    pub fn input_filter(&self) -> InputFilter {
+        self.input_filter
     }
 
-    pub fn get_family_name(&self, id: FontId) -> Option<&String> {
+    pub fn set_input_filter(&mut self, filter: InputFilter) {
+        self.input_filter = filter;
+    }
+
+    pub fn show_invisibles(&self) -> bool {
+        self.show_invisibles
+    }
+
+    pub fn set_show_invisibles(&mut self, show_invisibles: bool) {
+        self.show_invisibles = show_invisibles;
+    }
+
+    pub fn min_font_size(&self) -> Option<f32> {
+        self.min_font_size
+    }
+
+    // Accessibility floor: texts created or resized below it are laid out at the minimum
+    // instead, with the line height scaled along. Texts already laid out keep their size.
+    pub fn set_min_font_size(&mut self, min_font_size: Option<f32>) -> Result<(), TextError> {
+        if let Some(size) = min_font_size {
+            validate_metrics(size, None)?;
+        }
+        self.min_font_size = min_font_size;
+        Ok(())
+    }
+
+    // Metrics after the minimum size, plus the requested size when it was raised
+    pub(crate) fn readable_metrics(&self, font_size: f32, line_height: Option<f32>) -> Result<(Metrics, Option<f32>), TextError> {
+        let metrics = validate_metrics(font_size, line_height)?;
+
+        match self.min_font_size {
+            Some(min) if metrics.font_size < min => {
+                trace_event!(font_size, min, "font size clamped");
+                let scale = min / metrics.font_size;
+                Ok((Metrics::new(min, metrics.line_height * scale), Some(metrics.font_size)))
+            }
+            _ => Ok((metrics, None)),
+        }
+    }
+
+    pub fn get_family_name(&self, id: FontId) -> Option<&Arc<str>> {
         self.families.get(&id)
     }
-}
\ No newline at end of file
+
+    pub fn family_name(&self, id: FontId) -> Result<&str, TextError> {
+        self.families
+            .get(&id)
+            .map(|name| name.as_ref())
+            .ok_or(TextError::FontNotFound(id))
+    }
+
+    // The font a shaped glyph's face was loaded as, if it came from load_font_*
+    pub(crate) fn font_for_face(&self, face: cosmic_text::fontdb::ID) -> Option<FontId> {
+        self.faces.get(&face).copied()
+    }
+
+    pub fn find_font(&self, family_name: &str) -> Option<FontId> {
+        self.families
+            .iter()
+            .filter(|(_, name)| name.eq_ignore_ascii_case(family_name))
+            .map(|(id, _)| *id)
+            .min_by_key(|id| id.0)
+    }
+
+    pub fn create_text(&mut self, content: &str, font_id: Option<FontId>, font_size: f32, line_height: Option<f32>) -> Result<Text, TextError> {
+        let _span = trace_span!("textware::create_text", bytes = content.len(), font_size);
+        let attrs = self.resolve_attrs(font_id);
+        self.create_text_with(content, font_id, attrs, font_size, line_height)
+    }
+
+    pub(crate) fn create_text_with(&mut self, content: &str, font_id: Option<FontId>, attrs: AttrsOwned, font_size: f32, line_height: Option<f32>) -> Result<Text, TextError> {
+        let mut text = self.empty_text(font_id, attrs, font_size, line_height)?;
+        self.update_text(&mut text, content);
+        Ok(text)
+    }
+
+    // The one place a Text is put together, plain and rich texts fill it afterwards
+    pub(crate) fn empty_text(&mut self, font_id: Option<FontId>, attrs: AttrsOwned, font_size: f32, line_height: Option<f32>) -> Result<Text, TextError> {
+        let (metrics, clamped_from) = self.readable_metrics(font_size, line_height)?;
+        let buffer = cosmic_text::Buffer::new(&mut self.sys, metrics);
+
+        Ok(Text {
+            buffer,
+            color: [1.0, 1.0, 1.0, 1.0],
+            font_id,
+            attrs,
+            spans: Vec::new(),
+            exclusions: Vec::new(),
+            features: FontFeatures::default(),
+            clamped_from,
+            embolden: 0.0,
+            hollow: None,
+            style_name: None,
+            style_generation: 0,
+            space: CoordinateSpace::default(),
+            id: TextId::next(),
+            user_data: None,
+            align: None,
+            limit: BufferLimit::default(),
+            dropped_height: 0.0,
+            pointer: PointerState::default(),
+            shaping: ShapingMode::default(),
+            clip: false,
+            edge_fade: None,
+            scale_factor: 1.0,
+            distance_field: None,
+            cached_mesh: None,
+            dirty: true,
+        })
+    }
+
+    pub fn update_text(&mut self, text: &mut Text, content: &str) {
+        let _span = trace_span!("textware::update_text", bytes = content.len());
+
+        let content = self.input_filter.apply(content);
+        self.fill_buffer(&mut text.buffer, &content, text.attrs.as_attrs(), text.shaping);
+        text.spans.clear();
+        text.dirty = true;
+    }
+
+    // Sets prepared content on a buffer, with invisibles flagged for placeholders when shown
+    pub(crate) fn fill_buffer(&mut self, buffer: &mut cosmic_text::Buffer, content: &str, attrs: Attrs, shaping: ShapingMode) {
+        if self.show_invisibles && content.chars().any(filter::is_invisible) {
+            let pieces = filter::split_invisibles(content)
+                .into_iter()
+                .map(|(piece, invisible)| (piece, if invisible { attrs.metadata(INVISIBLE_METADATA) } else { attrs }));
+            buffer.set_rich_text(&mut self.sys, pieces, attrs, shaping.into());
+        } else {
+            buffer.set_text(&mut self.sys, content, attrs, shaping.into());
+        }
+    }
+
+    pub(crate) fn resolve_attrs(&self, font_id: Option<FontId>) -> AttrsOwned {
+        let mut attrs = Attrs::new();
+        if let Some(name) = font_id.and_then(|id| self.families.get(&id)) {
+            attrs = attrs.family(Family::Name(name));
+        }
+        AttrsOwned::new(attrs)
+    }
+
+    pub fn resize_text(&mut self, text: &mut Text, font_size: f32, line_height: Option<f32>) -> Result<(), TextError> {
+        let (metrics, clamped_from) = self.readable_metrics(font_size, line_height)?;
+        text.buffer.set_metrics(&mut self.sys, metrics);
+        text.clamped_from = clamped_from;
+        text.dirty = true;
+        Ok(())
+    }
+
+    pub fn set_size(&mut self, text: &mut Text, width: Option<f32>, height: Option<f32>) -> Result<(), TextError> {
+        let w = width.unwrap_or(f32::MAX);
+        let h = height.unwrap_or(f32::MAX);
+        if w.is_nan() || h.is_nan() || w < 0.0 || h < 0.0 {
+            return Err(TextError::InvalidSize { width: w, height: h });
+        }
+
+        text.buffer.set_size(&mut self.sys, w.min(f32::MAX), h.min(f32::MAX));
+        text.dirty = true;
+        Ok(())
+    }
+
+    pub fn set_wrap(&mut self, text: &mut Text, wrap: Wrap) {
+        text.buffer.set_wrap(&mut self.sys, wrap);
+        text.dirty = true;
+    }
+
+    pub fn shape(&mut self, text: &mut Text) {
+        if !text.dirty && !text.buffer.redraw() {
+            return;
+        }
+
+        let _span = trace_span!("textware::shape");
+        text.buffer.shape_until_scroll(&mut self.sys, false);
+        text.buffer.set_redraw(false);
+        text.dirty = false;
+        text.cached_mesh = None;
+    }
+}
+
+// Larger sizes would make swash allocate glyph bitmaps far beyond any atlas
+pub(crate) const MAX_FONT_SIZE: f32 = 4096.0;
+
+pub(crate) fn validate_metrics(font_size: f32, line_height: Option<f32>) -> Result<Metrics, TextError> {
+    let line_height = line_height.unwrap_or(font_size * 1.2);
+    let valid = |v: f32| v.is_finite() && v > 0.0 && v <= MAX_FONT_SIZE * 4.0;
+
+    if !valid(font_size) || font_size > MAX_FONT_SIZE || !valid(line_height) {
+        return Err(TextError::InvalidMetrics { font_size, line_height });
+    }
+
+    Ok(Metrics::new(font_size, line_height))
+}
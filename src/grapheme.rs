@@ -0,0 +1,96 @@
+use unicode_segmentation::GraphemeCursor;
+
+use crate::filter;
+use crate::{Cursor, Text};
+
+impl Text {
+    // True where a caret may sit: a grapheme boundary that also doesn't split a shaped cluster such as a ligature
+    pub fn is_cluster_boundary(&self, cursor: Cursor) -> bool {
+        let Some(line) = self.buffer.lines.get(cursor.line) else {
+            return false;
+        };
+
+        let content = line.text();
+        if cursor.index == 0 || cursor.index == content.len() {
+            return true;
+        }
+        if !content.is_char_boundary(cursor.index) {
+            return false;
+        }
+
+        let mut graphemes = GraphemeCursor::new(cursor.index, content.len(), true);
+        if !graphemes.is_boundary(content, 0).unwrap_or(false) {
+            return false;
+        }
+
+        !self.buffer
+            .layout_runs()
+            .filter(|run| run.line_i == cursor.line)
+            .flat_map(|run| run.glyphs.iter())
+            .any(|glyph| glyph.start < cursor.index && cursor.index < glyph.end)
+    }
+
+    // Bidi controls are invisible and zero width, so the caret steps over them together with the next grapheme
+    pub fn next_grapheme(&self, cursor: Cursor) -> Cursor {
+        let Some(line) = self.buffer.lines.get(cursor.line) else {
+            return cursor;
+        };
+
+        let content = line.text();
+        if cursor.index >= content.len() {
+            return if cursor.line + 1 < self.buffer.lines.len() {
+                Cursor::new(cursor.line + 1, 0)
+            } else {
+                Cursor::new(cursor.line, content.len())
+            };
+        }
+
+        let mut index = char_floor(content, cursor.index);
+        let mut visible = false;
+        let mut graphemes = GraphemeCursor::new(index, content.len(), true);
+        while let Ok(Some(next)) = graphemes.next_boundary(content, 0) {
+            visible |= !content[index..next].chars().all(filter::is_bidi_control);
+            index = next;
+            if visible && self.is_cluster_boundary(Cursor::new(cursor.line, index)) {
+                break;
+            }
+        }
+
+        Cursor::new(cursor.line, index)
+    }
+
+    pub fn prev_grapheme(&self, cursor: Cursor) -> Cursor {
+        let Some(line) = self.buffer.lines.get(cursor.line) else {
+            return cursor;
+        };
+
+        if cursor.index == 0 {
+            return match cursor.line.checked_sub(1) {
+                Some(prev) => Cursor::new(prev, self.buffer.lines[prev].text().len()),
+                None => cursor,
+            };
+        }
+
+        let content = line.text();
+        let mut index = char_floor(content, cursor.index);
+        let mut visible = false;
+        let mut graphemes = GraphemeCursor::new(index, content.len(), true);
+        while let Ok(Some(prev)) = graphemes.prev_boundary(content, 0) {
+            visible |= !content[prev..index].chars().all(filter::is_bidi_control);
+            index = prev;
+            if visible && self.is_cluster_boundary(Cursor::new(cursor.line, index)) {
+                break;
+            }
+        }
+
+        Cursor::new(cursor.line, index)
+    }
+}
+
+fn char_floor(content: &str, index: usize) -> usize {
+    let mut index = index.min(content.len());
+    while !content.is_char_boundary(index) {
+        index -= 1;
+    }
+    index
+}
@@ -0,0 +1,109 @@
+use cosmic_text::LayoutGlyph;
+use crate::backend::AtlasBackend;
+use crate::id::TextId;
+use crate::{layout_bounds, Cursor, Text, TextWare};
+
+// Which text a point landed on and where, the id routing it back to the app's model
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TextHit {
+    pub id: TextId,
+    pub cursor: Cursor,
+}
+
+// Caret x at byte `index` of the glyph's cluster, ligatures split evenly by bytes
+fn caret_x(glyph: &LayoutGlyph, index: usize) -> f32 {
+    let fraction = (index - glyph.start) as f32 / (glyph.end - glyph.start).max(1) as f32;
+    if glyph.level.is_rtl() {
+        glyph.x + glyph.w * (1.0 - fraction)
+    } else {
+        glyph.x + glyph.w * fraction
+    }
+}
+
+impl<B: AtlasBackend> TextWare<B> {
+    // Cursor closest to a point in the text's layout space, None when the text is empty
+    pub fn hit_test(&mut self, text: &mut Text, x: f32, y: f32) -> Option<TextHit> {
+        self.shape(text);
+        text.buffer.hit(x, y).map(|cursor| TextHit { id: text.id, cursor })
+    }
+
+    // Topmost of several texts, each at its origin, whose laid out glyphs contain the point.
+    // Later texts are drawn over earlier ones, as in generate_batch.
+    pub fn hit_test_texts(&mut self, texts: &mut [(&mut Text, [f32; 2])], x: f32, y: f32) -> Option<TextHit> {
+        for (text, [origin_x, origin_y]) in texts.iter_mut().rev() {
+            self.shape(text);
+            let (x, y) = (x - *origin_x, y - *origin_y);
+            let inside = layout_bounds(text).is_some_and(|[min_x, min_y, max_x, max_y]| (min_x..=max_x).contains(&x) && (min_y..=max_y).contains(&y));
+            if inside {
+                return self.hit_test(text, x, y);
+            }
+        }
+        None
+    }
+
+    // Caret x, line top and line height for a cursor, None if its line isn't laid out
+    pub fn cursor_position(&mut self, text: &mut Text, cursor: Cursor) -> Option<(f32, f32, f32)> {
+        self.shape(text);
+
+        let line_height = text.buffer.metrics().line_height;
+        let runs = || text.buffer.layout_runs().filter(|run| run.line_i == cursor.line);
+
+        // A cursor on a wrap point belongs to the start of the next layout line, so clusters
+        // containing it win over ones ending at it
+        let inside = runs().find_map(|run| {
+            run.glyphs
+                .iter()
+                .find(|glyph| (glyph.start..glyph.end).contains(&cursor.index))
+                .map(|glyph| (caret_x(glyph, cursor.index), run.line_top))
+        });
+        let after = || {
+            runs().find_map(|run| {
+                run.glyphs
+                    .iter()
+                    .find(|glyph| glyph.end == cursor.index)
+                    .map(|glyph| (caret_x(glyph, glyph.end), run.line_top))
+            })
+        };
+        // Empty lines still get a run to put the caret on
+        let empty = || runs().find(|run| run.glyphs.is_empty()).map(|run| (0.0, run.line_top));
+
+        inside.or_else(after).or_else(empty).map(|(x, top)| (x, top, line_height))
+    }
+
+    // Highlight rects [x, y, w, h] covering the selection between two cursors in either order,
+    // one per contiguous visual stretch so mixed direction lines get several
+    pub fn selection_rects(&mut self, text: &mut Text, start: Cursor, end: Cursor) -> Vec<[f32; 4]> {
+        self.shape(text);
+
+        let (start, end) = if (end.line, end.index) < (start.line, start.index) { (end, start) } else { (start, end) };
+        let line_height = text.buffer.metrics().line_height;
+        let mut rects: Vec<[f32; 4]> = Vec::new();
+
+        for run in text.buffer.layout_runs().filter(|run| (start.line..=end.line).contains(&run.line_i)) {
+            let from = if run.line_i == start.line { start.index } else { 0 };
+            let to = if run.line_i == end.line { end.index } else { usize::MAX };
+
+            let mut segments: Vec<(f32, f32)> = run.glyphs
+                .iter()
+                .filter_map(|glyph| {
+                    let (lo, hi) = (from.max(glyph.start), to.min(glyph.end));
+                    (lo < hi).then(|| {
+                        let (a, b) = (caret_x(glyph, lo), caret_x(glyph, hi));
+                        (a.min(b), a.max(b))
+                    })
+                })
+                .collect();
+            segments.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+            let first = rects.len();
+            for (x0, x1) in segments {
+                match rects[first..].last_mut() {
+                    Some([x, _, w, _]) if x0 <= *x + *w + 0.5 => *w = w.max(x1 - *x),
+                    _ => rects.push([x0, run.line_top, x1 - x0, line_height]),
+                }
+            }
+        }
+
+        rects
+    }
+}
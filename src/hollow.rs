@@ -0,0 +1,22 @@
+use crate::backend::AtlasBackend;
+use crate::error::TextError;
+use crate::{Text, TextWare};
+
+impl Text {
+    pub fn hollow(&self) -> Option<f32> {
+        self.hollow
+    }
+}
+
+impl<B: AtlasBackend> TextWare<B> {
+    // Draws only the stroked outline of the glyphs, `width` being a fraction of the font
+    // size like set_embolden. Takes precedence over emboldening; None fills them again.
+    pub fn set_hollow(&mut self, text: &mut Text, width: Option<f32>) -> Result<(), TextError> {
+        if let Some(width) = width.filter(|width| !width.is_finite() || *width <= 0.0 || *width > 0.25) {
+            return Err(TextError::InvalidArgument(format!("hollow stroke width {} is out of range", width)));
+        }
+        text.hollow = width;
+        text.invalidate_mesh();
+        Ok(())
+    }
+}
@@ -0,0 +1,82 @@
+use crate::backend::AtlasBackend;
+use crate::filter::INVISIBLE_METADATA;
+use crate::{Text, TextWare};
+
+// Replaces parts of an interactive span's style while the pointer is over it
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct InteractiveStyle {
+    pub color: Option<[f32; 4]>,
+    pub underline: bool,
+}
+
+// Marks a span as interactive. Active applies on top of hover while the pointer is down.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct SpanInteraction {
+    pub hover: InteractiveStyle,
+    pub active: InteractiveStyle,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) struct PointerState {
+    hovered: Option<usize>,
+    pressed: bool,
+}
+
+impl Text {
+    // Index into spans() of the interactive span under the pointer
+    pub fn hovered_span(&self) -> Option<usize> {
+        self.pointer.hovered
+    }
+
+    pub fn set_pointer_down(&mut self, pressed: bool) {
+        if self.pointer.pressed != pressed {
+            self.pointer.pressed = pressed;
+            self.dirty |= self.pointer.hovered.is_some();
+        }
+    }
+}
+
+impl<B: AtlasBackend> TextWare<B> {
+    // Point in the text's layout space, None once the pointer leaves. The next mesh
+    // draws the hovered span's variant; the text is marked dirty when that changes.
+    pub fn hover(&mut self, text: &mut Text, point: Option<[f32; 2]>) -> Option<usize> {
+        self.shape(text);
+
+        let line_height = text.buffer.metrics().line_height;
+        let hovered = point.and_then(|[x, y]| {
+            text.buffer
+                .layout_runs()
+                .filter(|run| (run.line_top..run.line_top + line_height).contains(&y))
+                .flat_map(|run| run.glyphs.iter())
+                .find(|glyph| (glyph.x..glyph.x + glyph.w).contains(&x))
+                .map(|glyph| glyph.metadata & !INVISIBLE_METADATA)
+                .filter(|span| text.spans.get(*span).is_some_and(|style| style.interaction.is_some()))
+        });
+
+        if text.pointer.hovered != hovered {
+            text.pointer.hovered = hovered;
+            text.dirty = true;
+        }
+        hovered
+    }
+}
+
+// Variant to draw a glyph of `span` with, if the pointer is on it
+pub(crate) fn span_variant(text: &Text, span: usize) -> Option<InteractiveStyle> {
+    if text.pointer.hovered != Some(span) {
+        return None;
+    }
+
+    let interaction = text.spans.get(span)?.interaction?;
+    if !text.pointer.pressed {
+        return Some(interaction.hover);
+    }
+    Some(InteractiveStyle {
+        color: interaction.active.color.or(interaction.hover.color),
+        underline: interaction.active.underline || interaction.hover.underline,
+    })
+}
@@ -0,0 +1,35 @@
+use std::collections::HashMap;
+use crate::backend::AtlasBackend;
+use crate::error::TextError;
+use crate::font::FontId;
+use crate::{Text, TextWare};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Icon {
+    pub font_id: FontId,
+    pub codepoint: char,
+}
+
+pub(crate) type IconMap = HashMap<String, Icon>;
+
+impl<B: AtlasBackend> TextWare<B> {
+    // Names icon font glyphs so callers never embed private use codepoints in strings
+    pub fn register_icon(&mut self, name: impl Into<String>, font_id: FontId, codepoint: char) -> Result<(), TextError> {
+        self.font_system.family_name(font_id)?;
+        self.icons.insert(name.into(), Icon { font_id, codepoint });
+        Ok(())
+    }
+
+    pub fn icon(&self, name: &str) -> Option<Icon> {
+        self.icons.get(name).copied()
+    }
+
+    // One glyph text with a square line box, ready to place next to labels
+    pub fn create_icon(&mut self, name: &str, size: f32) -> Result<Text, TextError> {
+        let icon = self.icon(name).ok_or_else(|| TextError::InvalidArgument(format!("no icon named {:?}", name)))?;
+
+        let mut buffer = [0; 4];
+        self.create_text(icon.codepoint.encode_utf8(&mut buffer), Some(icon.font_id), size, Some(size))
+    }
+}
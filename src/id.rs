@@ -0,0 +1,42 @@
+use std::any::Any;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::Text;
+
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+
+// Unique for the life of the process, never reused after a Text is dropped
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TextId(pub u64);
+
+impl TextId {
+    pub(crate) fn next() -> Self {
+        TextId(NEXT_ID.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
+pub(crate) type UserData = Option<Box<dyn Any + Send + Sync>>;
+
+impl Text {
+    pub fn id(&self) -> TextId {
+        self.id
+    }
+
+    // Lets pointer events find their way back to the app's own model object
+    pub fn set_user_data<T: Any + Send + Sync>(&mut self, data: T) {
+        self.user_data = Some(Box::new(data));
+    }
+
+    pub fn user_data<T: Any>(&self) -> Option<&T> {
+        self.user_data.as_ref()?.downcast_ref()
+    }
+
+    pub fn user_data_mut<T: Any>(&mut self) -> Option<&mut T> {
+        self.user_data.as_mut()?.downcast_mut()
+    }
+
+    pub fn take_user_data(&mut self) -> Option<Box<dyn Any + Send + Sync>> {
+        self.user_data.take()
+    }
+}
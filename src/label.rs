@@ -0,0 +1,98 @@
+use std::collections::HashMap;
+use cosmic_text::LayoutGlyph;
+use crate::backend::AtlasBackend;
+use crate::cache::GlyphKey;
+use crate::error::TextError;
+use crate::style::TextStyle;
+use crate::{FontId, TextMesh, TextWare};
+
+const FIRST: u8 = b' ';
+const LAST: u8 = b'~';
+
+// Printable ASCII glyphs shaped one by one, laid out by plain advances
+struct AsciiTable {
+    glyphs: Vec<Option<LayoutGlyph>>,
+    line_y: f32,
+}
+
+#[derive(Default)]
+pub(crate) struct LabelCache {
+    tables: HashMap<(Option<FontId>, u32, u32), AsciiTable>,
+}
+
+impl LabelCache {
+    pub(crate) fn bytes(&self) -> usize {
+        self.tables.values().map(|table| table.glyphs.capacity() * std::mem::size_of::<Option<LayoutGlyph>>()).sum()
+    }
+}
+
+impl<B: AtlasBackend> TextWare<B> {
+    // Fast path for single-line printable ASCII, skipping bidi, line breaking and kerning.
+    // Anything else falls back to the regular shaper.
+    pub fn generate_label_mesh(&mut self, content: &str, style: &TextStyle, position: [f32; 2]) -> Result<TextMesh, TextError> {
+        let _span = trace_span!("textware::generate_label_mesh", bytes = content.len());
+
+        if !content.bytes().all(|b| (FIRST..=LAST).contains(&b)) {
+            let mut text = self.create_text_styled(content, style)?;
+            self.shape(&mut text);
+
+            let mut mesh = TextMesh::default();
+            self.glyph_cache.append_glyphs_with(&mut self.font_system, &text, position, &mut mesh, |_, _, _| true)?;
+            return Ok(mesh);
+        }
+
+        let (metrics, _) = self.font_system.readable_metrics(style.font_size, style.line_height)?;
+        let key = (style.font_id, metrics.font_size.to_bits(), metrics.line_height.to_bits());
+        if !self.labels.tables.contains_key(&key) {
+            let table = self.build_ascii_table(style.font_id, metrics);
+            self.labels.tables.insert(key, table);
+        }
+
+        let table = &self.labels.tables[&key];
+        let mut mesh = TextMesh::default();
+        let mut pen = 0.0;
+
+        for b in content.bytes() {
+            let Some(glyph) = &table.glyphs[(b - FIRST) as usize] else {
+                continue;
+            };
+
+            let physical = glyph.physical((pen, 0.0), 1.0);
+            pen += glyph.w;
+
+            if let Some((placement, uv_rect)) = self.glyph_cache.glyph_placement(GlyphKey::plain(physical.cache_key), &mut self.font_system)? {
+                if !mesh.has_capacity(1) {
+                    return Err(TextError::IndexOverflow);
+                }
+
+                let x = physical.x as f32 + placement.left as f32 + position[0];
+                let y = table.line_y + physical.y as f32 - placement.top as f32 + position[1];
+                mesh.push_quad([x, y, placement.width as f32, placement.height as f32], uv_rect, style.color);
+            }
+        }
+
+        Ok(mesh)
+    }
+
+    fn build_ascii_table(&mut self, font_id: Option<FontId>, metrics: cosmic_text::Metrics) -> AsciiTable {
+        let attrs = self.font_system.resolve_attrs(font_id);
+        let mut buffer = cosmic_text::Buffer::new(&mut self.font_system.sys, metrics);
+        let mut glyphs = Vec::with_capacity((LAST - FIRST + 1) as usize);
+        let mut line_y = metrics.font_size;
+
+        for b in FIRST..=LAST {
+            let mut content = [0u8; 4];
+            let ch = (b as char).encode_utf8(&mut content);
+            buffer.set_text(&mut self.font_system.sys, ch, attrs.as_attrs(), cosmic_text::Shaping::Advanced);
+            buffer.shape_until_scroll(&mut self.font_system.sys, false);
+
+            let run = buffer.layout_runs().next();
+            if let Some(run) = run.as_ref() {
+                line_y = run.line_y;
+            }
+            glyphs.push(run.and_then(|run| run.glyphs.first().cloned()));
+        }
+
+        AsciiTable { glyphs, line_y }
+    }
+}
@@ -1,53 +1,414 @@
+#[macro_use]
+mod trace;
+
 mod error;
 mod font;
+mod filter;
+mod grapheme;
 mod cache;
+mod backend;
+mod style;
+mod subtitle;
+mod terminal;
+mod chart;
+mod rich;
+mod color;
+mod markup;
+mod effects;
+mod bbcode;
+mod shared;
+mod arena;
+mod label;
+mod memory;
+mod outline;
+mod columns;
+mod flow;
+mod dropcap;
+mod list;
+mod tabstops;
+mod clamp;
+mod advance;
+mod features;
+mod zoom;
+mod theme;
+mod space;
+mod reveal;
+mod icon;
+mod pill;
+mod shadow;
+mod bake;
+mod quadmap;
+mod id;
+mod align;
+mod diagnostics;
+mod batch;
+mod diff;
+mod tail;
+mod limit;
+mod hover;
+mod autolink;
+mod breaks;
+mod shaping;
+mod overflow;
+mod visit;
+mod emoji;
+mod hollow;
+mod fade;
+mod anchor;
+mod styled;
+mod bucket;
+mod measure;
+mod hit;
+mod nested;
+mod types;
+mod editable;
+mod sticky;
+mod runs;
+mod contrast;
+mod sdf;
+mod cached;
+#[cfg(feature = "markdown")]
+mod markdown;
+#[cfg(feature = "testing")]
+pub mod testing;
+#[cfg(feature = "renderer")]
+mod profiler;
+#[cfg(feature = "renderer")]
+mod viewport;
+#[cfg(feature = "renderer")]
+mod renderer;
 
 pub use error::TextError;
 pub use font::{FontSystem, FontId};
-pub use cache::GlyphCache;
-pub use cosmic_text::{Attrs, Color as CosmicColor, Metrics, Weight, Family, Wrap, Align};
+pub use filter::{EmojiPresentation, InputFilter};
+pub use cache::{GlyphCache, CacheConfig, UvRect};
+pub use style::TextStyle;
+pub use subtitle::SubtitleStyle;
+pub use terminal::{TerminalGrid, Cell, CellFlags};
+pub use rich::{Span, SpanDirection, SpanStyle};
+pub use color::{parse_color, ToRgba};
+pub use markup::parse_markup;
+pub use effects::{ClusterInfo, SpanEffects};
+pub use bbcode::parse_bbcode;
+pub use shared::SharedTextWare;
+pub use arena::FrameArena;
+pub use memory::MemoryReport;
+pub use outline::{GlyphOutline, GlyphPath};
+pub use columns::{ColumnFlow, ColumnLayout};
+pub use dropcap::DropCap;
+pub use list::{ListItem, ListLayout, ListMarker, ListMesh};
+pub use clamp::ClampedMesh;
+pub use advance::ClusterAdvance;
+pub use features::FontFeatures;
+pub use zoom::{ZoomAction, ZoomController, ZoomPolicy};
+pub use theme::StyleRegistry;
+pub use space::CoordinateSpace;
+pub use reveal::RevealProgress;
+pub use icon::Icon;
+pub use pill::PillStyle;
+pub use shadow::SoftShadow;
+pub use bake::{AtlasSlice, BakedMesh};
+pub use quadmap::{GlyphQuads, QuadMap};
+pub use id::TextId;
+pub use align::TextAlign;
+pub use diagnostics::FaceRun;
+pub use tail::TailFollow;
+pub use limit::BufferLimit;
+pub use hover::{InteractiveStyle, SpanInteraction};
+pub use autolink::{DetectedLink, LinkDetector, LinkKind};
+pub use breaks::{line_breaks, LineBreak};
+pub use shaping::ShapingMode;
+pub use overflow::Overflow;
+pub use visit::GlyphQuad;
+pub use anchor::{Anchor, Insets, ScreenRect};
+pub use bucket::SizeBuckets;
+pub use measure::{LineMetrics, TextMetricsResult};
+pub use nested::SpanNode;
+pub use types::{FontStretch, FontStyle, GlyphFlags};
+pub use editable::{CursorMotion, EditableText};
+pub use hit::TextHit;
+pub use sticky::StickyHeader;
+pub use runs::{GlyphRun, ShapedGlyph};
+pub use contrast::{contrast_ratio, contrasting_color, ContrastStyle};
+pub use sdf::DistanceField;
+#[cfg(feature = "markdown")]
+pub use markdown::{parse_markdown, MarkdownDocument, MarkdownStyle};
+pub use chart::{AxisLabel, AxisLabelOptions, AxisLayout, AxisOrientation, PlacedLabel};
+#[cfg(feature = "renderer")]
+pub use profiler::{GpuProfiler, TextPassTimings};
+#[cfg(feature = "renderer")]
+pub use viewport::{EyeProjections, Viewport, ViewportBatch};
+#[cfg(feature = "renderer")]
+pub use renderer::{MeshKind, TextDraw, TextRenderer};
+pub use backend::{AtlasBackend, WgpuBackend};
+#[cfg(feature = "glow")]
+pub use backend::GlowBackend;
+pub use cosmic_text::{Attrs, Color as CosmicColor, Cursor, Metrics, Weight, Family, Wrap, Align};
 
 use bytemuck::{Pod, Zeroable};
 
 #[repr(C)]
 #[derive(Copy, Clone, Debug, Pod, Zeroable)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TextVertex {
     pub position: [f32; 3],
     pub uv: [f32; 2],
     pub color: [f32; 4],
 }
 
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TextMesh {
     pub vertices: Vec<TextVertex>,
-    pub indices: Vec<u16>,
+    pub indices: Vec<u32>,
+}
+
+impl TextMesh {
+    pub fn with_capacity(quads: usize) -> Self {
+        Self {
+            vertices: Vec::with_capacity(quads * 4),
+            indices: Vec::with_capacity(quads * 6),
+        }
+    }
+
+    pub fn has_capacity(&self, quads: usize) -> bool {
+        self.vertices.len() + quads * 4 <= u32::MAX as usize
+    }
+
+    // Z of every vertex, e.g. to layer texts batched into one buffer
+    pub fn set_depth(&mut self, z: f32) {
+        for vertex in &mut self.vertices {
+            vertex.position[2] = z;
+        }
+    }
+
+    pub fn append(&mut self, other: &TextMesh) -> Result<(), TextError> {
+        if self.vertices.len() + other.vertices.len() > u32::MAX as usize {
+            return Err(TextError::IndexOverflow);
+        }
+
+        let base = self.vertices.len() as u32;
+        self.vertices.extend_from_slice(&other.vertices);
+        self.indices.extend(other.indices.iter().map(|i| i + base));
+        Ok(())
+    }
+
+    pub(crate) fn push_quad(&mut self, rect: [f32; 4], uv_rect: UvRect, color: [f32; 4]) {
+        let [x, y, w, h] = rect;
+        let (u, v, uw, vh) = uv_rect;
+        let z = 0.0;
+        let index = self.vertices.len() as u32;
+
+        self.vertices.push(TextVertex { position: [x, y, z], uv: [u, v], color });
+        self.vertices.push(TextVertex { position: [x, y + h, z], uv: [u, v + vh], color });
+        self.vertices.push(TextVertex { position: [x + w, y + h, z], uv: [u + uw, v + vh], color });
+        self.vertices.push(TextVertex { position: [x + w, y, z], uv: [u + uw, v], color });
+
+        self.indices.extend_from_slice(&[
+            index, index + 1, index + 2,
+            index, index + 2, index + 3,
+        ]);
+    }
+
+    pub(crate) fn push_frame(&mut self, rect: [f32; 4], stroke: f32, uv_rect: UvRect, color: [f32; 4]) {
+        let [x, y, w, h] = rect;
+        self.push_quad([x, y, w, stroke], uv_rect, color);
+        self.push_quad([x, y + h - stroke, w, stroke], uv_rect, color);
+        self.push_quad([x, y + stroke, stroke, h - stroke * 2.0], uv_rect, color);
+        self.push_quad([x + w - stroke, y + stroke, stroke, h - stroke * 2.0], uv_rect, color);
+    }
+
+    pub(crate) fn hatch_quads(rect: [f32; 4], stroke: f32) -> usize {
+        4 + ((rect[2] - stroke * 2.0) / (stroke * 3.0)).max(0.0) as usize
+    }
+
+    // Frame with vertical stripes every three strokes
+    pub(crate) fn push_hatch(&mut self, rect: [f32; 4], stroke: f32, uv_rect: UvRect, color: [f32; 4]) {
+        let [x, y, _, h] = rect;
+        self.push_frame(rect, stroke, uv_rect, color);
+
+        for i in 1..=TextMesh::hatch_quads(rect, stroke) - 4 {
+            let stripe_x = x + stroke * 3.0 * i as f32 - stroke;
+            self.push_quad([stripe_x, y + stroke, stroke, h - stroke * 2.0], uv_rect, color);
+        }
+    }
 }
 
-pub struct TextWare {
+pub struct TextWare<B: AtlasBackend = WgpuBackend> {
     font_system: FontSystem,
-    glyph_cache: GlyphCache,
+    glyph_cache: GlyphCache<B>,
+    labels: label::LabelCache,
+    theme: theme::Theme,
+    icons: icon::IconMap,
 }
 
 pub struct Text {
     pub buffer: cosmic_text::Buffer,
     pub color: [f32; 4],
-    font_id: Option<FontId>, 
+    font_id: Option<FontId>,
+    attrs: cosmic_text::AttrsOwned,
+    spans: Vec<SpanStyle>,
+    exclusions: Vec<[f32; 4]>,
+    features: FontFeatures,
+    clamped_from: Option<f32>,
+    embolden: f32,
+    hollow: Option<f32>,
+    style_name: Option<std::sync::Arc<str>>,
+    style_generation: u64,
+    space: CoordinateSpace,
+    id: TextId,
+    user_data: id::UserData,
+    align: Option<TextAlign>,
+    limit: BufferLimit,
+    dropped_height: f32,
+    pointer: hover::PointerState,
+    shaping: ShapingMode,
+    clip: bool,
+    edge_fade: Option<f32>,
+    // Device pixels per layout pixel that glyphs are rasterized at
+    scale_factor: f32,
+    distance_field: Option<sdf::DistanceField>,
+    cached_mesh: Option<cached::CachedMesh>,
+    dirty: bool,
+}
+
+impl Text {
+    pub fn attrs(&self) -> Attrs<'_> {
+        self.attrs.as_attrs()
+    }
+
+    pub fn spans(&self) -> &[SpanStyle] {
+        &self.spans
+    }
+
+    // Needed after editing buffer lines directly, e.g. BufferLine::set_align, which cosmic doesn't flag for redraw
+    pub fn mark_dirty(&mut self) {
+        self.dirty = true;
+    }
+
+    pub fn is_dirty(&self) -> bool {
+        self.dirty || self.buffer.redraw()
+    }
+
+    // Size the text asked for when the minimum font size raised it
+    pub fn clamped_from(&self) -> Option<f32> {
+        self.clamped_from
+    }
+
+    pub fn embolden(&self) -> f32 {
+        self.embolden
+    }
+
+    pub fn glyph_count(&self) -> usize {
+        self.buffer.layout_runs().map(|run| run.glyphs.len()).sum()
+    }
+
+    // Characters no loaded font covers, as of the last shaping pass
+    pub fn missing_chars(&self) -> Vec<char> {
+        let mut missing: Vec<char> = self.buffer
+            .layout_runs()
+            .flat_map(|run| {
+                run.glyphs
+                    .iter()
+                    .filter(|glyph| glyph.glyph_id == 0)
+                    .flat_map(move |glyph| run.text[glyph.start..glyph.end].chars())
+            })
+            .collect();
+
+        missing.sort_unstable();
+        missing.dedup();
+        missing
+    }
 }
 
 impl TextWare {
     #[cfg(not(target_os = "android"))]
     pub fn new(device: &wgpu::Device, queue: &wgpu::Queue) -> Self {
-        Self {
-            font_system: FontSystem::new(),
-            glyph_cache: GlyphCache::new(device, queue),
-        }
+        Self::from_parts(FontSystem::new(), GlyphCache::new(device, queue))
     }
 
     #[cfg(target_os = "android")]
     pub fn new(device: &wgpu::Device, queue: &wgpu::Queue, asset_manager: ndk::asset::AssetManager) -> Self {
+        Self::from_parts(FontSystem::new(asset_manager), GlyphCache::new(device, queue))
+    }
+
+    #[cfg(not(target_os = "android"))]
+    pub fn with_config(device: &wgpu::Device, queue: &wgpu::Queue, config: CacheConfig) -> Self {
+        Self::from_parts(FontSystem::new(), GlyphCache::with_config(device, queue, config))
+    }
+
+    #[cfg(target_os = "android")]
+    pub fn with_config(device: &wgpu::Device, queue: &wgpu::Queue, config: CacheConfig, asset_manager: ndk::asset::AssetManager) -> Self {
+        Self::from_parts(FontSystem::new(asset_manager), GlyphCache::with_config(device, queue, config))
+    }
+
+    pub fn get_bind_group(&self) -> &wgpu::BindGroup {
+        self.glyph_cache.get_bind_group()
+    }
+
+    // None when CacheConfig::color_atlas_size is zero
+    pub fn get_color_bind_group(&self) -> Option<&wgpu::BindGroup> {
+        self.glyph_cache.get_color_bind_group()
+    }
+}
+
+impl<B: AtlasBackend> TextWare<B> {
+    #[cfg(not(target_os = "android"))]
+    pub fn with_backend(backend: B) -> Self {
+        Self::from_parts(FontSystem::new(), GlyphCache::with_backend_config(backend, CacheConfig::default()))
+    }
+
+    #[cfg(target_os = "android")]
+    pub fn with_backend(backend: B, asset_manager: ndk::asset::AssetManager) -> Self {
+        Self::from_parts(FontSystem::new(asset_manager), GlyphCache::with_backend_config(backend, CacheConfig::default()))
+    }
+
+    #[cfg(not(target_os = "android"))]
+    pub fn with_backend_config(backend: B, config: CacheConfig) -> Self {
+        Self::from_parts(FontSystem::new(), GlyphCache::with_backend_config(backend, config))
+    }
+
+    #[cfg(target_os = "android")]
+    pub fn with_backend_config(backend: B, config: CacheConfig, asset_manager: ndk::asset::AssetManager) -> Self {
+        Self::from_parts(FontSystem::new(asset_manager), GlyphCache::with_backend_config(backend, config))
+    }
+
+    // Joins a FontSystem and GlyphCache that were set up on their own
+    pub fn from_parts(font_system: FontSystem, glyph_cache: GlyphCache<B>) -> Self {
         Self {
-            font_system: FontSystem::new(asset_manager),
-            glyph_cache: GlyphCache::new(device, queue),
+            font_system,
+            glyph_cache,
+            labels: label::LabelCache::default(),
+            theme: theme::Theme::default(),
+            icons: icon::IconMap::new(),
+        }
+    }
+
+    pub fn backend(&self) -> &B {
+        self.glyph_cache.backend()
+    }
+
+    // See GlyphCache::trim
+    pub fn trim_glyph_cache(&mut self, max_age: u64) -> usize {
+        self.glyph_cache.trim(max_age)
+    }
+
+    // Changes when the atlas grows or is trimmed; meshes kept from an older generation sample the wrong texels
+    pub fn atlas_generation(&self) -> u32 {
+        self.glyph_cache.atlas_generation()
+    }
+
+    pub fn solid_region(&self) -> UvRect {
+        self.glyph_cache.solid_region()
+    }
+
+    // Untextured rectangle batched with the text, e.g. a caret or a selection
+    pub fn push_solid_rect(&self, mesh: &mut TextMesh, rect: [f32; 4], color: [f32; 4]) -> Result<(), TextError> {
+        if !mesh.has_capacity(1) {
+            return Err(TextError::IndexOverflow);
         }
+        mesh.push_quad(rect, self.glyph_cache.solid_uv(), color);
+        Ok(())
     }
 
     pub fn load_font_bytes(&mut self, data: &[u8], name: &str) -> Result<FontId, TextError> {
@@ -58,110 +419,204 @@ impl TextWare {
         self.font_system.load_font(path)
     }
 
-    pub fn create_text(&mut self, content: &str, font_id: Option<FontId>, font_size: f32, line_height: Option<f32>) -> Text {
-        let metrics = Metrics::new(font_size, line_height.unwrap_or(font_size * 1.2));
-        let mut buffer = cosmic_text::Buffer::new(&mut self.font_system.sys, metrics);
-        
-        let mut attrs = Attrs::new();
-        
-        let family_name = if let Some(id) = font_id {
-            self.font_system.get_family_name(id).cloned()
-        } else {
-            None
-        };
-
-        if let Some(name) = family_name.as_ref() {
-            attrs = attrs.family(Family::Name(name.as_str()));
-        }
+    pub fn create_text(&mut self, content: &str, font_id: Option<FontId>, font_size: f32, line_height: Option<f32>) -> Result<Text, TextError> {
+        self.font_system.create_text(content, font_id, font_size, line_height)
+    }
 
-        buffer.set_text(&mut self.font_system.sys, content, attrs, cosmic_text::Shaping::Advanced);
-        
-        Text {
-            buffer,
-            color: [1.0, 1.0, 1.0, 1.0],
-            font_id,
-        }
+    pub fn create_text_styled(&mut self, content: &str, style: &TextStyle) -> Result<Text, TextError> {
+        let mut text = self.create_text(content, style.font_id, style.font_size, style.line_height)?;
+        text.color = style.color;
+        Ok(text)
     }
 
     pub fn update_text(&mut self, text: &mut Text, content: &str) {
-        let mut attrs = Attrs::new();
-        
-        let family_name = if let Some(id) = text.font_id {
-            self.font_system.get_family_name(id).cloned()
-        } else {
-            None
-        };
-
-        if let Some(name) = family_name.as_ref() {
-            attrs = attrs.family(Family::Name(name.as_str()));
+        self.font_system.update_text(text, content);
+    }
+
+    pub fn set_font(&mut self, text: &mut Text, font_id: Option<FontId>) -> Result<(), TextError> {
+        if let Some(id) = font_id {
+            self.font_system.family_name(id)?;
         }
 
-        text.buffer.set_text(&mut self.font_system.sys, content, attrs, cosmic_text::Shaping::Advanced);
+        text.font_id = font_id;
+        text.attrs = self.font_system.resolve_attrs(font_id);
+
+        // Swaps the family in place, spans that picked their own font keep it
+        let family = text.attrs.as_attrs().family;
+        for line in text.buffer.lines.iter_mut() {
+            let old = line.attrs_list();
+            let mut attrs_list = cosmic_text::AttrsList::new(old.defaults().family(family));
+            for (range, attrs) in old.spans() {
+                let attrs = attrs.as_attrs();
+                let own_font = text
+                    .spans
+                    .get(attrs.metadata & !filter::INVISIBLE_METADATA)
+                    .is_some_and(|style| style.font_id.is_some() || style.monospace);
+                attrs_list.add_span(range.clone(), if own_font { attrs } else { attrs.family(family) });
+            }
+            line.set_attrs_list(attrs_list);
+        }
+        text.dirty = true;
+        Ok(())
     }
 
-    pub fn resize_text(&mut self, text: &mut Text, font_size: f32, line_height: Option<f32>) {
-        let metrics = Metrics::new(font_size, line_height.unwrap_or(font_size * 1.2));
-        text.buffer.set_metrics(&mut self.font_system.sys, metrics);
+    pub fn resize_text(&mut self, text: &mut Text, font_size: f32, line_height: Option<f32>) -> Result<(), TextError> {
+        self.font_system.resize_text(text, font_size, line_height)
     }
 
-    pub fn set_size(&mut self, text: &mut Text, width: Option<f32>, height: Option<f32>) {
-        let w = width.unwrap_or(f32::MAX);
-        let h = height.unwrap_or(f32::MAX);
-        text.buffer.set_size(&mut self.font_system.sys, w, h);
+    pub fn set_size(&mut self, text: &mut Text, width: Option<f32>, height: Option<f32>) -> Result<(), TextError> {
+        self.font_system.set_size(text, width, height)
     }
 
     pub fn set_wrap(&mut self, text: &mut Text, wrap: Wrap) {
-        text.buffer.set_wrap(&mut self.font_system.sys, wrap);
+        self.font_system.set_wrap(text, wrap);
     }
 
-    pub fn prepare(&mut self, queue: &wgpu::Queue) {
-        self.glyph_cache.upload_pending(queue);
+    // Faux bold stroke applied when rasterizing, as a fraction of the font size. Small
+    // steps such as 0.01 tune contrast between the weights a font ships, negative values thin.
+    pub fn set_embolden(&mut self, text: &mut Text, embolden: f32) -> Result<(), TextError> {
+        if !embolden.is_finite() || embolden.abs() > 0.5 {
+            return Err(TextError::InvalidArgument(format!("embolden {} is out of range", embolden)));
+        }
+        text.embolden = embolden;
+        text.invalidate_mesh();
+        Ok(())
     }
 
-    pub fn get_bind_group(&self) -> &wgpu::BindGroup {
-        self.glyph_cache.get_bind_group()
+    pub fn set_input_filter(&mut self, filter: InputFilter) {
+        self.font_system.set_input_filter(filter);
+    }
+
+    pub fn set_show_invisibles(&mut self, show_invisibles: bool) {
+        self.font_system.set_show_invisibles(show_invisibles);
+    }
+
+    pub fn set_min_font_size(&mut self, min_font_size: Option<f32>) -> Result<(), TextError> {
+        self.font_system.set_min_font_size(min_font_size)
+    }
+
+    pub fn set_show_missing(&mut self, show_missing: bool) {
+        self.glyph_cache.set_show_missing(show_missing);
+    }
+
+    pub fn split(&mut self) -> (&mut FontSystem, &mut GlyphCache<B>) {
+        (&mut self.font_system, &mut self.glyph_cache)
+    }
+
+    pub fn into_parts(self) -> (FontSystem, GlyphCache<B>) {
+        (self.font_system, self.glyph_cache)
+    }
+
+    // Returns whether anything was written, so callers can skip a queue submit when nothing changed
+    pub fn prepare(&mut self, uploader: &B::Uploader) -> bool {
+        self.glyph_cache.upload_pending(uploader)
     }
 
     pub fn generate_mesh(&mut self, text: &mut Text) -> TextMesh {
-        text.buffer.shape_until_scroll(&mut self.font_system.sys, false);
-
-        let mut vertices = Vec::new();
-        let mut indices = Vec::new();
-        let mut index_count = 0;
-
-        for run in text.buffer.layout_runs() {
-            for glyph in run.glyphs.iter() {
-                let physical = glyph.physical((0., 0.), 1.0);
-                
-                let key = cache::get_cache_key(&physical);
-
-                if let Some((image, uv_rect)) = self.glyph_cache.get_glyph(key, &mut self.font_system) {
-                    let left = image.placement.left as f32;
-                    let top = image.placement.top as f32;
-                    let w = image.placement.width as f32;
-                    let h = image.placement.height as f32;
-
-                    let x = physical.x as f32 + left;
-                    let y = run.line_y + physical.y as f32 - top;
-
-                    let (u, v, uw, vh) = uv_rect;
-                    let c = text.color;
-                    let z = 0.0;
-
-                    vertices.push(TextVertex { position: [x, y, z], uv: [u, v], color: c });
-                    vertices.push(TextVertex { position: [x, y + h, z], uv: [u, v + vh], color: c });
-                    vertices.push(TextVertex { position: [x + w, y + h, z], uv: [u + uw, v + vh], color: c });
-                    vertices.push(TextVertex { position: [x + w, y, z], uv: [u + uw, v], color: c });
-
-                    indices.extend_from_slice(&[
-                        index_count, index_count + 1, index_count + 2,
-                        index_count, index_count + 2, index_count + 3,
-                    ]);
-                    index_count += 4;
-                }
-            }
+        self.generate_mesh_at(text, 0.0, 0.0)
+    }
+
+    // Mesh with the text's origin at (x, y) in layout pixels, so many texts can share one
+    // vertex buffer. Screen space snapping happens after the offset.
+    pub fn generate_mesh_at(&mut self, text: &mut Text, x: f32, y: f32) -> TextMesh {
+        let _span = trace_span!("textware::generate_mesh");
+
+        if !text.exclusions.is_empty() {
+            return self.generate_flow_mesh(text, [x, y]).unwrap_or_else(|err| {
+                log::debug!("textware: {}", err);
+                TextMesh::default()
+            });
+        }
+
+        self.shape(text);
+
+        let mut mesh = TextMesh::with_capacity(text.glyph_count());
+        self.append_glyphs(text, [x, y], None, &mut mesh);
+
+        trace_event!(glyphs = mesh.vertices.len() / 4, indices = mesh.indices.len(), "mesh generated");
+
+        mesh
+    }
+
+    pub fn try_generate_mesh(&mut self, text: &mut Text) -> Result<TextMesh, TextError> {
+        let _span = trace_span!("textware::try_generate_mesh");
+
+        if !text.exclusions.is_empty() {
+            return self.generate_flow_mesh(text, [0.0, 0.0]);
         }
 
-        TextMesh { vertices, indices }
+        self.shape(text);
+
+        let mut mesh = TextMesh::default();
+        self.append_glyphs_with(text, [0.0, 0.0], &mut mesh, |_, _, _| true)?;
+
+        Ok(mesh)
     }
-}
\ No newline at end of file
+
+    fn shape(&mut self, text: &mut Text) {
+        self.refresh_style(text);
+        self.font_system.shape(text);
+        if align::resolve_alignment(text) {
+            text.mark_dirty();
+            self.font_system.shape(text);
+        }
+    }
+
+    fn append_glyphs(&mut self, text: &Text, offset: [f32; 2], color_override: Option<[f32; 4]>, mesh: &mut TextMesh) {
+        self.glyph_cache.append_glyphs(&mut self.font_system, text, offset, color_override, mesh);
+    }
+
+    fn append_glyphs_with<F>(&mut self, text: &Text, offset: [f32; 2], mesh: &mut TextMesh, adjust: F) -> Result<(), TextError>
+    where
+        F: FnMut(&GlyphContext, &mut [f32; 2], &mut [f32; 4]) -> bool,
+    {
+        self.glyph_cache.append_glyphs_with(&mut self.font_system, text, offset, mesh, adjust)
+    }
+}
+
+pub(crate) struct GlyphContext<'a> {
+    pub index: usize,
+    pub line: usize,
+    pub run: usize,
+    pub cluster: std::ops::Range<usize>,
+    // Quads already in the mesh, i.e. where this glyph's quads will start
+    pub quad: usize,
+    pub font_size: f32,
+    pub style: Option<&'a SpanStyle>,
+}
+
+fn text_content(text: &Text) -> String {
+    text.buffer
+        .lines
+        .iter()
+        .map(|line| line.text())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn layout_bounds(text: &Text) -> Option<[f32; 4]> {
+    let line_height = text.buffer.metrics().line_height;
+    let mut bounds: Option<[f32; 4]> = None;
+
+    for run in text.buffer.layout_runs() {
+        let (mut min_x, mut max_x) = (f32::MAX, f32::MIN);
+        for glyph in run.glyphs.iter() {
+            min_x = min_x.min(glyph.x);
+            max_x = max_x.max(glyph.x + glyph.w);
+        }
+        if min_x > max_x {
+            min_x = 0.0;
+            max_x = 0.0;
+        }
+
+        let top = run.line_top;
+        let bottom = run.line_top + line_height;
+
+        bounds = Some(match bounds {
+            Some([x0, y0, x1, y1]) => [x0.min(min_x), y0.min(top), x1.max(max_x), y1.max(bottom)],
+            None => [min_x, top, max_x, bottom],
+        });
+    }
+
+    bounds
+}
@@ -0,0 +1,108 @@
+use crate::Text;
+
+// Cap for console style texts: append_text drops whole lines from the top
+// once either limit is passed. The newest line is always kept.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct BufferLimit {
+    pub max_lines: Option<usize>,
+    // Counts line breaks as one byte each
+    pub max_bytes: Option<usize>,
+}
+
+impl Text {
+    pub fn buffer_limit(&self) -> BufferLimit {
+        self.limit
+    }
+
+    // Applied on the next append
+    pub fn set_buffer_limit(&mut self, limit: BufferLimit) {
+        self.limit = limit;
+    }
+
+    // Laid out height dropped from the top since the last call, for keeping a
+    // scrolled back view on the same lines
+    pub fn take_dropped_height(&mut self) -> f32 {
+        std::mem::take(&mut self.dropped_height)
+    }
+}
+
+// Returns the number of lines dropped
+pub(crate) fn enforce_limit(text: &mut Text) -> usize {
+    let lines = &text.buffer.lines;
+    let mut drop = text.limit.max_lines.map_or(0, |max| lines.len().saturating_sub(max.max(1)));
+
+    if let Some(max) = text.limit.max_bytes {
+        let mut bytes = lines[drop..].iter().map(|line| line.text().len() + 1).sum::<usize>().saturating_sub(1);
+        while drop + 1 < lines.len() && bytes > max {
+            bytes -= lines[drop].text().len() + 1;
+            drop += 1;
+        }
+    }
+
+    if drop == 0 {
+        return 0;
+    }
+
+    let layout_lines: usize = lines[..drop].iter().map(|line| line.layout_opt().as_ref().map_or(0, |layout| layout.len())).sum();
+    text.dropped_height += layout_lines as f32 * text.buffer.metrics().line_height;
+    text.buffer.lines.drain(..drop);
+    text.dirty = true;
+
+    trace_event!(lines = drop, "buffer limit dropped lines");
+    drop
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::font::FontSystem;
+
+    fn limited(content: &str, limit: BufferLimit) -> (Text, usize) {
+        let mut fonts = FontSystem::new();
+        let mut text = fonts.create_text(content, None, 16.0, None).unwrap();
+        text.set_buffer_limit(limit);
+        let dropped = enforce_limit(&mut text);
+        (text, dropped)
+    }
+
+    fn lines(text: &Text) -> Vec<&str> {
+        text.buffer.lines.iter().map(|line| line.text()).collect()
+    }
+
+    #[test]
+    fn oldest_lines_are_dropped() {
+        let (text, dropped) = limited("a\nb\nc\nd", BufferLimit { max_lines: Some(2), max_bytes: None });
+        assert_eq!(dropped, 2);
+        assert_eq!(lines(&text), ["c", "d"]);
+
+        // "bb\ncc" is five bytes
+        let (text, dropped) = limited("aa\nbb\ncc", BufferLimit { max_lines: None, max_bytes: Some(5) });
+        assert_eq!(dropped, 1);
+        assert_eq!(lines(&text), ["bb", "cc"]);
+
+        let (_, dropped) = limited("a\nb", BufferLimit::default());
+        assert_eq!(dropped, 0);
+    }
+
+    #[test]
+    fn newest_line_is_always_kept() {
+        let (text, _) = limited("a\nb", BufferLimit { max_lines: Some(0), max_bytes: None });
+        assert_eq!(lines(&text), ["b"]);
+        let (text, _) = limited("a\nlong line", BufferLimit { max_lines: None, max_bytes: Some(3) });
+        assert_eq!(lines(&text), ["long line"]);
+    }
+
+    #[test]
+    fn dropped_layout_height_is_reported_once() {
+        let mut fonts = FontSystem::new();
+        let mut text = fonts.create_text("a\nb\nc", None, 16.0, Some(20.0)).unwrap();
+        text.buffer.shape_until_scroll(&mut fonts.sys, false);
+        text.set_buffer_limit(BufferLimit { max_lines: Some(1), max_bytes: None });
+
+        assert_eq!(enforce_limit(&mut text), 2);
+        assert_eq!(text.take_dropped_height(), 40.0);
+        assert_eq!(text.take_dropped_height(), 0.0);
+    }
+}
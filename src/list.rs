@@ -0,0 +1,146 @@
+use crate::backend::AtlasBackend;
+use crate::error::TextError;
+use crate::style::TextStyle;
+use crate::{TextMesh, TextWare};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ListMarker {
+    Bullet,
+    Decimal,
+    LowerAlpha,
+    UpperAlpha,
+    LowerRoman,
+    None,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ListItem {
+    pub content: String,
+    // Nesting depth, 0 for top level items
+    pub level: usize,
+    pub marker: ListMarker,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct ListLayout {
+    pub width: f32,
+    // Gutter width added per nesting level, markers are right aligned inside it
+    pub indent: f32,
+    // Space between a marker and its item body
+    pub gap: f32,
+    pub item_spacing: f32,
+    // Bullet used at each level, cycling for deeper ones
+    pub bullets: [char; 3],
+}
+
+impl Default for ListLayout {
+    fn default() -> Self {
+        Self {
+            width: 400.0,
+            indent: 24.0,
+            gap: 6.0,
+            item_spacing: 0.0,
+            bullets: ['•', '◦', '▪'],
+        }
+    }
+}
+
+pub struct ListMesh {
+    pub mesh: TextMesh,
+    pub height: f32,
+    // Top of every item, in item order
+    pub item_tops: Vec<f32>,
+}
+
+fn roman(mut n: u32) -> String {
+    const NUMERALS: [(u32, &str); 13] = [
+        (1000, "m"), (900, "cm"), (500, "d"), (400, "cd"), (100, "c"), (90, "xc"),
+        (50, "l"), (40, "xl"), (10, "x"), (9, "ix"), (5, "v"), (4, "iv"), (1, "i"),
+    ];
+
+    let mut out = String::new();
+    for (value, numeral) in NUMERALS {
+        while n >= value {
+            out.push_str(numeral);
+            n -= value;
+        }
+    }
+    out
+}
+
+fn alpha(mut n: u32, base: u8) -> String {
+    let mut out = Vec::new();
+    while n > 0 {
+        n -= 1;
+        out.push(base + (n % 26) as u8);
+        n /= 26;
+    }
+    out.reverse();
+    String::from_utf8(out).unwrap_or_default()
+}
+
+fn marker_label(marker: ListMarker, number: u32, level: usize, layout: &ListLayout) -> String {
+    match marker {
+        ListMarker::Bullet => layout.bullets[level % layout.bullets.len()].to_string(),
+        ListMarker::Decimal => format!("{}.", number),
+        ListMarker::LowerAlpha => format!("{}.", alpha(number, b'a')),
+        ListMarker::UpperAlpha => format!("{}.", alpha(number, b'A')),
+        ListMarker::LowerRoman => format!("{}.", roman(number)),
+        ListMarker::None => String::new(),
+    }
+}
+
+impl<B: AtlasBackend> TextWare<B> {
+    // Markers sit in a gutter in front of each item and wrapped lines hang under
+    // the item body. Numbering restarts whenever a shallower item ends a nested list.
+    pub fn generate_list_mesh(&mut self, items: &[ListItem], style: &TextStyle, layout: &ListLayout) -> Result<ListMesh, TextError> {
+        let _span = trace_span!("textware::generate_list_mesh", items = items.len());
+
+        if [layout.width, layout.indent, layout.gap, layout.item_spacing].iter().any(|v| !v.is_finite()) {
+            return Err(TextError::InvalidArgument("list layout values must be finite".to_string()));
+        }
+
+        let mut body = self.create_text_styled("", style)?;
+        let mut marker = self.create_text_styled("", style)?;
+        let line_height = body.buffer.metrics().line_height;
+
+        let mut mesh = TextMesh::default();
+        let mut item_tops = Vec::with_capacity(items.len());
+        let mut counters: Vec<u32> = Vec::new();
+        let mut y = 0.0;
+
+        for item in items {
+            counters.resize(item.level + 1, 0);
+            counters[item.level] += 1;
+
+            let body_x = (item.level + 1) as f32 * layout.indent;
+            let body_width = (layout.width - body_x).max(style.font_size);
+
+            self.set_size(&mut body, Some(body_width), None)?;
+            self.update_text(&mut body, &item.content);
+            self.shape(&mut body);
+
+            let label = marker_label(item.marker, counters[item.level], item.level, layout);
+            self.update_text(&mut marker, &label);
+            self.shape(&mut marker);
+            let marker_width = marker.buffer.layout_runs().next().map_or(0.0, |run| run.line_w);
+
+            for (text, x) in [(&body, body_x), (&marker, body_x - layout.gap - marker_width)] {
+                if let Err(err) = self.append_glyphs_with(text, [x, y], &mut mesh, |_, _, _| true) {
+                    log::debug!("textware: {}", err);
+                }
+            }
+
+            item_tops.push(y);
+            let lines = body.buffer.layout_runs().count().max(1);
+            y += lines as f32 * line_height + layout.item_spacing;
+        }
+
+        let height = if items.is_empty() { 0.0 } else { y - layout.item_spacing };
+        Ok(ListMesh { mesh, height, item_tops })
+    }
+}
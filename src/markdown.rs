@@ -0,0 +1,194 @@
+use pulldown_cmark::{Event, Parser, Tag};
+use crate::backend::AtlasBackend;
+use crate::error::TextError;
+use crate::font::FontId;
+use crate::rich::{Span, SpanStyle};
+use crate::{Text, TextWare};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MarkdownStyle {
+    pub text: SpanStyle,
+    pub heading: SpanStyle,
+    pub code: SpanStyle,
+    pub link: SpanStyle,
+    pub bullet: String,
+    pub indent: String,
+}
+
+impl Default for MarkdownStyle {
+    fn default() -> Self {
+        Self {
+            text: SpanStyle::default(),
+            heading: SpanStyle { weight: Some(700), ..Default::default() },
+            code: SpanStyle { monospace: true, color: Some([0.9, 0.6, 0.4, 1.0]), ..Default::default() },
+            link: SpanStyle { color: Some([0.35, 0.6, 1.0, 1.0]), ..Default::default() },
+            bullet: "• ".to_string(),
+            indent: "  ".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct MarkdownDocument {
+    pub spans: Vec<Span>,
+    // Link destinations; a link span carries `metadata == index + 1`
+    pub links: Vec<String>,
+}
+
+struct Builder<'a> {
+    style: &'a MarkdownStyle,
+    stack: Vec<SpanStyle>,
+    lists: Vec<Option<u64>>,
+    document: MarkdownDocument,
+}
+
+impl Builder<'_> {
+    fn current(&self) -> &SpanStyle {
+        self.stack.last().unwrap_or(&self.style.text)
+    }
+
+    fn push_style(&mut self, overrides: &SpanStyle) {
+        let style = self.current().merge(overrides);
+        self.stack.push(style);
+    }
+
+    fn push_text(&mut self, content: &str, style: SpanStyle) {
+        if content.is_empty() {
+            return;
+        }
+
+        if let Some(last) = self.document.spans.last_mut() {
+            if last.style == style {
+                last.text.push_str(content);
+                return;
+            }
+        }
+
+        self.document.spans.push(Span::new(content, style));
+    }
+
+    fn ends_with_newline(&self) -> bool {
+        self.document.spans.last().is_none_or(|span| span.text.ends_with('\n'))
+    }
+
+    fn end_block(&mut self) {
+        if !self.ends_with_newline() {
+            let style = self.style.text.clone();
+            self.push_text("\n", style);
+        }
+    }
+
+    fn start_block(&mut self) {
+        if !self.document.spans.is_empty() && self.lists.is_empty() {
+            self.end_block();
+            let style = self.style.text.clone();
+            self.push_text("\n", style);
+        }
+    }
+}
+
+pub fn parse_markdown(source: &str, style: &MarkdownStyle) -> MarkdownDocument {
+    let mut builder = Builder {
+        style,
+        stack: Vec::new(),
+        lists: Vec::new(),
+        document: MarkdownDocument::default(),
+    };
+
+    for event in Parser::new(source) {
+        match event {
+            Event::Start(tag) => match tag {
+                Tag::Paragraph => builder.start_block(),
+                Tag::Heading(..) => {
+                    builder.start_block();
+                    builder.push_style(&style.heading);
+                }
+                Tag::CodeBlock(_) => {
+                    builder.start_block();
+                    builder.push_style(&style.code);
+                }
+                Tag::List(start) => {
+                    if builder.lists.is_empty() {
+                        builder.start_block();
+                    } else {
+                        builder.end_block();
+                    }
+                    builder.lists.push(start);
+                }
+                Tag::Item => {
+                    builder.end_block();
+                    let depth = builder.lists.len().saturating_sub(1);
+                    let mut marker = style.indent.repeat(depth);
+                    match builder.lists.last_mut() {
+                        Some(Some(number)) => {
+                            marker.push_str(&format!("{}. ", number));
+                            *number += 1;
+                        }
+                        _ => marker.push_str(&style.bullet),
+                    }
+                    builder.push_text(&marker, style.text.clone());
+                }
+                Tag::Emphasis => builder.push_style(&SpanStyle { italic: true, ..Default::default() }),
+                Tag::Strong => builder.push_style(&SpanStyle { weight: Some(700), ..Default::default() }),
+                Tag::Link(_, url, _) => {
+                    builder.document.links.push(url.to_string());
+                    let link = SpanStyle { metadata: builder.document.links.len(), ..style.link.clone() };
+                    builder.push_style(&link);
+                }
+                _ => {}
+            },
+            Event::End(tag) => match tag {
+                Tag::Paragraph | Tag::Item => builder.end_block(),
+                Tag::Heading(..) | Tag::CodeBlock(_) => {
+                    builder.stack.pop();
+                    builder.end_block();
+                }
+                Tag::List(_) => {
+                    builder.lists.pop();
+                    builder.end_block();
+                }
+                Tag::Emphasis | Tag::Strong | Tag::Link(..) => {
+                    builder.stack.pop();
+                }
+                _ => {}
+            },
+            Event::Text(content) => {
+                let current = builder.current().clone();
+                builder.push_text(&content, current);
+            }
+            Event::Code(content) => {
+                let code = builder.current().merge(&style.code);
+                builder.push_text(&content, code);
+            }
+            Event::SoftBreak => {
+                let current = builder.current().clone();
+                builder.push_text(" ", current);
+            }
+            Event::HardBreak => {
+                let current = builder.current().clone();
+                builder.push_text("\n", current);
+            }
+            _ => {}
+        }
+    }
+
+    let mut document = builder.document;
+    while let Some(last) = document.spans.last_mut() {
+        let trimmed = last.text.trim_end_matches('\n').len();
+        last.text.truncate(trimmed);
+        if !last.text.is_empty() {
+            break;
+        }
+        document.spans.pop();
+    }
+
+    document
+}
+
+impl<B: AtlasBackend> TextWare<B> {
+    pub fn create_markdown_text(&mut self, source: &str, style: &MarkdownStyle, font_id: Option<FontId>, font_size: f32, line_height: Option<f32>) -> Result<(Text, MarkdownDocument), TextError> {
+        let document = parse_markdown(source, style);
+        let text = self.create_rich_text(&document.spans, font_id, font_size, line_height)?;
+        Ok((text, document))
+    }
+}
@@ -0,0 +1,240 @@
+use crate::backend::AtlasBackend;
+use crate::color::parse_color;
+use crate::error::TextError;
+use crate::font::{FontId, FontSystem};
+use crate::rich::{Span, SpanStyle};
+use crate::{Text, TextWare};
+
+pub fn parse_markup(source: &str, fonts: &FontSystem) -> Result<Vec<Span>, TextError> {
+    let mut spans: Vec<Span> = Vec::new();
+    let mut stack: Vec<(String, SpanStyle)> = Vec::new();
+    let mut rest = source;
+
+    while !rest.is_empty() {
+        let Some(open) = rest.find('<') else {
+            push_span(&mut spans, &decode_entities(rest)?, &stack);
+            break;
+        };
+
+        if open > 0 {
+            push_span(&mut spans, &decode_entities(&rest[..open])?, &stack);
+        }
+
+        let close = rest[open..]
+            .find('>')
+            .ok_or_else(|| TextError::Markup("unterminated tag".to_string()))?;
+        let tag = rest[open + 1..open + close].trim();
+        rest = &rest[open + close + 1..];
+
+        if let Some(name) = tag.strip_prefix('/') {
+            let name = name.trim();
+            match stack.pop() {
+                Some((open_name, _)) if open_name == name => {}
+                Some((open_name, _)) => {
+                    return Err(TextError::Markup(format!("expected </{}>, found </{}>", open_name, name)));
+                }
+                None => return Err(TextError::Markup(format!("unexpected </{}>", name))),
+            }
+            continue;
+        }
+
+        let (tag, self_closing) = match tag.strip_suffix('/') {
+            Some(tag) => (tag.trim(), true),
+            None => (tag, false),
+        };
+
+        let (name, attributes) = match tag.find(char::is_whitespace) {
+            Some(split) => (&tag[..split], &tag[split..]),
+            None => (tag, ""),
+        };
+
+        let overrides = tag_style(name, attributes, fonts)?;
+        if !self_closing {
+            let parent = stack.last().map(|(_, style)| style.clone()).unwrap_or_default();
+            stack.push((name.to_string(), parent.merge(&overrides)));
+        }
+    }
+
+    if let Some((name, _)) = stack.last() {
+        return Err(TextError::Markup(format!("unclosed <{}>", name)));
+    }
+
+    Ok(spans)
+}
+
+fn push_span(spans: &mut Vec<Span>, content: &str, stack: &[(String, SpanStyle)]) {
+    if content.is_empty() {
+        return;
+    }
+
+    let style = stack.last().map(|(_, style)| style.clone()).unwrap_or_default();
+    if let Some(last) = spans.last_mut() {
+        if last.style == style {
+            last.text.push_str(content);
+            return;
+        }
+    }
+
+    spans.push(Span::new(content, style));
+}
+
+fn tag_style(name: &str, attributes: &str, fonts: &FontSystem) -> Result<SpanStyle, TextError> {
+    let mut style = SpanStyle::default();
+
+    match name {
+        "b" => style.weight = Some(700),
+        "i" => style.italic = true,
+        "tt" => style.monospace = true,
+        "u" => style.underline = true,
+        "s" => style.strikethrough = true,
+        "span" => {
+            for (key, value) in parse_attributes(attributes)? {
+                match key.as_str() {
+                    "color" | "foreground" | "fgcolor" => {
+                        style.color = Some(parse_color(&value)
+                            .ok_or_else(|| TextError::Markup(format!("invalid color '{}'", value)))?);
+                    }
+                    "weight" | "font_weight" => {
+                        style.weight = Some(match value.as_str() {
+                            "ultralight" => 200,
+                            "light" => 300,
+                            "normal" => 400,
+                            "bold" => 700,
+                            "ultrabold" => 800,
+                            "heavy" => 900,
+                            number => number
+                                .parse()
+                                .map_err(|_| TextError::Markup(format!("invalid weight '{}'", value)))?,
+                        });
+                    }
+                    "style" | "font_style" => match value.as_str() {
+                        "italic" | "oblique" => style.italic = true,
+                        "normal" => {}
+                        _ => return Err(TextError::Markup(format!("invalid style '{}'", value))),
+                    },
+                    "underline" => style.underline = match value.as_str() {
+                        "single" | "true" => true,
+                        "none" | "false" => false,
+                        _ => return Err(TextError::Markup(format!("invalid underline '{}'", value))),
+                    },
+                    "strikethrough" => style.strikethrough = match value.as_str() {
+                        "true" => true,
+                        "false" => false,
+                        _ => return Err(TextError::Markup(format!("invalid strikethrough '{}'", value))),
+                    },
+                    "face" | "font" | "font_family" => {
+                        if value.eq_ignore_ascii_case("monospace") {
+                            style.monospace = true;
+                        } else {
+                            style.font_id = Some(fonts
+                                .find_font(&value)
+                                .ok_or_else(|| TextError::Markup(format!("unknown font '{}'", value)))?);
+                        }
+                    }
+                    _ => return Err(TextError::Markup(format!("unsupported attribute '{}'", key))),
+                }
+            }
+        }
+        _ => return Err(TextError::Markup(format!("unsupported tag <{}>", name))),
+    }
+
+    Ok(style)
+}
+
+fn parse_attributes(source: &str) -> Result<Vec<(String, String)>, TextError> {
+    let mut attributes = Vec::new();
+    let mut rest = source.trim_start();
+
+    while !rest.is_empty() {
+        let eq = rest
+            .find('=')
+            .ok_or_else(|| TextError::Markup(format!("malformed attribute '{}'", rest)))?;
+        let key = rest[..eq].trim().to_string();
+        let value_part = rest[eq + 1..].trim_start();
+
+        let quote = value_part
+            .chars()
+            .next()
+            .filter(|c| *c == '\'' || *c == '"')
+            .ok_or_else(|| TextError::Markup(format!("unquoted value for '{}'", key)))?;
+        let end = value_part[1..]
+            .find(quote)
+            .ok_or_else(|| TextError::Markup(format!("unterminated value for '{}'", key)))?;
+
+        attributes.push((key, decode_entities(&value_part[1..end + 1])?));
+        rest = value_part[end + 2..].trim_start();
+    }
+
+    Ok(attributes)
+}
+
+fn decode_entities(source: &str) -> Result<String, TextError> {
+    let mut out = String::with_capacity(source.len());
+    let mut rest = source;
+
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        let semi = rest[amp..]
+            .find(';')
+            .ok_or_else(|| TextError::Markup("unterminated entity".to_string()))?;
+        let entity = &rest[amp + 1..amp + semi];
+
+        let decoded = match entity {
+            "lt" => '<',
+            "gt" => '>',
+            "amp" => '&',
+            "quot" => '"',
+            "apos" => '\'',
+            _ => entity
+                .strip_prefix("#x")
+                .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                .or_else(|| entity.strip_prefix('#').and_then(|dec| dec.parse().ok()))
+                .and_then(char::from_u32)
+                .ok_or_else(|| TextError::Markup(format!("unknown entity '&{};'", entity)))?,
+        };
+
+        out.push(decoded);
+        rest = &rest[amp + semi + 1..];
+    }
+
+    out.push_str(rest);
+    Ok(out)
+}
+
+impl<B: AtlasBackend> TextWare<B> {
+    pub fn create_text_markup(&mut self, markup: &str, font_id: Option<FontId>, font_size: f32, line_height: Option<f32>) -> Result<Text, TextError> {
+        let spans = parse_markup(markup, &self.font_system)?;
+        self.create_rich_text(&spans, font_id, font_size, line_height)
+    }
+
+    pub fn update_text_markup(&mut self, text: &mut Text, markup: &str) -> Result<(), TextError> {
+        let spans = parse_markup(markup, &self.font_system)?;
+        self.update_rich_text(text, &spans);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nested_tags_inherit_and_merge() {
+        let fonts = FontSystem::new();
+        let spans = parse_markup("a <b>b <u>c</u></b> <span color=\"#ff0000\" strikethrough=\"true\">d</span>", &fonts).unwrap();
+        let texts: Vec<_> = spans.iter().map(|span| span.text.as_str()).collect();
+        assert_eq!(texts, ["a ", "b ", "c", " ", "d"]);
+        assert_eq!(spans[2].style.weight, Some(700));
+        assert!(spans[2].style.underline && !spans[1].style.underline);
+        assert!(spans[4].style.strikethrough);
+        assert_eq!(spans[4].style.color, Some([1.0, 0.0, 0.0, 1.0]));
+    }
+
+    #[test]
+    fn size_tags_are_rejected() {
+        let fonts = FontSystem::new();
+        for source in ["<big>a</big>", "<small>a</small>", "<sub>a</sub>", "<sup>a</sup>"] {
+            assert!(matches!(parse_markup(source, &fonts), Err(TextError::Markup(_))), "{source}");
+        }
+    }
+}
@@ -0,0 +1,82 @@
+use crate::backend::AtlasBackend;
+use crate::error::TextError;
+use crate::{Text, TextAlign, TextWare};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LineMetrics {
+    // Buffer line the layout line belongs to, wrapped lines share it
+    pub line: usize,
+    pub top: f32,
+    pub baseline: f32,
+    pub ascent: f32,
+    pub descent: f32,
+    pub width: f32,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TextMetricsResult {
+    pub width: f32,
+    pub height: f32,
+    pub line_count: usize,
+    pub lines: Vec<LineMetrics>,
+}
+
+impl<B: AtlasBackend> TextWare<B> {
+    // Laid out size of the text without touching the atlas, for sizing containers ahead of generate_mesh
+    pub fn measure(&mut self, text: &mut Text) -> TextMetricsResult {
+        let _span = trace_span!("textware::measure");
+
+        self.shape(text);
+
+        let line_height = text.buffer.metrics().line_height;
+        let lines: Vec<LineMetrics> = text.buffer
+            .layout_runs()
+            .map(|run| LineMetrics {
+                line: run.line_i,
+                top: run.line_top,
+                baseline: run.line_y,
+                ascent: run.line_y - run.line_top,
+                descent: run.line_top + line_height - run.line_y,
+                width: run.line_w,
+            })
+            .collect();
+
+        TextMetricsResult {
+            width: lines.iter().map(|line| line.width).fold(0.0, f32::max),
+            height: lines.last().map_or(0.0, |line| line.top + line_height),
+            line_count: lines.len(),
+            lines,
+        }
+    }
+
+    // Wraps at `max_width` with no height limit and measures the result, the common "wrap
+    // this label at 300px, how tall is it" in one call. The box then shrinks to the widest
+    // line, so centered and right aligned lines line up with each other rather than with a
+    // max width the text doesn't fill.
+    pub fn wrap_to_width(&mut self, text: &mut Text, max_width: f32, align: TextAlign) -> Result<TextMetricsResult, TextError> {
+        let _span = trace_span!("textware::wrap_to_width", max_width);
+
+        if !max_width.is_finite() || max_width <= 0.0 {
+            return Err(TextError::InvalidSize { width: max_width, height: f32::MAX });
+        }
+
+        text.set_alignment(Some(align));
+        self.set_size(text, Some(max_width), None)?;
+        let metrics = self.measure(text);
+
+        let width = metrics.width.ceil();
+        if width <= 0.0 || width >= max_width {
+            return Ok(metrics);
+        }
+
+        self.set_size(text, Some(width), None)?;
+        let shrunk = self.measure(text);
+        if shrunk.line_count == metrics.line_count {
+            return Ok(shrunk);
+        }
+
+        // Hanging whitespace can wrap again at the tighter width, keep the full one then
+        self.set_size(text, Some(max_width), None)?;
+        Ok(self.measure(text))
+    }
+}
@@ -0,0 +1,79 @@
+use std::collections::HashSet;
+use crate::backend::AtlasBackend;
+use crate::{Text, TextWare};
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct MemoryReport {
+    // Font files loaded from bytes, memory mapped system fonts are not counted
+    pub font_data_bytes: usize,
+    pub font_faces: usize,
+    pub cached_glyphs: usize,
+    // CPU-side copies of rasterized glyphs kept by the cache
+    pub glyph_bitmap_bytes: usize,
+    pub atlas_pages: usize,
+    pub atlas_bytes: usize,
+    // Fraction of the atlas area already packed
+    pub atlas_fill: f32,
+    pub label_cache_bytes: usize,
+    pub texts: usize,
+    // Estimated from line text and laid out glyphs, filled by add_text
+    pub text_buffer_bytes: usize,
+}
+
+impl MemoryReport {
+    pub fn add_text(&mut self, text: &Text) {
+        self.texts += 1;
+        self.text_buffer_bytes += text.buffer_bytes();
+    }
+
+    pub fn cpu_bytes(&self) -> usize {
+        self.font_data_bytes + self.glyph_bitmap_bytes + self.label_cache_bytes + self.text_buffer_bytes
+    }
+
+    pub fn gpu_bytes(&self) -> usize {
+        self.atlas_bytes
+    }
+}
+
+impl Text {
+    pub fn buffer_bytes(&self) -> usize {
+        let text_bytes: usize = self.buffer.lines.iter().map(|line| line.text().len()).sum();
+        let glyphs = self.glyph_count();
+
+        text_bytes
+            + glyphs * std::mem::size_of::<cosmic_text::LayoutGlyph>()
+            + self.spans.len() * std::mem::size_of::<crate::SpanStyle>()
+    }
+}
+
+impl<B: AtlasBackend> TextWare<B> {
+    pub fn memory_report(&self) -> MemoryReport {
+        let db = self.font_system.sys.db();
+        let mut sources = HashSet::new();
+        let mut font_data_bytes = 0;
+
+        for face in db.faces() {
+            if let cosmic_text::fontdb::Source::Binary(data) = &face.source {
+                let bytes: &[u8] = (**data).as_ref();
+                if sources.insert(bytes.as_ptr() as usize) {
+                    font_data_bytes += bytes.len();
+                }
+            }
+        }
+
+        let (cached_glyphs, glyph_bitmap_bytes) = self.glyph_cache.bitmap_usage();
+        let atlas_size = self.glyph_cache.atlas_size() as usize;
+
+        MemoryReport {
+            font_data_bytes,
+            font_faces: db.len(),
+            cached_glyphs,
+            glyph_bitmap_bytes,
+            atlas_pages: 1,
+            atlas_bytes: atlas_size * atlas_size,
+            atlas_fill: self.glyph_cache.fill(),
+            label_cache_bytes: self.labels.bytes(),
+            ..Default::default()
+        }
+    }
+}
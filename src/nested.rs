@@ -0,0 +1,56 @@
+use crate::backend::AtlasBackend;
+use crate::error::TextError;
+use crate::font::FontId;
+use crate::rich::{Span, SpanStyle};
+use crate::{Text, TextWare};
+
+// Span tree: a group's style applies to everything under it, and nested groups only
+// override what they set, the same way SpanStyle::merge combines two styles
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SpanNode {
+    Text(String),
+    Group { style: SpanStyle, children: Vec<SpanNode> },
+}
+
+impl SpanNode {
+    pub fn text(text: impl Into<String>) -> Self {
+        SpanNode::Text(text.into())
+    }
+
+    pub fn group(style: SpanStyle, children: Vec<SpanNode>) -> Self {
+        SpanNode::Group { style, children }
+    }
+
+    // Flat spans with every text node's inherited style resolved
+    pub fn flatten(nodes: &[SpanNode]) -> Vec<Span> {
+        let mut spans = Vec::new();
+        for node in nodes {
+            node.flatten_into(&SpanStyle::default(), &mut spans);
+        }
+        spans
+    }
+
+    fn flatten_into(&self, inherited: &SpanStyle, spans: &mut Vec<Span>) {
+        match self {
+            SpanNode::Text(text) if text.is_empty() => {}
+            SpanNode::Text(text) => spans.push(Span::new(text.as_str(), inherited.clone())),
+            SpanNode::Group { style, children } => {
+                let resolved = inherited.merge(style);
+                for child in children {
+                    child.flatten_into(&resolved, spans);
+                }
+            }
+        }
+    }
+}
+
+impl<B: AtlasBackend> TextWare<B> {
+    pub fn create_nested_text(&mut self, nodes: &[SpanNode], font_id: Option<FontId>, font_size: f32, line_height: Option<f32>) -> Result<Text, TextError> {
+        self.create_rich_text(&SpanNode::flatten(nodes), font_id, font_size, line_height)
+    }
+
+    pub fn update_nested_text(&mut self, text: &mut Text, nodes: &[SpanNode]) {
+        self.update_rich_text(text, &SpanNode::flatten(nodes));
+    }
+}
@@ -0,0 +1,146 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use cosmic_text::fontdb;
+use swash::scale::ScaleContext;
+use swash::zeno::{Command, PathData, Vector};
+use std::ops::Range;
+use crate::backend::AtlasBackend;
+use crate::font::FontSystem;
+use crate::{rich, Text, TextWare};
+
+// Glyph outline in font units, y up. One copy per (font, glyph) serves every size.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GlyphOutline {
+    pub units_per_em: f32,
+    pub commands: Vec<Command>,
+    pub bounds: [f32; 4],
+}
+
+impl GlyphOutline {
+    pub fn scaled(&self, font_size: f32) -> Vec<Command> {
+        let scale = font_size / self.units_per_em;
+        let p = |v: Vector| Vector::new(v.x * scale, v.y * scale);
+
+        self.commands
+            .iter()
+            .map(|command| match *command {
+                Command::MoveTo(to) => Command::MoveTo(p(to)),
+                Command::LineTo(to) => Command::LineTo(p(to)),
+                Command::QuadTo(c, to) => Command::QuadTo(p(c), p(to)),
+                Command::CurveTo(c1, c2, to) => Command::CurveTo(p(c1), p(c2), p(to)),
+                Command::Close => Command::Close,
+            })
+            .collect()
+    }
+
+    // Scaled to `font_size` and flipped to y down with the origin at `origin`
+    pub fn placed(&self, font_size: f32, origin: [f32; 2]) -> Vec<Command> {
+        let scale = font_size / self.units_per_em;
+        let p = |v: Vector| Vector::new(origin[0] + v.x * scale, origin[1] - v.y * scale);
+
+        self.commands
+            .iter()
+            .map(|command| match *command {
+                Command::MoveTo(to) => Command::MoveTo(p(to)),
+                Command::LineTo(to) => Command::LineTo(p(to)),
+                Command::QuadTo(c, to) => Command::QuadTo(p(c), p(to)),
+                Command::CurveTo(c1, c2, to) => Command::CurveTo(p(c1), p(c2), p(to)),
+                Command::Close => Command::Close,
+            })
+            .collect()
+    }
+}
+
+// Outline of one glyph of a laid out text, in the text's layout space (pixels, y down)
+#[derive(Debug, Clone, PartialEq)]
+pub struct GlyphPath {
+    pub line: usize,
+    // Byte range of the glyph's cluster in its buffer line
+    pub cluster: Range<usize>,
+    // Pen position on the baseline
+    pub origin: [f32; 2],
+    pub color: [f32; 4],
+    pub commands: Vec<Command>,
+}
+
+#[derive(Default)]
+pub(crate) struct OutlineCache {
+    context: ScaleContext,
+    outlines: HashMap<(fontdb::ID, u16), Option<Arc<GlyphOutline>>>,
+    // Glyphs with color layers, drawn from the color atlas rather than their outline
+    color: HashSet<(fontdb::ID, u16)>,
+}
+
+impl OutlineCache {
+    pub(crate) fn get(&mut self, font_system: &mut FontSystem, font_id: fontdb::ID, glyph_id: u16) -> Option<Arc<GlyphOutline>> {
+        if let Some(outline) = self.outlines.get(&(font_id, glyph_id)) {
+            return outline.clone();
+        }
+
+        let outline = font_system.sys.get_font(font_id).and_then(|font| {
+            let font = font.as_swash();
+            let units_per_em = font.metrics(&[]).units_per_em as f32;
+
+            // No size on the scaler keeps the outline in font units
+            let mut scaler = self.context.builder(font).build();
+            if scaler.has_color_outlines() && scaler.scale_color_outline(glyph_id).is_some() {
+                self.color.insert((font_id, glyph_id));
+            }
+            let outline = scaler.scale_outline(glyph_id)?;
+            let bounds = outline.bounds();
+            let commands: Vec<Command> = outline.path().commands().collect();
+
+            Some(Arc::new(GlyphOutline {
+                units_per_em,
+                commands,
+                bounds: [bounds.min.x, bounds.min.y, bounds.max.x, bounds.max.y],
+            }))
+        });
+
+        self.outlines.insert((font_id, glyph_id), outline.clone());
+        outline
+    }
+
+    // Outline to rasterize as an alpha mask, None for color glyphs
+    pub(crate) fn mask(&mut self, font_system: &mut FontSystem, font_id: fontdb::ID, glyph_id: u16) -> Option<Arc<GlyphOutline>> {
+        let outline = self.get(font_system, font_id, glyph_id)?;
+        (!self.color.contains(&(font_id, glyph_id))).then_some(outline)
+    }
+}
+
+impl<B: AtlasBackend> TextWare<B> {
+    pub fn glyph_outline(&mut self, font_id: fontdb::ID, glyph_id: u16) -> Option<Arc<GlyphOutline>> {
+        self.glyph_cache.outlines.get(&mut self.font_system, font_id, glyph_id)
+    }
+
+    // Vector outlines of every glyph of the text, for plotter export, custom tessellation
+    // or physics driven effects. Glyphs without an outline, such as spaces and bitmap
+    // emoji, are left out.
+    pub fn text_paths(&mut self, text: &mut Text) -> Vec<GlyphPath> {
+        let _span = trace_span!("textware::text_paths");
+
+        self.shape(text);
+
+        let mut paths = Vec::new();
+        for run in text.buffer.layout_runs() {
+            for glyph in run.glyphs.iter() {
+                let Some(outline) = self.glyph_cache.outlines.get(&mut self.font_system, glyph.font_id, glyph.glyph_id) else {
+                    continue;
+                };
+                if outline.commands.is_empty() {
+                    continue;
+                }
+
+                let origin = [glyph.x + glyph.x_offset * glyph.font_size, run.line_y + glyph.y - glyph.y_offset * glyph.font_size];
+                paths.push(GlyphPath {
+                    line: run.line_i,
+                    cluster: glyph.start..glyph.end,
+                    origin,
+                    color: glyph.color_opt.map(rich::from_cosmic_color).unwrap_or(text.color),
+                    commands: outline.placed(glyph.font_size, origin),
+                });
+            }
+        }
+        paths
+    }
+}
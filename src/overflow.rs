@@ -0,0 +1,73 @@
+use crate::backend::AtlasBackend;
+use crate::{Text, TextMesh, TextWare};
+
+// How far the laid out content reaches past the text's width and height
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Overflow {
+    pub x: f32,
+    // A lower bound when cosmic stopped laying out lines below the height,
+    // each of those counts as one line
+    pub y: f32,
+}
+
+impl Text {
+    pub fn clips_to_bounds(&self) -> bool {
+        self.clip
+    }
+
+    // Cuts generated quads off at the width and height set with set_size
+    pub fn set_clip_to_bounds(&mut self, clip: bool) {
+        self.clip = clip;
+        self.invalidate_mesh();
+    }
+}
+
+impl<B: AtlasBackend> TextWare<B> {
+    // None when the content fits, e.g. to decide on a scrollbar or a tooltip
+    pub fn overflow(&mut self, text: &mut Text) -> Option<Overflow> {
+        self.shape(text);
+
+        let (width, height) = text.buffer.size();
+        let mut widest = 0.0f32;
+        let mut lines = 0;
+        for line in &text.buffer.lines {
+            match line.layout_opt() {
+                Some(layout) => {
+                    widest = layout.iter().fold(widest, |widest, layout_line| widest.max(layout_line.w));
+                    lines += layout.len();
+                }
+                None => lines += 1,
+            }
+        }
+
+        let overflow = Overflow {
+            x: (widest - width).max(0.0),
+            y: (lines as f32 * text.buffer.metrics().line_height - height).max(0.0),
+        };
+        (overflow.x > 0.0 || overflow.y > 0.0).then_some(overflow)
+    }
+}
+
+// Clamps the quads from `first_vertex` on to [x0, y0, x1, y1] with their UVs cut to match.
+// Quads fully outside collapse to nothing but stay in place, keeping quad indices stable.
+pub(crate) fn clip_quads(mesh: &mut TextMesh, first_vertex: usize, rect: [f32; 4]) {
+    let [x0, y0, x1, y1] = rect;
+
+    for quad in mesh.vertices[first_vertex..].chunks_exact_mut(4) {
+        let (min, max) = (quad[0], quad[2]);
+        let lerp = |axis: usize, value: f32| {
+            let extent = max.position[axis] - min.position[axis];
+            let t = if extent > 0.0 { (value - min.position[axis]) / extent } else { 0.0 };
+            min.uv[axis] + (max.uv[axis] - min.uv[axis]) * t
+        };
+
+        for vertex in quad.iter_mut() {
+            let x = vertex.position[0].clamp(x0, x1.max(x0));
+            let y = vertex.position[1].clamp(y0, y1.max(y0));
+            vertex.position[0] = x;
+            vertex.position[1] = y;
+            vertex.uv = [lerp(0, x), lerp(1, y)];
+        }
+    }
+}
@@ -0,0 +1,94 @@
+use crate::backend::AtlasBackend;
+use crate::error::TextError;
+use crate::{layout_bounds, space, Text, TextMesh, TextVertex, TextWare, UvRect};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct PillStyle {
+    // Horizontal and vertical space between the text bounds and the edge
+    pub padding: [f32; 2],
+    // None rounds the short sides fully, the classic pill
+    pub radius: Option<f32>,
+    pub color: [f32; 4],
+    // Triangles per corner
+    pub segments: u32,
+}
+
+impl Default for PillStyle {
+    fn default() -> Self {
+        Self {
+            padding: [8.0, 2.0],
+            radius: None,
+            color: [0.2, 0.2, 0.25, 1.0],
+            segments: 6,
+        }
+    }
+}
+
+impl TextMesh {
+    // Triangle fan around the center, drawn with the atlas' solid texel
+    pub(crate) fn push_rounded_rect(&mut self, rect: [f32; 4], radius: f32, segments: u32, uv: UvRect, color: [f32; 4]) {
+        let [x, y, w, h] = rect;
+        let radius = radius.clamp(0.0, w.min(h) / 2.0);
+        let segments = segments.max(1);
+        let (u, v) = (uv.0, uv.1);
+
+        let center = self.vertices.len() as u32;
+        self.vertices.push(TextVertex { position: [x + w / 2.0, y + h / 2.0, 0.0], uv: [u, v], color });
+
+        // Counter clockwise on screen like push_quad: down the left side, along the bottom, up the right
+        let corners = [
+            (x + radius, y + radius, 270.0f32),
+            (x + radius, y + h - radius, 180.0),
+            (x + w - radius, y + h - radius, 90.0),
+            (x + w - radius, y + radius, 0.0),
+        ];
+        for (cx, cy, start) in corners {
+            for step in 0..=segments {
+                let angle = (start - 90.0 * step as f32 / segments as f32).to_radians();
+                let position = [cx + radius * angle.cos(), cy + radius * angle.sin(), 0.0];
+                self.vertices.push(TextVertex { position, uv: [u, v], color });
+            }
+        }
+
+        let ring = 4 * (segments + 1);
+        for i in 0..ring {
+            self.indices.extend_from_slice(&[center, center + 1 + i, center + 1 + (i + 1) % ring]);
+        }
+    }
+}
+
+impl<B: AtlasBackend> TextWare<B> {
+    // Badge/chip/tag: a rounded background sized to the text bounds, under the glyphs
+    pub fn generate_pill_mesh(&mut self, text: &mut Text, style: &PillStyle) -> Result<TextMesh, TextError> {
+        let _span = trace_span!("textware::generate_pill_mesh");
+
+        let values = [style.padding[0], style.padding[1], style.radius.unwrap_or(0.0)];
+        if values.iter().any(|v| !v.is_finite()) {
+            return Err(TextError::InvalidArgument("pill padding and radius must be finite".to_string()));
+        }
+        if style.segments > 64 {
+            return Err(TextError::InvalidArgument(format!("{} segments per corner is too many", style.segments)));
+        }
+
+        self.shape(text);
+
+        let mut mesh = TextMesh::with_capacity(text.glyph_count() + style.segments as usize + 2);
+        if let Some([x0, y0, x1, y1]) = layout_bounds(text) {
+            let rect = [
+                x0 - style.padding[0],
+                y0 - style.padding[1],
+                x1 - x0 + style.padding[0] * 2.0,
+                y1 - y0 + style.padding[1] * 2.0,
+            ];
+            let radius = style.radius.unwrap_or(rect[3] / 2.0);
+
+            mesh.push_rounded_rect(rect, radius, style.segments, self.glyph_cache.solid_uv(), style.color);
+            space::apply_space(text.space, &mut mesh, 0, 0, 1.0);
+        }
+
+        self.append_glyphs_with(text, [0.0, 0.0], &mut mesh, |_, _, _| true)?;
+        Ok(mesh)
+    }
+}
@@ -0,0 +1,136 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+const QUERY_COUNT: u32 = 2;
+const BUFFER_SIZE: u64 = QUERY_COUNT as u64 * std::mem::size_of::<u64>() as u64;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TextPassTimings {
+    pub gpu_time: Duration,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ReadbackState {
+    Idle,
+    Copied,
+    Mapping,
+}
+
+pub struct GpuProfiler {
+    query_set: wgpu::QuerySet,
+    resolve_buffer: wgpu::Buffer,
+    readback_buffer: wgpu::Buffer,
+    period: f32,
+    state: ReadbackState,
+    mapped: Arc<AtomicBool>,
+    // Set by the map callback when the readback failed, e.g. on device loss
+    map_failed: Arc<AtomicBool>,
+    failed: bool,
+    last: Option<TextPassTimings>,
+}
+
+impl GpuProfiler {
+    pub fn new(device: &wgpu::Device, queue: &wgpu::Queue) -> Option<Self> {
+        if !device.features().contains(wgpu::Features::TIMESTAMP_QUERY) {
+            return None;
+        }
+
+        let query_set = device.create_query_set(&wgpu::QuerySetDescriptor {
+            label: Some("textware timestamps"),
+            ty: wgpu::QueryType::Timestamp,
+            count: QUERY_COUNT,
+        });
+
+        let resolve_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("textware timestamp resolve"),
+            size: BUFFER_SIZE,
+            usage: wgpu::BufferUsages::QUERY_RESOLVE | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+
+        let readback_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("textware timestamp readback"),
+            size: BUFFER_SIZE,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        Some(Self {
+            query_set,
+            resolve_buffer,
+            readback_buffer,
+            period: queue.get_timestamp_period(),
+            state: ReadbackState::Idle,
+            mapped: Arc::new(AtomicBool::new(false)),
+            map_failed: Arc::new(AtomicBool::new(false)),
+            failed: false,
+            last: None,
+        })
+    }
+
+    pub fn timestamp_writes(&self) -> wgpu::RenderPassTimestampWrites<'_> {
+        wgpu::RenderPassTimestampWrites {
+            query_set: &self.query_set,
+            beginning_of_pass_write_index: Some(0),
+            end_of_pass_write_index: Some(1),
+        }
+    }
+
+    pub fn resolve(&mut self, encoder: &mut wgpu::CommandEncoder) {
+        if self.state != ReadbackState::Idle {
+            return;
+        }
+
+        encoder.resolve_query_set(&self.query_set, 0..QUERY_COUNT, &self.resolve_buffer, 0);
+        encoder.copy_buffer_to_buffer(&self.resolve_buffer, 0, &self.readback_buffer, 0, BUFFER_SIZE);
+        self.state = ReadbackState::Copied;
+    }
+
+    pub fn collect(&mut self, device: &wgpu::Device) -> Option<TextPassTimings> {
+        if self.state == ReadbackState::Copied {
+            let (mapped, map_failed) = (self.mapped.clone(), self.map_failed.clone());
+            self.readback_buffer.slice(..).map_async(wgpu::MapMode::Read, move |result| match result {
+                Ok(()) => mapped.store(true, Ordering::Release),
+                Err(_) => map_failed.store(true, Ordering::Release),
+            });
+            self.state = ReadbackState::Mapping;
+        }
+
+        if self.state == ReadbackState::Mapping {
+            device.poll(wgpu::Maintain::Poll);
+
+            if self.mapped.swap(false, Ordering::Acquire) {
+                {
+                    let data = self.readback_buffer.slice(..).get_mapped_range();
+                    let timestamps: &[u64] = bytemuck::cast_slice(&data);
+                    let ticks = timestamps[1].saturating_sub(timestamps[0]);
+                    let nanos = ticks as f64 * self.period as f64;
+                    self.last = Some(TextPassTimings { gpu_time: Duration::from_nanos(nanos as u64) });
+                }
+                self.readback_buffer.unmap();
+                self.state = ReadbackState::Idle;
+                self.failed = false;
+            } else if self.map_failed.swap(false, Ordering::Acquire) {
+                // The failed map usually leaves the buffer unmapped already, the scope keeps
+                // that validation error from reaching the uncaptured error handler
+                device.push_error_scope(wgpu::ErrorFilter::Validation);
+                self.readback_buffer.unmap();
+                drop(device.pop_error_scope());
+                self.state = ReadbackState::Idle;
+                self.failed = true;
+            }
+        }
+
+        self.last
+    }
+
+    pub fn last_timings(&self) -> Option<TextPassTimings> {
+        self.last
+    }
+
+    // Whether the latest readback failed to map; the next resolve tries again
+    pub fn readback_failed(&self) -> bool {
+        self.failed
+    }
+}
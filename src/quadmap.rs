@@ -0,0 +1,105 @@
+use std::ops::Range;
+
+use crate::backend::AtlasBackend;
+use crate::error::TextError;
+use crate::{Text, TextMesh, TextWare};
+
+// Quads drawn for one shaped glyph. Byte ranges index the content joined with '\n'
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GlyphQuads {
+    pub bytes: Range<usize>,
+    pub quads: Range<usize>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct QuadMap {
+    glyphs: Vec<GlyphQuads>,
+}
+
+impl QuadMap {
+    pub fn glyphs(&self) -> &[GlyphQuads] {
+        &self.glyphs
+    }
+
+    // Quad ranges of every glyph overlapping `bytes`, adjacent ranges merged
+    pub fn quads(&self, bytes: Range<usize>) -> Vec<Range<usize>> {
+        let mut ranges: Vec<Range<usize>> = Vec::new();
+        for glyph in &self.glyphs {
+            if glyph.quads.is_empty() || glyph.bytes.start >= bytes.end || glyph.bytes.end <= bytes.start {
+                continue;
+            }
+            match ranges.iter_mut().find(|range| range.end == glyph.quads.start || range.start == glyph.quads.end) {
+                Some(range) => *range = range.start.min(glyph.quads.start)..range.end.max(glyph.quads.end),
+                None => ranges.push(glyph.quads.clone()),
+            }
+        }
+        ranges.sort_by_key(|range| range.start);
+        ranges
+    }
+}
+
+impl TextMesh {
+    pub fn set_quad_color(&mut self, quads: Range<usize>, color: [f32; 4]) {
+        let end = (quads.end * 4).min(self.vertices.len());
+        for vertex in self.vertices.get_mut((quads.start * 4).min(end)..end).unwrap_or_default() {
+            vertex.color = color;
+        }
+    }
+
+    // Zeroes the alpha, set_quad_color brings the quads back
+    pub fn hide_quads(&mut self, quads: Range<usize>) {
+        let end = (quads.end * 4).min(self.vertices.len());
+        for vertex in self.vertices.get_mut((quads.start * 4).min(end)..end).unwrap_or_default() {
+            vertex.color[3] = 0.0;
+        }
+    }
+}
+
+impl<B: AtlasBackend> TextWare<B> {
+    // Mesh plus the quads each byte range produced, so spans can be recolored or
+    // hidden by patching vertices instead of regenerating
+    pub fn generate_mapped_mesh(&mut self, text: &mut Text) -> Result<(TextMesh, QuadMap), TextError> {
+        let _span = trace_span!("textware::generate_mapped_mesh");
+
+        if !text.exclusions.is_empty() {
+            return Err(TextError::InvalidArgument("quad maps are not available for texts with exclusions".to_string()));
+        }
+
+        self.shape(text);
+
+        let mut line_starts = Vec::with_capacity(text.buffer.lines.len());
+        let mut start = 0;
+        for line in &text.buffer.lines {
+            line_starts.push(start);
+            start += line.text().len() + 1;
+        }
+
+        let glyph_bytes: Vec<Range<usize>> = text
+            .buffer
+            .layout_runs()
+            .flat_map(|run| {
+                let line_start = line_starts[run.line_i];
+                run.glyphs.iter().map(move |glyph| line_start + glyph.start..line_start + glyph.end)
+            })
+            .collect();
+
+        let mut mesh = TextMesh::with_capacity(text.glyph_count());
+        let mut quad_starts = Vec::with_capacity(glyph_bytes.len());
+        self.append_glyphs_with(text, [0.0, 0.0], &mut mesh, |glyph, _, _| {
+            quad_starts.push((glyph.index, glyph.quad));
+            true
+        })?;
+
+        let total = mesh.vertices.len() / 4;
+        let glyphs = quad_starts
+            .iter()
+            .enumerate()
+            .map(|(i, &(index, start))| GlyphQuads {
+                bytes: glyph_bytes[index].clone(),
+                quads: start..quad_starts.get(i + 1).map_or(total, |next| next.1),
+            })
+            .collect();
+
+        Ok((mesh, QuadMap { glyphs }))
+    }
+}
@@ -0,0 +1,400 @@
+use std::num::NonZeroU32;
+use crate::viewport::{EyeProjections, Viewport, ViewportBatch};
+use crate::{TextMesh, TextVertex, TextWare};
+
+const SHADER: &str = r#"
+@group(0) @binding(0) var t_atlas: texture_2d<f32>;
+@group(0) @binding(1) var s_atlas: sampler;
+
+struct VertexInput {
+    @location(0) position: vec3<f32>,
+    @location(1) uv: vec2<f32>,
+    @location(2) color: vec4<f32>,
+};
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+    @location(1) color: vec4<f32>,
+};
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let alpha = textureSample(t_atlas, s_atlas, in.uv).r;
+    return vec4<f32>(in.color.rgb, in.color.a * alpha);
+}
+
+@fragment
+fn fs_color(in: VertexOutput) -> @location(0) vec4<f32> {
+    let texel = textureSample(t_atlas, s_atlas, in.uv);
+    return vec4<f32>(texel.rgb, texel.a * in.color.a);
+}
+
+@fragment
+fn fs_sdf(in: VertexOutput) -> @location(0) vec4<f32> {
+    let distance = textureSample(t_atlas, s_atlas, in.uv).r;
+    let width = fwidth(distance) * 0.75;
+    let alpha = smoothstep(0.5 - width, 0.5 + width, distance);
+    return vec4<f32>(in.color.rgb, in.color.a * alpha);
+}
+"#;
+
+const VERTEX: &str = r#"
+struct Globals { projection: mat4x4<f32> };
+@group(1) @binding(0) var<uniform> globals: Globals;
+
+@vertex
+fn vs_main(in: VertexInput) -> VertexOutput {
+    var out: VertexOutput;
+    out.position = globals.projection * vec4<f32>(in.position, 1.0);
+    out.uv = in.uv;
+    out.color = in.color;
+    return out;
+}
+"#;
+
+// Same inputs and outputs, with one projection per eye
+const MULTIVIEW_VERTEX: &str = r#"
+struct Globals { views: array<mat4x4<f32>, 2> };
+@group(1) @binding(0) var<uniform> globals: Globals;
+
+@vertex
+fn vs_main(in: VertexInput, @builtin(view_index) view: i32) -> VertexOutput {
+    var out: VertexOutput;
+    out.position = globals.views[view] * vec4<f32>(in.position, 1.0);
+    out.uv = in.uv;
+    out.color = in.color;
+    return out;
+}
+"#;
+
+const PROJECTION_SIZE: u64 = std::mem::size_of::<[[f32; 4]; 4]>() as u64;
+const INITIAL_QUADS: u64 = 1024;
+
+// Pipeline and growable buffers for drawing TextMesh output with the atlas bind group.
+// A frame goes through three phases:
+//
+// 1. queue: meshes are copied into the renderer with their viewport, each returning a
+//    TextDraw handle
+// 2. prepare: every GPU write of the frame, the atlas uploads included, buffers growing to
+//    fit all queued draws at once
+// 3. render: records draws into a pass through &self, each in its viewport, so any number
+//    of passes can share the renderer without mutating it
+//
+// Handles render what was queued before the last prepare. The next prepare overwrites the
+// buffers, so it has to wait until the passes rendering the current ones are submitted.
+pub struct TextRenderer {
+    // Indexed by MeshKind
+    pipelines: [wgpu::RenderPipeline; 3],
+    globals_layout: wgpu::BindGroupLayout,
+    globals: Slab,
+    globals_bind_group: wgpu::BindGroup,
+    vertices: Slab,
+    indices: Slab,
+    alignment: u64,
+    // Projections per draw, 2 for a multiview renderer
+    views: u32,
+    queued: Vec<QueuedDraw>,
+    prepared: Vec<PreparedDraw>,
+    target_size: [u32; 2],
+    // Target the prepared projections were written for
+    prepared_target: [u32; 2],
+}
+
+// One queued mesh, returned by TextRenderer::queue
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TextDraw(usize);
+
+// What the atlas a mesh samples holds, which picks the fragment shader
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum MeshKind {
+    // generate_mesh output, drawn with get_bind_group
+    #[default]
+    Coverage,
+    // generate_color_mesh output, drawn with get_color_bind_group
+    Color,
+    // Meshes of distance field texts, thresholded at the outline
+    DistanceField,
+}
+
+struct QueuedDraw {
+    kind: MeshKind,
+    viewport: Viewport,
+    eyes: Option<EyeProjections>,
+    vertices: Vec<TextVertex>,
+    indices: Vec<u32>,
+}
+
+struct PreparedDraw {
+    kind: MeshKind,
+    viewport: Viewport,
+    globals_offset: u32,
+    vertices: std::ops::Range<u64>,
+    indices: std::ops::Range<u64>,
+    index_count: u32,
+}
+
+struct Slab {
+    buffer: wgpu::Buffer,
+    usage: wgpu::BufferUsages,
+}
+
+impl Slab {
+    fn new(device: &wgpu::Device, size: u64, usage: wgpu::BufferUsages) -> Self {
+        let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("textware renderer"),
+            size,
+            usage: usage | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        Self { buffer, usage }
+    }
+
+    // Replaces the buffer with one at least `size` bytes long, returning whether it did
+    fn reserve(&mut self, device: &wgpu::Device, size: u64) -> bool {
+        if size <= self.buffer.size() {
+            return false;
+        }
+        *self = Self::new(device, size.max(self.buffer.size() * 2), self.usage);
+        true
+    }
+}
+
+impl TextRenderer {
+    // `target_size` is the size of the texture the passes render into, see resize
+    pub fn new(device: &wgpu::Device, format: wgpu::TextureFormat, target_size: [u32; 2]) -> Self {
+        Self::with_views(device, format, target_size, 1)
+    }
+
+    // Renders into both layers of a two view target in one pass, for XR. The device needs
+    // wgpu::Features::MULTIVIEW and the pass a multiview target. Draws queued with queue_eyes
+    // use one projection per eye, other draws show the same in both.
+    pub fn new_multiview(device: &wgpu::Device, format: wgpu::TextureFormat, target_size: [u32; 2]) -> Self {
+        Self::with_views(device, format, target_size, 2)
+    }
+
+    fn with_views(device: &wgpu::Device, format: wgpu::TextureFormat, target_size: [u32; 2], views: u32) -> Self {
+        let vertex = if views > 1 { MULTIVIEW_VERTEX } else { VERTEX };
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("textware shader"),
+            source: wgpu::ShaderSource::Wgsl(format!("{}{}", SHADER, vertex).into()),
+        });
+        let globals_size = PROJECTION_SIZE * views as u64;
+
+        // Matches the layout of the atlas bind group, which wgpu treats as compatible
+        let atlas_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        multisampled: false,
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+            label: None,
+        });
+
+        let globals_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            entries: &[wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::VERTEX,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: true,
+                    min_binding_size: wgpu::BufferSize::new(globals_size),
+                },
+                count: None,
+            }],
+            label: None,
+        });
+
+        let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("textware pipeline layout"),
+            bind_group_layouts: &[&atlas_layout, &globals_layout],
+            push_constant_ranges: &[],
+        });
+
+        let multiview = NonZeroU32::new(views).filter(|views| views.get() > 1);
+        let pipelines = ["fs_main", "fs_color", "fs_sdf"].map(|entry_point| Self::pipeline(device, &layout, &shader, format, entry_point, multiview));
+
+        let alignment = device.limits().min_uniform_buffer_offset_alignment as u64;
+        let globals = Slab::new(device, alignment.max(globals_size) * 16, wgpu::BufferUsages::UNIFORM);
+        let globals_bind_group = Self::globals_bind_group(device, &globals_layout, &globals.buffer, globals_size);
+
+        Self {
+            pipelines,
+            globals_layout,
+            globals_bind_group,
+            globals,
+            vertices: Slab::new(device, INITIAL_QUADS * 4 * std::mem::size_of::<TextVertex>() as u64, wgpu::BufferUsages::VERTEX),
+            indices: Slab::new(device, INITIAL_QUADS * 6 * 4, wgpu::BufferUsages::INDEX),
+            alignment,
+            views,
+            queued: Vec::new(),
+            prepared: Vec::new(),
+            target_size,
+            prepared_target: target_size,
+        }
+    }
+
+    // Draws are placed and clipped against the target, so a resize takes effect on the next prepare
+    pub fn resize(&mut self, target_size: [u32; 2]) {
+        self.target_size = target_size;
+    }
+
+    fn pipeline(device: &wgpu::Device, layout: &wgpu::PipelineLayout, shader: &wgpu::ShaderModule, format: wgpu::TextureFormat, fragment: &str, multiview: Option<NonZeroU32>) -> wgpu::RenderPipeline {
+        device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("textware pipeline"),
+            layout: Some(layout),
+            vertex: wgpu::VertexState {
+                module: shader,
+                entry_point: Some("vs_main"),
+                compilation_options: Default::default(),
+                buffers: &[wgpu::VertexBufferLayout {
+                    array_stride: std::mem::size_of::<TextVertex>() as u64,
+                    step_mode: wgpu::VertexStepMode::Vertex,
+                    attributes: &wgpu::vertex_attr_array![0 => Float32x3, 1 => Float32x2, 2 => Float32x4],
+                }],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: shader,
+                entry_point: Some(fragment),
+                compilation_options: Default::default(),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format,
+                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+            }),
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            multiview,
+            cache: None,
+        })
+    }
+
+    fn globals_bind_group(device: &wgpu::Device, layout: &wgpu::BindGroupLayout, buffer: &wgpu::Buffer, size: u64) -> wgpu::BindGroup {
+        device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: wgpu::BindingResource::Buffer(wgpu::BufferBinding {
+                    buffer,
+                    offset: 0,
+                    size: wgpu::BufferSize::new(size),
+                }),
+            }],
+            label: None,
+        })
+    }
+
+    // Copies the mesh for the next prepare. It is drawn into the viewport's pane with its
+    // projection and clipped to the pane.
+    pub fn queue(&mut self, mesh: &TextMesh, viewport: &Viewport) -> TextDraw {
+        self.queue_kind(mesh, viewport, MeshKind::Coverage)
+    }
+
+    // Same as queue for meshes that sample the color atlas or a distance field, render
+    // then uses the matching shader. The bind group passed to render must match too.
+    pub fn queue_kind(&mut self, mesh: &TextMesh, viewport: &Viewport, kind: MeshKind) -> TextDraw {
+        self.queued.push(QueuedDraw { kind, viewport: *viewport, eyes: None, vertices: mesh.vertices.clone(), indices: mesh.indices.clone() });
+        TextDraw(self.queued.len() - 1)
+    }
+
+    // For multiview renderers, draws the mesh with a projection per eye in place of the
+    // viewport's, each mapping into the pane like custom_projection. Other renderers use
+    // the left eye.
+    pub fn queue_eyes(&mut self, mesh: &TextMesh, viewport: &Viewport, eyes: &EyeProjections, kind: MeshKind) -> TextDraw {
+        self.queued.push(QueuedDraw { kind, viewport: *viewport, eyes: Some(*eyes), vertices: mesh.vertices.clone(), indices: mesh.indices.clone() });
+        TextDraw(self.queued.len() - 1)
+    }
+
+    // Every mesh pushed to the batch as one draw
+    pub fn queue_batch(&mut self, batch: &ViewportBatch) -> TextDraw {
+        self.queue(&batch.mesh, &batch.viewport)
+    }
+
+    // Uploads the atlas' pending glyphs and writes every queued draw in one go, then starts a
+    // new queue. Returns whether anything was written, so idle frames can skip the submit.
+    pub fn prepare(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, textware: &mut TextWare) -> bool {
+        let mut uploaded = textware.prepare(queue);
+
+        let vertex_size = std::mem::size_of::<TextVertex>() as u64;
+        let globals_size = PROJECTION_SIZE * self.views as u64;
+        let globals_stride = globals_size.div_ceil(self.alignment) * self.alignment;
+
+        let mut prepared = Vec::with_capacity(self.queued.len());
+        let (mut globals_end, mut vertices_end, mut indices_end) = (0, 0, 0);
+        for draw in &self.queued {
+            let vertices = vertices_end..vertices_end + draw.vertices.len() as u64 * vertex_size;
+            // Padded to four bytes, which write_buffer requires
+            let indices = indices_end..indices_end + draw.indices.len() as u64 * 4;
+            prepared.push(PreparedDraw { kind: draw.kind, viewport: draw.viewport, globals_offset: globals_end as u32, vertices: vertices.clone(), indices: indices.clone(), index_count: draw.indices.len() as u32 });
+            globals_end += globals_stride;
+            vertices_end = vertices.end;
+            indices_end = indices.end;
+        }
+
+        if self.globals.reserve(device, globals_end) {
+            self.globals_bind_group = Self::globals_bind_group(device, &self.globals_layout, &self.globals.buffer, globals_size);
+        }
+        self.vertices.reserve(device, vertices_end);
+        self.indices.reserve(device, indices_end);
+
+        for (draw, slices) in self.queued.drain(..).zip(&prepared) {
+            if draw.indices.is_empty() {
+                continue;
+            }
+            let [width, height] = self.target_size;
+            let projections: Vec<_> = (0..self.views as usize)
+                .map(|view| {
+                    let projection = draw.eyes.map_or_else(|| draw.viewport.projection(), |eyes| eyes.views[view]);
+                    draw.viewport.place(projection, width, height)
+                })
+                .collect();
+            queue.write_buffer(&self.globals.buffer, slices.globals_offset as u64, bytemuck::cast_slice(&projections));
+            queue.write_buffer(&self.vertices.buffer, slices.vertices.start, bytemuck::cast_slice(&draw.vertices));
+            queue.write_buffer(&self.indices.buffer, slices.indices.start, bytemuck::cast_slice(&draw.indices));
+            uploaded = true;
+        }
+
+        self.prepared = prepared;
+        self.prepared_target = self.target_size;
+        uploaded
+    }
+
+    // Records the draw with the atlas bind group it was generated for, clipped to its pane.
+    // The pass viewport is set to the whole target and the scissor to the pane, both stay
+    // set for whatever the pass draws next. Handles that weren't prepared, whose mesh was
+    // empty or whose pane is off the target draw nothing.
+    pub fn render(&self, pass: &mut wgpu::RenderPass<'_>, atlas: &wgpu::BindGroup, draw: TextDraw) {
+        let Some(prepared) = self.prepared.get(draw.0).filter(|prepared| prepared.index_count > 0) else {
+            return;
+        };
+        let [width, height] = self.prepared_target;
+        let Some([x, y, scissor_width, scissor_height]) = prepared.viewport.scissor(width, height) else {
+            return;
+        };
+
+        pass.set_viewport(0.0, 0.0, width as f32, height as f32, 0.0, 1.0);
+        pass.set_scissor_rect(x, y, scissor_width, scissor_height);
+
+        pass.set_pipeline(&self.pipelines[prepared.kind as usize]);
+        pass.set_bind_group(0, atlas, &[]);
+        pass.set_bind_group(1, &self.globals_bind_group, &[prepared.globals_offset]);
+        pass.set_vertex_buffer(0, self.vertices.buffer.slice(prepared.vertices.clone()));
+        pass.set_index_buffer(self.indices.buffer.slice(prepared.indices.start..prepared.indices.start + prepared.index_count as u64 * 4), wgpu::IndexFormat::Uint32);
+        pass.draw_indexed(0..prepared.index_count, 0, 0..1);
+    }
+}
@@ -0,0 +1,78 @@
+use crate::backend::AtlasBackend;
+use crate::error::TextError;
+use crate::{Cursor, Text, TextMesh, TextWare};
+
+// Line breaks count as one character / byte, matching the content joined with '\n'
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RevealProgress {
+    Chars(usize),
+    Bytes(usize),
+}
+
+impl Text {
+    pub fn char_count(&self) -> usize {
+        let lines = &self.buffer.lines;
+        lines.iter().map(|line| line.text().chars().count()).sum::<usize>() + lines.len().saturating_sub(1)
+    }
+
+    // End of the revealed prefix, rounded down so a grapheme or ligature never shows in part
+    pub fn reveal_cursor(&self, progress: RevealProgress) -> Cursor {
+        let mut remaining = match progress {
+            RevealProgress::Chars(count) | RevealProgress::Bytes(count) => count,
+        };
+
+        for (line_i, line) in self.buffer.lines.iter().enumerate() {
+            let content = line.text();
+            let index = match progress {
+                RevealProgress::Chars(_) => content.char_indices().nth(remaining).map(|(i, _)| i),
+                RevealProgress::Bytes(_) => (remaining < content.len()).then_some(remaining),
+            };
+
+            let Some(mut index) = index else {
+                let length = match progress {
+                    RevealProgress::Chars(_) => content.chars().count(),
+                    RevealProgress::Bytes(_) => content.len(),
+                };
+                remaining -= length;
+                if remaining == 0 || line_i + 1 == self.buffer.lines.len() {
+                    return Cursor::new(line_i, content.len());
+                }
+                remaining -= 1;
+                continue;
+            };
+
+            while index > 0 && !self.is_cluster_boundary(Cursor::new(line_i, index)) {
+                index -= 1;
+            }
+            return Cursor::new(line_i, index);
+        }
+
+        Cursor::new(0, 0)
+    }
+}
+
+impl<B: AtlasBackend> TextWare<B> {
+    // Mesh of the revealed prefix only, e.g. a dialogue box typing itself out
+    pub fn generate_reveal_mesh(&mut self, text: &mut Text, progress: RevealProgress) -> Result<TextMesh, TextError> {
+        let _span = trace_span!("textware::generate_reveal_mesh");
+
+        self.shape(text);
+
+        let end = text.reveal_cursor(progress);
+        let revealed: Vec<bool> = text
+            .buffer
+            .layout_runs()
+            .flat_map(|run| {
+                run.glyphs
+                    .iter()
+                    .map(move |glyph| run.line_i < end.line || (run.line_i == end.line && glyph.end <= end.index))
+            })
+            .collect();
+
+        let mut mesh = TextMesh::with_capacity(revealed.iter().filter(|shown| **shown).count());
+        self.append_glyphs_with(text, [0.0, 0.0], &mut mesh, |glyph, _, _| revealed.get(glyph.index).copied().unwrap_or(false))?;
+
+        Ok(mesh)
+    }
+}
@@ -0,0 +1,173 @@
+use std::sync::Arc;
+
+use crate::backend::AtlasBackend;
+use crate::effects::SpanEffects;
+use crate::features::FontFeatures;
+use crate::shaping::ShapingMode;
+use crate::hover::SpanInteraction;
+use crate::filter::{self, INVISIBLE_METADATA};
+use crate::error::TextError;
+use crate::font::FontId;
+use crate::{Family, Text, TextWare, Weight};
+
+// Structured replacement for embedding bidi control characters in span text.
+// Isolates set the span's base direction, overrides force every character's.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SpanDirection {
+    Ltr,
+    Rtl,
+    OverrideLtr,
+    OverrideRtl,
+}
+
+impl SpanDirection {
+    fn controls(self) -> (&'static str, &'static str) {
+        match self {
+            SpanDirection::Ltr => ("\u{2066}", "\u{2069}"),
+            SpanDirection::Rtl => ("\u{2067}", "\u{2069}"),
+            SpanDirection::OverrideLtr => ("\u{202D}", "\u{202C}"),
+            SpanDirection::OverrideRtl => ("\u{202E}", "\u{202C}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct SpanStyle {
+    pub font_id: Option<FontId>,
+    pub weight: Option<u16>,
+    pub italic: bool,
+    pub monospace: bool,
+    // Drawn as solid quads under or through the glyphs, in the glyph color
+    pub underline: bool,
+    pub strikethrough: bool,
+    pub color: Option<[f32; 4]>,
+    pub effects: SpanEffects,
+    pub direction: Option<SpanDirection>,
+    pub features: Option<FontFeatures>,
+    pub interaction: Option<SpanInteraction>,
+    pub shaping: Option<ShapingMode>,
+    pub metadata: usize,
+}
+
+impl SpanStyle {
+    pub fn merge(&self, overrides: &SpanStyle) -> SpanStyle {
+        SpanStyle {
+            font_id: overrides.font_id.or(self.font_id),
+            weight: overrides.weight.or(self.weight),
+            italic: self.italic || overrides.italic,
+            monospace: self.monospace || overrides.monospace,
+            underline: self.underline || overrides.underline,
+            strikethrough: self.strikethrough || overrides.strikethrough,
+            color: overrides.color.or(self.color),
+            effects: self.effects | overrides.effects,
+            direction: overrides.direction.or(self.direction),
+            features: overrides.features.or(self.features),
+            interaction: overrides.interaction.or(self.interaction),
+            shaping: overrides.shaping.or(self.shaping),
+            metadata: if overrides.metadata != 0 { overrides.metadata } else { self.metadata },
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Span {
+    pub text: String,
+    pub style: SpanStyle,
+}
+
+impl Span {
+    pub fn new(text: impl Into<String>, style: SpanStyle) -> Self {
+        Self { text: text.into(), style }
+    }
+}
+
+impl<B: AtlasBackend> TextWare<B> {
+    pub fn create_rich_text(&mut self, spans: &[Span], font_id: Option<FontId>, font_size: f32, line_height: Option<f32>) -> Result<Text, TextError> {
+        let attrs = self.font_system.resolve_attrs(font_id);
+        let mut text = self.font_system.empty_text(font_id, attrs, font_size, line_height)?;
+        self.update_rich_text(&mut text, spans);
+        Ok(text)
+    }
+
+    pub fn update_rich_text(&mut self, text: &mut Text, spans: &[Span]) {
+        let _span = trace_span!("textware::update_rich_text", spans = spans.len());
+
+        let families: Vec<Option<Arc<str>>> = spans
+            .iter()
+            .map(|span| span.style.font_id.and_then(|id| self.font_system.get_family_name(id).cloned()))
+            .collect();
+
+        let default_attrs = text.attrs.as_attrs();
+
+        let input_filter = self.font_system.input_filter();
+        let show_invisibles = self.font_system.show_invisibles();
+        let contents: Vec<_> = spans
+            .iter()
+            .map(|span| input_filter.apply(&span.text))
+            .collect();
+
+        let rich = spans.iter().zip(families.iter()).zip(contents.iter()).enumerate().flat_map(|(index, ((span, family), content))| {
+            let style = &span.style;
+            let mut attrs = default_attrs.metadata(index);
+
+            if let Some(name) = family.as_ref() {
+                attrs = attrs.family(Family::Name(name));
+            } else if style.monospace {
+                attrs = attrs.family(Family::Monospace);
+            }
+            if let Some(weight) = style.weight {
+                attrs = attrs.weight(Weight(weight));
+            }
+            if style.italic {
+                attrs = attrs.style(cosmic_text::Style::Italic);
+            }
+            if let Some(color) = style.color {
+                attrs = attrs.color(to_cosmic_color(color));
+            }
+
+            let mut pieces = if show_invisibles {
+                filter::split_invisibles(content)
+            } else {
+                vec![(content.as_ref(), false)]
+            };
+
+            // Added after filtering so strip_bidi_controls only removes controls from the input
+            if let Some((open, close)) = style.direction.map(SpanDirection::controls) {
+                pieces.insert(0, (open, false));
+                pieces.push((close, false));
+            }
+
+            pieces
+                .into_iter()
+                .map(move |(piece, invisible)| (piece, if invisible { attrs.metadata(index | INVISIBLE_METADATA) } else { attrs }))
+        });
+
+        // Cosmic shapes whole lines one way, so a span asking for Advanced upgrades the text
+        let shaping = if spans.iter().any(|span| span.style.shaping == Some(ShapingMode::Advanced)) {
+            ShapingMode::Advanced
+        } else {
+            text.shaping
+        };
+        text.buffer.set_rich_text(&mut self.font_system.sys, rich, default_attrs, shaping.into());
+        text.spans = spans.iter().map(|span| span.style.clone()).collect();
+        text.dirty = true;
+    }
+}
+
+pub(crate) fn to_cosmic_color(color: [f32; 4]) -> cosmic_text::Color {
+    let channel = |v: f32| (v.clamp(0.0, 1.0) * 255.0).round() as u8;
+    cosmic_text::Color::rgba(channel(color[0]), channel(color[1]), channel(color[2]), channel(color[3]))
+}
+
+pub(crate) fn from_cosmic_color(color: cosmic_text::Color) -> [f32; 4] {
+    [
+        color.r() as f32 / 255.0,
+        color.g() as f32 / 255.0,
+        color.b() as f32 / 255.0,
+        color.a() as f32 / 255.0,
+    ]
+}
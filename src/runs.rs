@@ -0,0 +1,101 @@
+use std::ops::Range;
+use std::sync::Arc;
+use cosmic_text::fontdb;
+use crate::backend::AtlasBackend;
+use crate::font::FontId;
+use crate::filter::INVISIBLE_METADATA;
+use crate::types::GlyphFlags;
+use crate::{rich, Text, TextWare};
+
+// One shaped glyph, positions in layout pixels with y down
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShapedGlyph {
+    pub glyph_id: u16,
+    // Byte range of the glyph's cluster in its buffer line
+    pub cluster: Range<usize>,
+    pub font_size: f32,
+    // Pen position on the baseline, before the offsets
+    pub x: f32,
+    pub y: f32,
+    pub advance: f32,
+    // Shaper offsets from the pen position, already scaled to pixels and y down
+    pub x_offset: f32,
+    pub y_offset: f32,
+    pub color: [f32; 4],
+    // Index into the text's spans, None for plain text
+    pub span: Option<usize>,
+    pub flags: GlyphFlags,
+}
+
+// Consecutive glyphs of one layout line shaped by the same face in the same direction
+#[derive(Debug, Clone, PartialEq)]
+pub struct GlyphRun {
+    pub line: usize,
+    // Layout line the run sits on, counting wrapped lines
+    pub layout_line: usize,
+    pub face: fontdb::ID,
+    // Loaded font the face came from, None for system fonts
+    pub font_id: Option<FontId>,
+    pub rtl: bool,
+    pub baseline: f32,
+    pub line_top: f32,
+    // Glyphs in visual order, left to right
+    pub glyphs: Vec<ShapedGlyph>,
+}
+
+impl<B: AtlasBackend> TextWare<B> {
+    // Positioned glyphs of the shaped text, for renderers of their own such as path or SDF
+    // ones. Faces come from `font_face`, which hands out the same data the atlas rasterizes.
+    pub fn glyph_runs(&mut self, text: &mut Text) -> Vec<GlyphRun> {
+        let _span = trace_span!("textware::glyph_runs");
+
+        self.shape(text);
+
+        let mut runs: Vec<GlyphRun> = Vec::new();
+        for (layout_line, run) in text.buffer.layout_runs().enumerate() {
+            let run_start = runs.len();
+
+            for glyph in run.glyphs.iter() {
+                let span = glyph.metadata & !INVISIBLE_METADATA;
+                let shaped = ShapedGlyph {
+                    glyph_id: glyph.glyph_id,
+                    cluster: glyph.start..glyph.end,
+                    font_size: glyph.font_size,
+                    x: glyph.x,
+                    y: run.line_y + glyph.y,
+                    advance: glyph.w,
+                    x_offset: glyph.x_offset * glyph.font_size,
+                    y_offset: -glyph.y_offset * glyph.font_size,
+                    color: glyph.color_opt.map(rich::from_cosmic_color).unwrap_or(text.color),
+                    span: (span < text.spans.len()).then_some(span),
+                    flags: glyph.cache_key_flags.into(),
+                };
+
+                let rtl = glyph.level.is_rtl();
+                if let Some(current) = runs[run_start..].last_mut().filter(|current| current.face == glyph.font_id && current.rtl == rtl) {
+                    current.glyphs.push(shaped);
+                    continue;
+                }
+
+                runs.push(GlyphRun {
+                    line: run.line_i,
+                    layout_line,
+                    face: glyph.font_id,
+                    font_id: self.font_system.font_for_face(glyph.font_id),
+                    rtl,
+                    baseline: run.line_y,
+                    line_top: run.line_top,
+                    glyphs: vec![shaped],
+                });
+            }
+        }
+
+        runs
+    }
+
+    // Parsed face behind a run's `face`, with the raw font file in `data()` and swash and
+    // rustybuzz views for outlines and metrics
+    pub fn font_face(&mut self, face: fontdb::ID) -> Option<Arc<cosmic_text::Font>> {
+        self.font_system.sys.get_font(face)
+    }
+}
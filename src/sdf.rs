@@ -0,0 +1,153 @@
+use cosmic_text::{CacheKey, SubpixelBin};
+use swash::scale::image::{Content, Image as SwashImage};
+use crate::backend::AtlasBackend;
+use crate::error::TextError;
+use crate::{Text, TextWare};
+
+// Glyphs of a distance field text are rasterized once at `raster_size` and stored as signed
+// distances, so a single atlas entry stays sharp at any font size or zoom. The atlas value is
+// 0.5 on the outline, rising inside the glyph, and reaches 0 and 1 `spread` raster pixels out.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct DistanceField {
+    pub raster_size: f32,
+    pub spread: f32,
+}
+
+impl Default for DistanceField {
+    fn default() -> Self {
+        Self { raster_size: 64.0, spread: 8.0 }
+    }
+}
+
+impl DistanceField {
+    fn validate(&self) -> Result<(), TextError> {
+        if !(8.0..=256.0).contains(&self.raster_size) {
+            return Err(TextError::InvalidArgument(format!("distance field raster size {} is outside 8..=256", self.raster_size)));
+        }
+        if !(1.0..=32.0).contains(&self.spread) {
+            return Err(TextError::InvalidArgument(format!("distance field spread {} is outside 1..=32", self.spread)));
+        }
+        Ok(())
+    }
+
+    // Raster key shared by every size and subpixel position of the glyph, and how much its
+    // bitmap has to be stretched to the laid out size
+    pub(crate) fn key(&self, key: CacheKey) -> (CacheKey, f32) {
+        let size = f32::from_bits(key.font_size_bits);
+        let raster = CacheKey { font_size_bits: self.raster_size.to_bits(), x_bin: SubpixelBin::Zero, y_bin: SubpixelBin::Zero, ..key };
+        (raster, size / self.raster_size)
+    }
+}
+
+impl Text {
+    pub fn distance_field(&self) -> Option<DistanceField> {
+        self.distance_field
+    }
+}
+
+impl<B: AtlasBackend> TextWare<B> {
+    // Renders the text's glyphs as distance fields, for world space or zoomable text. Its
+    // meshes need a distance field shader, see the README; color glyphs, underlines and
+    // backgrounds stay usable with it. Hollow and emboldened strokes are not applied.
+    pub fn set_distance_field(&mut self, text: &mut Text, field: Option<DistanceField>) -> Result<(), TextError> {
+        if let Some(field) = &field {
+            field.validate()?;
+        }
+        text.distance_field = field;
+        text.mark_dirty();
+        Ok(())
+    }
+}
+
+// Squared 1D distance transform (Felzenszwalb and Huttenlocher) of `f` into `d`
+fn transform_1d(f: &[f32], d: &mut [f32], v: &mut [usize], z: &mut [f32]) {
+    let n = f.len();
+    let mut k = 0;
+    v[0] = 0;
+    z[0] = f32::NEG_INFINITY;
+    z[1] = f32::INFINITY;
+
+    for q in 1..n {
+        loop {
+            let p = v[k];
+            let s = ((f[q] + (q * q) as f32) - (f[p] + (p * p) as f32)) / (2.0 * (q as f32 - p as f32));
+            // z[0] is -inf, so k never drops below zero
+            if s <= z[k] {
+                k -= 1;
+                continue;
+            }
+            k += 1;
+            v[k] = q;
+            z[k] = s;
+            z[k + 1] = f32::INFINITY;
+            break;
+        }
+    }
+
+    k = 0;
+    for (q, out) in d.iter_mut().enumerate() {
+        while z[k + 1] < q as f32 {
+            k += 1;
+        }
+        let p = v[k];
+        *out = (q as f32 - p as f32).powi(2) + f[p];
+    }
+}
+
+// Squared distance of every texel to the nearest one where `seed` holds
+fn squared_distances(seed: &[bool], w: usize, h: usize) -> Vec<f32> {
+    let mut grid: Vec<f32> = seed.iter().map(|&inside| if inside { 0.0 } else { 1e20 }).collect();
+    let n = w.max(h);
+    let (mut f, mut d, mut v, mut z) = (vec![0.0; n], vec![0.0; n], vec![0; n], vec![0.0; n + 1]);
+
+    for x in 0..w {
+        for y in 0..h {
+            f[y] = grid[y * w + x];
+        }
+        transform_1d(&f[..h], &mut d[..h], &mut v, &mut z);
+        for y in 0..h {
+            grid[y * w + x] = d[y];
+        }
+    }
+    for row in grid.chunks_exact_mut(w) {
+        f[..w].copy_from_slice(row);
+        transform_1d(&f[..w], row, &mut v, &mut z);
+    }
+    grid
+}
+
+// Signed distance field of a coverage mask, grown by the spread on every side
+pub(crate) fn distance_field(image: &SwashImage, spread: f32) -> SwashImage {
+    let pad = spread.ceil() as usize;
+    let (w, h) = (image.placement.width as usize, image.placement.height as usize);
+    let (out_w, out_h) = (w + pad * 2, h + pad * 2);
+
+    let mut inside = vec![false; out_w * out_h];
+    for (row, line) in image.data.chunks_exact(w.max(1)).take(h).enumerate() {
+        for (column, &coverage) in line.iter().enumerate() {
+            inside[(row + pad) * out_w + column + pad] = coverage >= 128;
+        }
+    }
+    let outside: Vec<bool> = inside.iter().map(|inside| !inside).collect();
+
+    let to_inside = squared_distances(&inside, out_w, out_h);
+    let to_outside = squared_distances(&outside, out_w, out_h);
+
+    // Texel centers sit half a texel from the outline between an inside and an outside texel
+    let data = (0..out_w * out_h)
+        .map(|i| {
+            let distance = if inside[i] { to_outside[i].sqrt() - 0.5 } else { 0.5 - to_inside[i].sqrt() };
+            ((0.5 + distance / (2.0 * spread)).clamp(0.0, 1.0) * 255.0).round() as u8
+        })
+        .collect();
+
+    let mut placement = image.placement;
+    placement.left -= pad as i32;
+    placement.top += pad as i32;
+    placement.width = out_w as u32;
+    placement.height = out_h as u32;
+
+    SwashImage { source: image.source, content: Content::Mask, placement, data }
+}
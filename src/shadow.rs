@@ -0,0 +1,61 @@
+use crate::backend::AtlasBackend;
+use crate::error::TextError;
+use crate::{Text, TextMesh, TextWare};
+
+// Keeps blurred copies from eating the atlas, each one grows by the radius on every side
+pub(crate) const MAX_BLUR_RADIUS: f32 = 32.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct SoftShadow {
+    pub offset: [f32; 2],
+    // Blur radius in pixels, 0 draws a hard copy
+    pub radius: f32,
+    pub color: [f32; 4],
+}
+
+impl Default for SoftShadow {
+    fn default() -> Self {
+        Self {
+            offset: [2.0, 2.0],
+            radius: 3.0,
+            color: [0.0, 0.0, 0.0, 0.6],
+        }
+    }
+}
+
+impl<B: AtlasBackend> TextWare<B> {
+    // Shadow quads from blurred atlas copies of the glyphs, with the text on top
+    pub fn generate_shadowed_mesh(&mut self, text: &mut Text, shadow: &SoftShadow) -> Result<TextMesh, TextError> {
+        let _span = trace_span!("textware::generate_shadowed_mesh", radius = shadow.radius);
+
+        self.shape(text);
+
+        let mut mesh = TextMesh::with_capacity(text.glyph_count() * 2);
+        self.append_shadow(text, [0.0, 0.0], shadow, &mut mesh)?;
+        self.append_glyphs_with(text, [0.0, 0.0], &mut mesh, |_, _, _| true)?;
+
+        Ok(mesh)
+    }
+
+    pub(crate) fn append_shadow(&mut self, text: &Text, origin: [f32; 2], shadow: &SoftShadow, mesh: &mut TextMesh) -> Result<(), TextError> {
+        if !shadow.offset.iter().all(|v| v.is_finite()) || !(0.0..=MAX_BLUR_RADIUS).contains(&shadow.radius) {
+            return Err(TextError::InvalidArgument(format!("shadow radius {} is out of range", shadow.radius)));
+        }
+        if shadow.color[3] <= 0.0 {
+            return Ok(());
+        }
+
+        let offset = [origin[0] + shadow.offset[0], origin[1] + shadow.offset[1]];
+        if shadow.radius > 0.0 {
+            self.glyph_cache.append_blurred(&mut self.font_system, text, offset, mesh, shadow.radius, shadow.color)
+        } else {
+            let color = shadow.color;
+            self.append_glyphs_with(text, offset, mesh, |_, _, glyph_color| {
+                *glyph_color = color;
+                true
+            })
+        }
+    }
+}
@@ -0,0 +1,51 @@
+use crate::backend::AtlasBackend;
+use crate::{text_content, Text, TextWare};
+
+// Basic skips complex shaping, worth it for hot ASCII texts like frame counters.
+// Ligatures, kerning and complex scripts need Advanced.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ShapingMode {
+    Basic,
+    #[default]
+    Advanced,
+}
+
+impl From<ShapingMode> for cosmic_text::Shaping {
+    fn from(mode: ShapingMode) -> Self {
+        match mode {
+            ShapingMode::Basic => cosmic_text::Shaping::Basic,
+            ShapingMode::Advanced => cosmic_text::Shaping::Advanced,
+        }
+    }
+}
+
+impl From<cosmic_text::Shaping> for ShapingMode {
+    fn from(shaping: cosmic_text::Shaping) -> Self {
+        match shaping {
+            cosmic_text::Shaping::Basic => ShapingMode::Basic,
+            cosmic_text::Shaping::Advanced => ShapingMode::Advanced,
+        }
+    }
+}
+
+impl Text {
+    pub fn shaping(&self) -> ShapingMode {
+        self.shaping
+    }
+}
+
+impl<B: AtlasBackend> TextWare<B> {
+    // Plain texts are reshaped right away, rich texts on their next update_rich_text
+    pub fn set_shaping(&mut self, text: &mut Text, mode: ShapingMode) {
+        if text.shaping == mode {
+            return;
+        }
+        text.shaping = mode;
+
+        if text.spans.is_empty() {
+            let content = text_content(text);
+            self.update_text(text, &content);
+        }
+    }
+}
@@ -0,0 +1,55 @@
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+use crate::backend::{AtlasBackend, WgpuBackend};
+use crate::{Text, TextMesh, TextWare};
+
+pub struct SharedTextWare<B: AtlasBackend = WgpuBackend> {
+    inner: Arc<Mutex<TextWare<B>>>,
+}
+
+impl<B: AtlasBackend> Clone for SharedTextWare<B> {
+    fn clone(&self) -> Self {
+        Self { inner: Arc::clone(&self.inner) }
+    }
+}
+
+impl<B: AtlasBackend> SharedTextWare<B> {
+    pub fn new(textware: TextWare<B>) -> Self {
+        Self { inner: Arc::new(Mutex::new(textware)) }
+    }
+
+    pub fn lock(&self) -> MutexGuard<'_, TextWare<B>> {
+        self.inner.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    // Shapes the text so a later generate_mesh on the render thread only rasterizes
+    pub fn shape_text(&self, text: &mut Text) {
+        self.lock().shape(text);
+    }
+
+    pub fn generate_mesh(&self, text: &mut Text) -> TextMesh {
+        self.lock().generate_mesh(text)
+    }
+
+    pub fn prepare(&self, uploader: &B::Uploader) -> bool {
+        self.lock().prepare(uploader)
+    }
+}
+
+#[allow(dead_code)]
+fn assert_thread_safe() {
+    fn send<T: Send>() {}
+    fn send_sync<T: Send + Sync>() {}
+
+    send_sync::<Text>();
+    send_sync::<TextMesh>();
+
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        send::<TextWare>();
+        send_sync::<SharedTextWare>();
+    }
+
+    #[cfg(feature = "glow")]
+    send::<TextWare<crate::GlowBackend>>();
+}
@@ -0,0 +1,74 @@
+use crate::backend::AtlasBackend;
+use crate::error::TextError;
+use crate::{Text, TextMesh, TextWare};
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CoordinateSpace {
+    // Logical pixels, y down, quads snapped to whole pixels. For HUD and UI text.
+    #[default]
+    Screen,
+    // World units, y up and unsnapped, one layout pixel becoming `units_per_pixel` units
+    World { units_per_pixel: f32 },
+}
+
+impl Text {
+    pub fn coordinate_space(&self) -> CoordinateSpace {
+        self.space
+    }
+
+    pub fn scale_factor(&self) -> f32 {
+        self.scale_factor
+    }
+}
+
+impl<B: AtlasBackend> TextWare<B> {
+    pub fn set_coordinate_space(&mut self, text: &mut Text, space: CoordinateSpace) -> Result<(), TextError> {
+        if let CoordinateSpace::World { units_per_pixel } = space {
+            if !units_per_pixel.is_finite() || units_per_pixel <= 0.0 {
+                return Err(TextError::InvalidArgument(format!("units per pixel {} is not positive", units_per_pixel)));
+            }
+        }
+        if text.space != space {
+            text.space = space;
+            text.dirty = true;
+        }
+        Ok(())
+    }
+
+    // Rasterizes glyphs at `scale_factor` device pixels per layout pixel, e.g. 2.0 on a HiDPI
+    // display, while meshes stay in layout units for a logical projection
+    pub fn set_scale_factor(&mut self, text: &mut Text, scale_factor: f32) -> Result<(), TextError> {
+        if !scale_factor.is_finite() || scale_factor <= 0.0 {
+            return Err(TextError::InvalidArgument(format!("scale factor {} is not positive", scale_factor)));
+        }
+        if text.scale_factor != scale_factor {
+            text.scale_factor = scale_factor;
+            text.dirty = true;
+        }
+        Ok(())
+    }
+}
+
+// Converts the vertices appended from `first_vertex` on, layout always produces y-down pixels.
+// They come in at `scale` device pixels per layout pixel, Screen snapping to device pixels.
+pub(crate) fn apply_space(space: CoordinateSpace, mesh: &mut TextMesh, first_vertex: usize, first_index: usize, scale: f32) {
+    match space {
+        CoordinateSpace::Screen => {
+            for vertex in &mut mesh.vertices[first_vertex..] {
+                vertex.position[0] = vertex.position[0].round() / scale;
+                vertex.position[1] = vertex.position[1].round() / scale;
+            }
+        }
+        CoordinateSpace::World { units_per_pixel } => {
+            for vertex in &mut mesh.vertices[first_vertex..] {
+                vertex.position[0] *= units_per_pixel / scale;
+                vertex.position[1] *= -units_per_pixel / scale;
+            }
+            // Mirroring y flips the winding, swap back so face culling still sees front faces
+            for triangle in mesh.indices[first_index..].chunks_exact_mut(3) {
+                triangle.swap(1, 2);
+            }
+        }
+    }
+}
@@ -0,0 +1,48 @@
+use crate::backend::AtlasBackend;
+use crate::error::TextError;
+use crate::{Text, TextMesh, TextWare};
+
+// Heading pinned to the top of a scrolled view
+#[derive(Debug, Clone)]
+pub struct StickyHeader {
+    // Buffer line of the heading
+    pub line: usize,
+    // Top of the header in view space, below zero while the next heading pushes it out
+    pub y: f32,
+    // The heading's glyphs already at `y`, drawn over the scrolled text
+    pub mesh: TextMesh,
+}
+
+impl<B: AtlasBackend> TextWare<B> {
+    // `headings` are the buffer lines that stick, in any order, and `scroll` how far the text is
+    // scrolled up in layout pixels. None while the first heading is still below the top.
+    pub fn sticky_header(&mut self, text: &mut Text, headings: &[usize], scroll: f32) -> Result<Option<StickyHeader>, TextError> {
+        let _span = trace_span!("textware::sticky_header", scroll);
+
+        self.shape(text);
+
+        // Top and bottom of each heading, wrapped headings spanning several layout lines
+        let line_height = text.buffer.metrics().line_height;
+        let mut extents: Vec<(usize, f32, f32)> = Vec::with_capacity(headings.len());
+        for run in text.buffer.layout_runs().filter(|run| headings.contains(&run.line_i)) {
+            match extents.last_mut() {
+                Some((line, _, bottom)) if *line == run.line_i => *bottom = run.line_top + line_height,
+                _ => extents.push((run.line_i, run.line_top, run.line_top + line_height)),
+            }
+        }
+
+        let Some(current) = extents.iter().rposition(|(_, top, _)| *top <= scroll) else {
+            return Ok(None);
+        };
+        let (line, top, bottom) = extents[current];
+
+        let mut y = 0.0;
+        if let Some((_, next_top, _)) = extents.get(current + 1) {
+            y = (next_top - scroll - (bottom - top)).min(0.0);
+        }
+
+        let mut mesh = TextMesh::default();
+        self.append_glyphs_with(text, [0.0, y - top], &mut mesh, |glyph, _, _| glyph.line == line)?;
+        Ok(Some(StickyHeader { line, y, mesh }))
+    }
+}
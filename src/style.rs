@@ -0,0 +1,22 @@
+use crate::font::FontId;
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct TextStyle {
+    pub font_id: Option<FontId>,
+    pub font_size: f32,
+    pub line_height: Option<f32>,
+    pub color: [f32; 4],
+}
+
+impl Default for TextStyle {
+    fn default() -> Self {
+        Self {
+            font_id: None,
+            font_size: 16.0,
+            line_height: None,
+            color: [1.0, 1.0, 1.0, 1.0],
+        }
+    }
+}
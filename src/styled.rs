@@ -0,0 +1,44 @@
+use std::ops::Range;
+use crate::backend::AtlasBackend;
+use crate::error::TextError;
+use crate::font::FontId;
+use crate::rich::{Span, SpanStyle};
+use crate::{Text, TextWare};
+
+impl<B: AtlasBackend> TextWare<B> {
+    // Rich text from one string and styled byte ranges, e.g. from a syntax highlighter.
+    // Ranges must be ordered and not overlap; text between them gets the default style.
+    pub fn create_styled_text(&mut self, content: &str, ranges: &[(Range<usize>, SpanStyle)], font_id: Option<FontId>, font_size: f32, line_height: Option<f32>) -> Result<Text, TextError> {
+        let spans = styled_spans(content, ranges)?;
+        self.create_rich_text(&spans, font_id, font_size, line_height)
+    }
+
+    pub fn update_styled_text(&mut self, text: &mut Text, content: &str, ranges: &[(Range<usize>, SpanStyle)]) -> Result<(), TextError> {
+        let spans = styled_spans(content, ranges)?;
+        self.update_rich_text(text, &spans);
+        Ok(())
+    }
+}
+
+fn styled_spans(content: &str, ranges: &[(Range<usize>, SpanStyle)]) -> Result<Vec<Span>, TextError> {
+    let mut spans = Vec::with_capacity(ranges.len() * 2 + 1);
+    let mut cursor = 0;
+
+    for (range, style) in ranges {
+        if range.start < cursor || range.end < range.start || content.get(range.clone()).is_none() {
+            return Err(TextError::InvalidArgument(format!("style range {:?} is out of order or not on a character boundary", range)));
+        }
+        if range.start > cursor {
+            spans.push(Span::new(&content[cursor..range.start], SpanStyle::default()));
+        }
+        if !range.is_empty() {
+            spans.push(Span::new(&content[range.clone()], style.clone()));
+        }
+        cursor = range.end;
+    }
+
+    if cursor < content.len() || spans.is_empty() {
+        spans.push(Span::new(&content[cursor..], SpanStyle::default()));
+    }
+    Ok(spans)
+}
@@ -0,0 +1,124 @@
+use crate::backend::AtlasBackend;
+use crate::error::TextError;
+use crate::shadow::SoftShadow;
+use crate::style::TextStyle;
+use crate::{layout_bounds, Align, TextMesh, TextWare, Wrap};
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct SubtitleStyle {
+    pub text: TextStyle,
+    pub outline_color: [f32; 4],
+    pub outline_width: f32,
+    pub shadow_color: [f32; 4],
+    pub shadow_offset: [f32; 2],
+    // Blur radius of the shadow, 0 keeps it a hard copy
+    pub shadow_blur: f32,
+    pub background_color: [f32; 4],
+    pub background_padding: f32,
+    // Left, top, right, bottom insets as a fraction of the screen size
+    pub safe_area: [f32; 4],
+    // Maximum line width as a fraction of the safe area width
+    pub max_width: f32,
+}
+
+impl Default for SubtitleStyle {
+    fn default() -> Self {
+        Self {
+            text: TextStyle {
+                font_size: 32.0,
+                ..Default::default()
+            },
+            outline_color: [0.0, 0.0, 0.0, 1.0],
+            outline_width: 2.0,
+            shadow_color: [0.0, 0.0, 0.0, 0.6],
+            shadow_offset: [2.0, 2.0],
+            shadow_blur: 0.0,
+            background_color: [0.0, 0.0, 0.0, 0.0],
+            background_padding: 8.0,
+            safe_area: [0.05, 0.05, 0.05, 0.05],
+            max_width: 0.8,
+        }
+    }
+}
+
+impl<B: AtlasBackend> TextWare<B> {
+    pub fn generate_subtitle(&mut self, content: &str, style: &SubtitleStyle, screen_size: [f32; 2]) -> Result<TextMesh, TextError> {
+        let [screen_w, screen_h] = screen_size;
+        if !screen_w.is_finite() || !screen_h.is_finite() || screen_w < 0.0 || screen_h < 0.0 {
+            return Err(TextError::InvalidSize { width: screen_w, height: screen_h });
+        }
+
+        let finite = style.safe_area.iter().chain(style.shadow_offset.iter()).all(|v| v.is_finite())
+            && style.max_width.is_finite()
+            && style.outline_width.is_finite()
+            && style.background_padding.is_finite();
+        if !finite {
+            return Err(TextError::InvalidArgument("subtitle style contains a non-finite value".to_string()));
+        }
+        if !style.shadow_blur.is_finite() || style.shadow_blur < 0.0 {
+            return Err(TextError::InvalidArgument(format!("shadow blur {} is negative", style.shadow_blur)));
+        }
+        if style.max_width <= 0.0 {
+            return Err(TextError::InvalidArgument(format!("max width {} is not positive", style.max_width)));
+        }
+
+        let [inset_left, inset_top, inset_right, inset_bottom] = style.safe_area;
+
+        let safe_left = screen_w * inset_left;
+        let safe_width = (screen_w * (1.0 - inset_left - inset_right)).max(0.0);
+        let safe_top = screen_h * inset_top;
+        let safe_bottom = screen_h * (1.0 - inset_bottom);
+        let max_width = safe_width * style.max_width;
+
+        let mut text = self.create_text_styled(content, &style.text)?;
+        self.set_wrap(&mut text, Wrap::Word);
+        self.set_size(&mut text, Some(max_width), None)?;
+        for line in text.buffer.lines.iter_mut() {
+            line.set_align(Some(Align::Center));
+        }
+        self.shape(&mut text);
+
+        let mut mesh = TextMesh::default();
+
+        let Some([min_x, min_y, max_x, max_y]) = layout_bounds(&text) else {
+            return Ok(mesh);
+        };
+
+        let pad = style.background_padding;
+        let origin = [
+            safe_left + (safe_width - max_width) / 2.0,
+            // Bottom aligned, but text too tall for the safe area hangs from its top instead
+            (safe_bottom - pad - max_y).max(safe_top + pad - min_y),
+        ];
+
+        if style.background_color[3] > 0.0 {
+            let rect = [
+                origin[0] + min_x - pad,
+                origin[1] + min_y - pad,
+                max_x - min_x + pad * 2.0,
+                max_y - min_y + pad * 2.0,
+            ];
+            mesh.push_quad(rect, self.glyph_cache.solid_uv(), style.background_color);
+        }
+
+        if style.shadow_color[3] > 0.0 {
+            let shadow = SoftShadow { offset: style.shadow_offset, radius: style.shadow_blur, color: style.shadow_color };
+            if let Err(err) = self.append_shadow(&text, origin, &shadow, &mut mesh) {
+                log::debug!("textware: {}", err);
+            }
+        }
+
+        // A stroke twice the outline width, centered on the glyph outlines, under the fill
+        if style.outline_width > 0.0 && style.outline_color[3] > 0.0 {
+            text.hollow = Some((style.outline_width * 2.0 / style.text.font_size).min(0.25));
+            self.append_glyphs(&text, origin, Some(style.outline_color), &mut mesh);
+            text.hollow = None;
+        }
+
+        self.append_glyphs(&text, origin, None, &mut mesh);
+
+        Ok(mesh)
+    }
+}
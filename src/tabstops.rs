@@ -0,0 +1,104 @@
+use crate::backend::AtlasBackend;
+use crate::error::TextError;
+use crate::{Text, TextMesh, TextWare};
+
+// Tab separated cells of one layout run, as shaped
+struct RunCells {
+    starts: Vec<f32>,
+    widths: Vec<f32>,
+}
+
+impl<B: AtlasBackend> TextWare<B> {
+    // Elastic tabstops: a tab ends a cell, and cells in the same column across
+    // consecutive lines share the width of the widest one plus `gap`. A line with
+    // fewer tabs ends the column block, so separate tables align independently.
+    pub fn generate_elastic_mesh(&mut self, text: &mut Text, gap: f32) -> Result<TextMesh, TextError> {
+        let _span = trace_span!("textware::generate_elastic_mesh");
+
+        if !gap.is_finite() {
+            return Err(TextError::InvalidArgument(format!("tab gap {} is not finite", gap)));
+        }
+
+        self.shape(text);
+
+        let mut cells = Vec::new();
+        // Run and cell of every glyph in layout order, None drops the tab glyphs themselves
+        let mut glyph_cells: Vec<Option<(usize, usize)>> = Vec::with_capacity(text.glyph_count());
+
+        for (run_index, run) in text.buffer.layout_runs().enumerate() {
+            let tabs: Vec<usize> = run.text.match_indices('\t').map(|(i, _)| i).collect();
+            let count = tabs.len() + 1;
+            let mut starts = vec![f32::MAX; count];
+            let mut ends = vec![f32::MIN; count];
+
+            for glyph in run.glyphs.iter() {
+                let cell = tabs.partition_point(|&tab| tab < glyph.start);
+                if &run.text[glyph.start..glyph.end] == "\t" {
+                    if cell + 1 < count {
+                        starts[cell + 1] = starts[cell + 1].min(glyph.x + glyph.w);
+                    }
+                    glyph_cells.push(None);
+                } else {
+                    starts[cell] = starts[cell].min(glyph.x);
+                    ends[cell] = ends[cell].max(glyph.x + glyph.w);
+                    glyph_cells.push(Some((run_index, cell)));
+                }
+            }
+
+            let widths = starts.iter().zip(&ends).map(|(start, end)| (end - start).max(0.0)).collect();
+            for start in starts.iter_mut().filter(|start| **start == f32::MAX) {
+                *start = 0.0;
+            }
+            cells.push(RunCells { starts, widths });
+        }
+
+        // Column widths per run, only cells closed by a tab take part
+        let mut columns: Vec<Vec<f32>> = cells.iter().map(|run| vec![0.0; run.widths.len() - 1]).collect();
+        let deepest = columns.iter().map(Vec::len).max().unwrap_or(0);
+
+        for column in 0..deepest {
+            let mut run = 0;
+            while run < cells.len() {
+                if columns[run].len() <= column {
+                    run += 1;
+                    continue;
+                }
+
+                let block_start = run;
+                let mut width: f32 = 0.0;
+                while run < cells.len() && columns[run].len() > column {
+                    width = width.max(cells[run].widths[column]);
+                    run += 1;
+                }
+                for widths in &mut columns[block_start..run] {
+                    widths[column] = width + gap;
+                }
+            }
+        }
+
+        let shifts: Vec<Vec<f32>> = cells
+            .iter()
+            .zip(&columns)
+            .map(|(run, widths)| {
+                let mut x = run.starts[0];
+                let mut shifts = vec![0.0];
+                for (column, width) in widths.iter().enumerate() {
+                    x += width;
+                    shifts.push(x - run.starts[column + 1]);
+                }
+                shifts
+            })
+            .collect();
+
+        let mut mesh = TextMesh::with_capacity(glyph_cells.len());
+        self.append_glyphs_with(text, [0.0, 0.0], &mut mesh, |glyph, offset, _| match glyph_cells.get(glyph.index) {
+            Some(Some((run, cell))) => {
+                offset[0] += shifts[*run][*cell];
+                true
+            }
+            _ => false,
+        })?;
+
+        Ok(mesh)
+    }
+}
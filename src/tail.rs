@@ -0,0 +1,144 @@
+use std::ops::Range;
+use crate::backend::AtlasBackend;
+use crate::font::FontSystem;
+use crate::limit;
+use crate::{Text, TextWare};
+
+// Scroll offset for a log view that sticks to the newest line until the
+// user scrolls up, and sticks again once they scroll back to the bottom
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct TailFollow {
+    pub offset: f32,
+    pub following: bool,
+    // Distance from the bottom still counted as at the bottom
+    pub tolerance: f32,
+}
+
+impl Default for TailFollow {
+    fn default() -> Self {
+        Self {
+            offset: 0.0,
+            following: true,
+            tolerance: 1.0,
+        }
+    }
+}
+
+impl TailFollow {
+    pub fn scroll_to(&mut self, offset: f32, content_height: f32, view_height: f32) -> f32 {
+        let bottom = (content_height - view_height).max(0.0);
+        self.offset = offset.clamp(0.0, bottom);
+        self.following = bottom - self.offset <= self.tolerance;
+        self.offset
+    }
+
+    // Keeps a scrolled back view on the same lines after a buffer limit dropped some
+    pub fn content_dropped(&mut self, height: f32) {
+        if !self.following {
+            self.offset = (self.offset - height).max(0.0);
+        }
+    }
+
+    // Call after the content changed size
+    pub fn update(&mut self, content_height: f32, view_height: f32) -> f32 {
+        let bottom = (content_height - view_height).max(0.0);
+        self.offset = if self.following { bottom } else { self.offset.min(bottom) };
+        self.offset
+    }
+}
+
+impl Text {
+    // Height of the laid out lines, all of them once the text was shaped without a height limit
+    pub fn content_height(&self) -> f32 {
+        let lines: usize = self.buffer.lines.iter().map(|line| line.layout_opt().as_ref().map_or(0, |layout| layout.len())).sum();
+        lines as f32 * self.buffer.metrics().line_height
+    }
+}
+
+impl FontSystem {
+    // Extends the content without touching the shaped lines before the last one.
+    // Appended text takes the base style, the buffer limit is applied afterwards.
+    // Returns the lines that need shaping.
+    pub fn append_text(&mut self, text: &mut Text, more: &str) -> Range<usize> {
+        let _span = trace_span!("textware::append_text", bytes = more.len());
+
+        let more = self.input_filter().apply(more);
+        let last = text.buffer.lines.len().saturating_sub(1);
+        if more.is_empty() {
+            return last..last;
+        }
+
+        let mut scratch = cosmic_text::Buffer::new(&mut self.sys, text.buffer.metrics());
+        self.fill_buffer(&mut scratch, &more, text.attrs.as_attrs(), text.shaping);
+
+        let mut lines = std::mem::take(&mut scratch.lines).into_iter();
+        if let (Some(tail), Some(first)) = (text.buffer.lines.last_mut(), lines.next()) {
+            tail.append(first);
+        }
+        text.buffer.lines.extend(lines.map(|mut line| {
+            // Laid out again at the text's own width
+            line.reset_layout();
+            line
+        }));
+
+        text.dirty = true;
+        let dropped = limit::enforce_limit(text);
+        last.saturating_sub(dropped)..text.buffer.lines.len()
+    }
+}
+
+impl<B: AtlasBackend> TextWare<B> {
+    pub fn append_text(&mut self, text: &mut Text, more: &str) -> Range<usize> {
+        self.font_system.append_text(text, more)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BufferLimit;
+
+    #[test]
+    fn follows_until_scrolled_up() {
+        let mut follow = TailFollow::default();
+        assert_eq!(follow.update(500.0, 200.0), 300.0);
+
+        // Scrolled back, growth keeps the view where it is
+        assert_eq!(follow.scroll_to(100.0, 500.0, 200.0), 100.0);
+        assert!(!follow.following);
+        assert_eq!(follow.update(800.0, 200.0), 100.0);
+        follow.content_dropped(40.0);
+        assert_eq!(follow.offset, 60.0);
+
+        // Back within the tolerance of the bottom, it sticks again
+        follow.scroll_to(599.5, 800.0, 200.0);
+        assert!(follow.following);
+        assert_eq!(follow.update(900.0, 200.0), 700.0);
+        follow.content_dropped(40.0);
+        assert_eq!(follow.offset, 700.0);
+    }
+
+    #[test]
+    fn short_content_stays_at_the_top() {
+        let mut follow = TailFollow::default();
+        assert_eq!(follow.scroll_to(50.0, 100.0, 200.0), 0.0);
+        assert!(follow.following);
+        assert_eq!(follow.update(150.0, 200.0), 0.0);
+    }
+
+    #[test]
+    fn appends_extend_the_last_line() {
+        let mut fonts = FontSystem::new();
+        let mut text = fonts.create_text("one\ntw", None, 16.0, None).unwrap();
+        assert_eq!(fonts.append_text(&mut text, "o\nthree\nfour"), 1..4);
+        let lines: Vec<_> = text.buffer.lines.iter().map(|line| line.text()).collect();
+        assert_eq!(lines, ["one", "two", "three", "four"]);
+
+        text.set_buffer_limit(BufferLimit { max_lines: Some(2), max_bytes: None });
+        assert_eq!(fonts.append_text(&mut text, "!\nfive"), 0..2);
+        let lines: Vec<_> = text.buffer.lines.iter().map(|line| line.text()).collect();
+        assert_eq!(lines, ["four!", "five"]);
+    }
+}
@@ -0,0 +1,405 @@
+use std::collections::HashMap;
+use cosmic_text::{CacheKey, Style};
+use crate::backend::AtlasBackend;
+use crate::cache::GlyphKey;
+use crate::error::TextError;
+use crate::font::{validate_metrics, FontId};
+use crate::{Attrs, Family, Metrics, TextMesh, TextWare, Weight};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct CellFlags(pub u8);
+
+impl CellFlags {
+    pub const BOLD: CellFlags = CellFlags(1 << 0);
+    pub const ITALIC: CellFlags = CellFlags(1 << 1);
+    pub const UNDERLINE: CellFlags = CellFlags(1 << 2);
+    pub const INVERSE: CellFlags = CellFlags(1 << 3);
+    pub const WIDE: CellFlags = CellFlags(1 << 4);
+    pub const WIDE_SPACER: CellFlags = CellFlags(1 << 5);
+
+    pub fn contains(self, other: CellFlags) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl std::ops::BitOr for CellFlags {
+    type Output = CellFlags;
+
+    fn bitor(self, rhs: CellFlags) -> CellFlags {
+        CellFlags(self.0 | rhs.0)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Cell {
+    pub ch: char,
+    pub fg: [f32; 4],
+    pub bg: [f32; 4],
+    pub flags: CellFlags,
+}
+
+impl Default for Cell {
+    fn default() -> Self {
+        Self {
+            ch: ' ',
+            fg: [1.0, 1.0, 1.0, 1.0],
+            bg: [0.0, 0.0, 0.0, 0.0],
+            flags: CellFlags::default(),
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+struct GlyphTemplate {
+    key: CacheKey,
+    x: i32,
+    y: f32,
+}
+
+#[derive(Default)]
+struct RowMesh {
+    background: TextMesh,
+    foreground: TextMesh,
+}
+
+pub struct TerminalGrid {
+    cols: usize,
+    rows: usize,
+    cells: Vec<Cell>,
+    damaged: Vec<bool>,
+    row_meshes: Vec<RowMesh>,
+    // Atlas the row meshes' UVs were taken from
+    atlas_generation: u32,
+    font_id: Option<FontId>,
+    metrics: Metrics,
+    cell_width: f32,
+    templates: HashMap<(char, u8), Option<GlyphTemplate>>,
+    pub cursor: Option<(usize, usize)>,
+    pub cursor_color: [f32; 4],
+    pub selection: Option<((usize, usize), (usize, usize))>,
+    pub selection_color: [f32; 4],
+}
+
+impl TerminalGrid {
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cell_size(&self) -> (f32, f32) {
+        (self.cell_width, self.metrics.line_height)
+    }
+
+    pub fn cell(&self, col: usize, row: usize) -> Option<&Cell> {
+        if col < self.cols && row < self.rows {
+            self.cells.get(row * self.cols + col)
+        } else {
+            None
+        }
+    }
+
+    pub fn set_cell(&mut self, col: usize, row: usize, cell: Cell) {
+        if col >= self.cols || row >= self.rows {
+            return;
+        }
+
+        self.clear_wide_pair(col, row);
+
+        let mut cell = cell;
+        if is_wide(cell.ch) {
+            if col + 1 >= self.cols {
+                cell.ch = ' ';
+            } else {
+                self.clear_wide_pair(col + 1, row);
+                cell.flags = cell.flags | CellFlags::WIDE;
+                self.cells[row * self.cols + col + 1] = Cell {
+                    ch: ' ',
+                    flags: CellFlags::WIDE_SPACER,
+                    ..cell
+                };
+            }
+        }
+
+        self.cells[row * self.cols + col] = cell;
+        self.damaged[row] = true;
+    }
+
+    pub fn write_str(&mut self, col: usize, row: usize, content: &str, fg: [f32; 4], bg: [f32; 4], flags: CellFlags) -> usize {
+        let mut col = col;
+        for ch in content.chars() {
+            if col >= self.cols {
+                break;
+            }
+            self.set_cell(col, row, Cell { ch, fg, bg, flags });
+            col += if is_wide(ch) { 2 } else { 1 };
+        }
+        col
+    }
+
+    pub fn clear(&mut self) {
+        self.cells.fill(Cell::default());
+        self.damaged.fill(true);
+    }
+
+    pub fn clear_row(&mut self, row: usize) {
+        if row < self.rows {
+            self.cells[row * self.cols..(row + 1) * self.cols].fill(Cell::default());
+            self.damaged[row] = true;
+        }
+    }
+
+    pub fn resize(&mut self, cols: usize, rows: usize) {
+        let mut cells = vec![Cell::default(); cols * rows];
+        for row in 0..rows.min(self.rows) {
+            for col in 0..cols.min(self.cols) {
+                cells[row * cols + col] = self.cells[row * self.cols + col];
+            }
+        }
+
+        self.cols = cols;
+        self.rows = rows;
+        self.cells = cells;
+        self.damaged = vec![true; rows];
+        self.row_meshes.resize_with(rows, RowMesh::default);
+
+        for row in 0..rows {
+            if cols > 0 && self.cells[row * cols + cols - 1].flags.contains(CellFlags::WIDE) {
+                self.cells[row * cols + cols - 1] = Cell::default();
+            }
+        }
+    }
+
+    pub fn is_damaged(&self) -> bool {
+        self.damaged.iter().any(|d| *d)
+    }
+
+    pub fn damaged_rows(&self) -> impl Iterator<Item = usize> + '_ {
+        self.damaged.iter().enumerate().filter(|(_, d)| **d).map(|(row, _)| row)
+    }
+
+    fn clear_wide_pair(&mut self, col: usize, row: usize) {
+        let index = row * self.cols + col;
+        let flags = self.cells[index].flags;
+
+        if flags.contains(CellFlags::WIDE) && col + 1 < self.cols {
+            self.cells[index + 1] = Cell::default();
+        } else if flags.contains(CellFlags::WIDE_SPACER) && col > 0 {
+            self.cells[index - 1] = Cell::default();
+        }
+    }
+
+    fn is_selected(&self, col: usize, row: usize) -> bool {
+        let Some((start, end)) = self.selection else {
+            return false;
+        };
+
+        let (start, end) = if (start.1, start.0) <= (end.1, end.0) { (start, end) } else { (end, start) };
+        let pos = (row, col);
+        pos >= (start.1, start.0) && pos <= (end.1, end.0)
+    }
+}
+
+impl<B: AtlasBackend> TextWare<B> {
+    pub fn create_terminal_grid(&mut self, cols: usize, rows: usize, font_id: Option<FontId>, font_size: f32) -> Result<TerminalGrid, TextError> {
+        let metrics = validate_metrics(font_size, Some((font_size * 1.2).ceil()))?;
+
+        let mut grid = TerminalGrid {
+            cols,
+            rows,
+            cells: vec![Cell::default(); cols * rows],
+            damaged: vec![true; rows],
+            row_meshes: (0..rows).map(|_| RowMesh::default()).collect(),
+            atlas_generation: self.atlas_generation(),
+            font_id,
+            metrics,
+            cell_width: font_size * 0.6,
+            templates: HashMap::new(),
+            cursor: None,
+            cursor_color: [1.0, 1.0, 1.0, 0.8],
+            selection: None,
+            selection_color: [0.3, 0.5, 0.9, 0.5],
+        };
+
+        if let Some(width) = self.shape_cell_width(&grid) {
+            grid.cell_width = width;
+        }
+
+        Ok(grid)
+    }
+
+    pub fn generate_terminal_mesh(&mut self, grid: &mut TerminalGrid) -> Result<TextMesh, TextError> {
+        let _span = trace_span!("textware::generate_terminal_mesh");
+
+        // A glyph that grows or trims the atlas moves the UVs of every row built before it,
+        // so the rows are built again until the atlas holds still
+        loop {
+            let generation = self.atlas_generation();
+            if grid.atlas_generation != generation {
+                grid.damaged.fill(true);
+                grid.atlas_generation = generation;
+            }
+            for row in 0..grid.rows {
+                if grid.damaged[row] {
+                    grid.row_meshes[row] = self.build_terminal_row(grid, row)?;
+                    grid.damaged[row] = false;
+                }
+            }
+            if self.atlas_generation() == generation {
+                break;
+            }
+        }
+
+        let (cell_w, cell_h) = grid.cell_size();
+        let solid = self.glyph_cache.solid_uv();
+        let mut mesh = TextMesh::default();
+
+        for row_mesh in grid.row_meshes.iter() {
+            mesh.append(&row_mesh.background)?;
+        }
+
+        if grid.selection.is_some() {
+            for row in 0..grid.rows {
+                for col in 0..grid.cols {
+                    if grid.is_selected(col, row) {
+                        if !mesh.has_capacity(1) {
+                            return Err(TextError::IndexOverflow);
+                        }
+                        let rect = [col as f32 * cell_w, row as f32 * cell_h, cell_w, cell_h];
+                        mesh.push_quad(rect, solid, grid.selection_color);
+                    }
+                }
+            }
+        }
+
+        if let Some((col, row)) = grid.cursor {
+            if col < grid.cols && row < grid.rows {
+                let wide = grid.cells[row * grid.cols + col].flags.contains(CellFlags::WIDE);
+                let width = if wide { cell_w * 2.0 } else { cell_w };
+                if !mesh.has_capacity(1) {
+                    return Err(TextError::IndexOverflow);
+                }
+                mesh.push_quad([col as f32 * cell_w, row as f32 * cell_h, width, cell_h], solid, grid.cursor_color);
+            }
+        }
+
+        for row_mesh in grid.row_meshes.iter() {
+            mesh.append(&row_mesh.foreground)?;
+        }
+
+        Ok(mesh)
+    }
+
+    fn build_terminal_row(&mut self, grid: &mut TerminalGrid, row: usize) -> Result<RowMesh, TextError> {
+        let (cell_w, cell_h) = grid.cell_size();
+        let solid = self.glyph_cache.solid_uv();
+        let mut row_mesh = RowMesh::default();
+
+        for col in 0..grid.cols {
+            let cell = grid.cells[row * grid.cols + col];
+            if cell.flags.contains(CellFlags::WIDE_SPACER) {
+                continue;
+            }
+
+            if !row_mesh.background.has_capacity(1) || !row_mesh.foreground.has_capacity(2) {
+                return Err(TextError::IndexOverflow);
+            }
+
+            let (fg, bg) = if cell.flags.contains(CellFlags::INVERSE) { (cell.bg, cell.fg) } else { (cell.fg, cell.bg) };
+            let span = if cell.flags.contains(CellFlags::WIDE) { 2.0 } else { 1.0 };
+            let x = col as f32 * cell_w;
+            let y = row as f32 * cell_h;
+
+            if bg[3] > 0.0 {
+                row_mesh.background.push_quad([x, y, cell_w * span, cell_h], solid, bg);
+            }
+
+            if cell.flags.contains(CellFlags::UNDERLINE) {
+                let thickness = (grid.metrics.font_size / 14.0).max(1.0);
+                row_mesh.foreground.push_quad([x, y + cell_h - thickness * 2.0, cell_w * span, thickness], solid, fg);
+            }
+
+            if cell.ch == ' ' {
+                continue;
+            }
+
+            let Some(template) = self.glyph_template(grid, cell.ch, cell.flags) else {
+                continue;
+            };
+
+            if let Ok(Some((placement, uv_rect))) = self.glyph_cache.glyph_placement(GlyphKey::plain(template.key), &mut self.font_system) {
+                let gx = (x + template.x as f32 + placement.left as f32).round();
+                let gy = y + template.y - placement.top as f32;
+                let rect = [gx, gy, placement.width as f32, placement.height as f32];
+                row_mesh.foreground.push_quad(rect, uv_rect, fg);
+            }
+        }
+
+        Ok(row_mesh)
+    }
+
+    fn glyph_template(&mut self, grid: &mut TerminalGrid, ch: char, flags: CellFlags) -> Option<GlyphTemplate> {
+        let style = flags.0 & (CellFlags::BOLD.0 | CellFlags::ITALIC.0);
+        if let Some(template) = grid.templates.get(&(ch, style)) {
+            return *template;
+        }
+
+        let buffer = self.shape_cell(grid, ch, CellFlags(style));
+        let template = buffer.layout_runs().next().and_then(|run| {
+            let glyph = run.glyphs.first()?;
+            let physical = glyph.physical((0., 0.), 1.0);
+            Some(GlyphTemplate { key: physical.cache_key, x: physical.x, y: run.line_y + physical.y as f32 })
+        });
+
+        grid.templates.insert((ch, style), template);
+        template
+    }
+
+    fn shape_cell_width(&mut self, grid: &TerminalGrid) -> Option<f32> {
+        let buffer = self.shape_cell(grid, 'M', CellFlags::default());
+        let run = buffer.layout_runs().next()?;
+        run.glyphs.first().map(|glyph| glyph.w)
+    }
+
+    fn shape_cell(&mut self, grid: &TerminalGrid, ch: char, flags: CellFlags) -> cosmic_text::Buffer {
+        let family_name = grid.font_id.and_then(|id| self.font_system.get_family_name(id).cloned());
+
+        let mut attrs = Attrs::new().family(Family::Monospace);
+        if let Some(name) = family_name.as_ref() {
+            attrs = attrs.family(Family::Name(name));
+        }
+        if flags.contains(CellFlags::BOLD) {
+            attrs = attrs.weight(Weight::BOLD);
+        }
+        if flags.contains(CellFlags::ITALIC) {
+            attrs = attrs.style(Style::Italic);
+        }
+
+        let mut buffer = cosmic_text::Buffer::new(&mut self.font_system.sys, grid.metrics);
+        let mut content = [0u8; 4];
+        buffer.set_text(&mut self.font_system.sys, ch.encode_utf8(&mut content), attrs, cosmic_text::Shaping::Advanced);
+        buffer.shape_until_scroll(&mut self.font_system.sys, false);
+
+        buffer
+    }
+}
+
+pub(crate) fn is_wide(ch: char) -> bool {
+    matches!(ch as u32,
+        0x1100..=0x115F
+        | 0x2E80..=0x303E
+        | 0x3041..=0x33FF
+        | 0x3400..=0x4DBF
+        | 0x4E00..=0x9FFF
+        | 0xA000..=0xA4CF
+        | 0xAC00..=0xD7A3
+        | 0xF900..=0xFAFF
+        | 0xFE30..=0xFE4F
+        | 0xFF00..=0xFF60
+        | 0xFFE0..=0xFFE6
+        | 0x1F300..=0x1F64F
+        | 0x1F900..=0x1F9FF
+        | 0x20000..=0x3FFFD)
+}
@@ -0,0 +1,271 @@
+// Golden-image checks for layout and rasterization. Texts are drawn on the CPU from a
+// TextWare<CpuBackend>, so they run in CI without a GPU.
+use std::path::{Path, PathBuf};
+use crate::backend::AtlasBackend;
+use crate::error::TextError;
+use crate::{Text, TextMesh, TextWare};
+
+// Set to rewrite every reference with the current output instead of comparing
+pub const UPDATE_ENV: &str = "TEXTWARE_UPDATE_GOLDEN";
+
+// Mask atlas kept in memory, uploads just copy into it
+pub struct CpuBackend {
+    size: u32,
+    max_size: u32,
+    pixels: Vec<u8>,
+}
+
+impl CpuBackend {
+    pub fn new(size: u32) -> Self {
+        Self { size, max_size: 8192, pixels: vec![0; (size * size) as usize] }
+    }
+
+    pub fn pixels(&self) -> &[u8] {
+        &self.pixels
+    }
+
+    fn sample(&self, u: f32, v: f32) -> u8 {
+        let x = ((u * self.size as f32) as u32).min(self.size - 1);
+        let y = ((v * self.size as f32) as u32).min(self.size - 1);
+        self.pixels[(y * self.size + x) as usize]
+    }
+}
+
+impl AtlasBackend for CpuBackend {
+    type Uploader = ();
+
+    fn size(&self) -> u32 {
+        self.size
+    }
+
+    fn write_region(&mut self, _: &(), x: u32, y: u32, width: u32, height: u32, data: &[u8]) {
+        for row in 0..height {
+            let src = (row * width) as usize;
+            let dst = ((y + row) * self.size + x) as usize;
+            self.pixels[dst..dst + width as usize].copy_from_slice(&data[src..src + width as usize]);
+        }
+    }
+
+    fn max_size(&self) -> u32 {
+        self.max_size
+    }
+
+    fn grow(&mut self, _: &(), size: u32) {
+        self.pixels = vec![0; (size * size) as usize];
+        self.size = size;
+    }
+}
+
+// Straight alpha RGBA8
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GoldenImage {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u8>,
+}
+
+impl GoldenImage {
+    pub fn new(width: u32, height: u32) -> Self {
+        Self { width, height, pixels: vec![0; (width * height * 4) as usize] }
+    }
+
+    pub fn to_png(&self) -> Result<Vec<u8>, TextError> {
+        let mut out = Vec::new();
+        let mut encoder = png::Encoder::new(&mut out, self.width, self.height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header().map_err(png_error)?;
+        writer.write_image_data(&self.pixels).map_err(png_error)?;
+        writer.finish().map_err(png_error)?;
+        Ok(out)
+    }
+
+    // Any PNG, converted to 8-bit RGBA
+    pub fn from_png(data: &[u8]) -> Result<Self, TextError> {
+        let mut decoder = png::Decoder::new(data);
+        decoder.set_transformations(png::Transformations::normalize_to_color8());
+        let mut reader = decoder.read_info().map_err(png_error)?;
+        let mut buffer = vec![0; reader.output_buffer_size()];
+        let frame = reader.next_frame(&mut buffer).map_err(png_error)?;
+        let data = &buffer[..frame.buffer_size()];
+
+        let pixels = match frame.color_type {
+            png::ColorType::Rgba => data.to_vec(),
+            png::ColorType::Rgb => data.chunks_exact(3).flat_map(|p| [p[0], p[1], p[2], 255]).collect(),
+            png::ColorType::GrayscaleAlpha => data.chunks_exact(2).flat_map(|p| [p[0], p[0], p[0], p[1]]).collect(),
+            png::ColorType::Grayscale => data.iter().flat_map(|&v| [v, v, v, 255]).collect(),
+            // Expanded to Rgb or Rgba by the transformations
+            png::ColorType::Indexed => return Err(png_error("unexpanded palette")),
+        };
+        Ok(Self { width: frame.width, height: frame.height, pixels })
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, TextError> {
+        Self::from_png(&std::fs::read(path)?)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), TextError> {
+        if let Some(dir) = path.as_ref().parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, self.to_png()?)?;
+        Ok(())
+    }
+
+    // Alpha blends the mesh's quads with nearest sampling. Quads are taken as axis aligned,
+    // which holds for everything generate_mesh emits.
+    pub fn draw_mesh(&mut self, mesh: &TextMesh, atlas: &CpuBackend) {
+        for quad in mesh.vertices.chunks_exact(4) {
+            let ([x0, y0, _], [x1, y1, _]) = (quad[0].position, quad[2].position);
+            let ([u0, v0], [u1, v1]) = (quad[0].uv, quad[2].uv);
+            let color = quad[0].color;
+            if x1 <= x0 || y1 <= y0 {
+                continue;
+            }
+
+            let columns = x0.max(0.0).round() as u32..(x1.round().max(0.0) as u32).min(self.width);
+            for y in y0.max(0.0).round() as u32..(y1.round().max(0.0) as u32).min(self.height) {
+                let v = v0 + (v1 - v0) * ((y as f32 + 0.5 - y0) / (y1 - y0));
+                for x in columns.clone() {
+                    let u = u0 + (u1 - u0) * ((x as f32 + 0.5 - x0) / (x1 - x0));
+                    let coverage = atlas.sample(u, v) as f32 / 255.0 * color[3];
+                    self.blend(x, y, color, coverage);
+                }
+            }
+        }
+    }
+
+    fn blend(&mut self, x: u32, y: u32, color: [f32; 4], coverage: f32) {
+        if coverage <= 0.0 {
+            return;
+        }
+        let pixel = &mut self.pixels[((y * self.width + x) * 4) as usize..][..4];
+        let dst_alpha = pixel[3] as f32 / 255.0;
+        let alpha = coverage + dst_alpha * (1.0 - coverage);
+        for channel in 0..3 {
+            let dst = pixel[channel] as f32 / 255.0 * dst_alpha;
+            let blended = (color[channel].clamp(0.0, 1.0) * coverage + dst * (1.0 - coverage)) / alpha;
+            pixel[channel] = (blended * 255.0).round() as u8;
+        }
+        pixel[3] = (alpha * 255.0).round() as u8;
+    }
+
+    // Difference per pixel as the larger of the luma and alpha changes, so slight hue shifts
+    // in anti-aliased edges count less than missing or moved glyphs
+    pub fn compare(&self, reference: &GoldenImage, tolerance: Tolerance) -> GoldenDiff {
+        if self.width != reference.width || self.height != reference.height {
+            let pixels = (self.width.max(reference.width) * self.height.max(reference.height)) as usize;
+            return GoldenDiff { mismatched: pixels, pixels, max_difference: 255, size_changed: true, passed: false };
+        }
+
+        let premultiplied = |px: &[u8]| -> [f32; 4] {
+            let alpha = px[3] as f32;
+            [px[0] as f32 * alpha / 255.0, px[1] as f32 * alpha / 255.0, px[2] as f32 * alpha / 255.0, alpha]
+        };
+
+        let mut mismatched = 0;
+        let mut max_difference = 0u8;
+        for (a, b) in self.pixels.chunks_exact(4).zip(reference.pixels.chunks_exact(4)) {
+            let (a, b) = (premultiplied(a), premultiplied(b));
+            let luma = 0.299 * (a[0] - b[0]) + 0.587 * (a[1] - b[1]) + 0.114 * (a[2] - b[2]);
+            let difference = luma.abs().max((a[3] - b[3]).abs()).round() as u8;
+            max_difference = max_difference.max(difference);
+            if difference > tolerance.threshold {
+                mismatched += 1;
+            }
+        }
+
+        let pixels = (self.width * self.height) as usize;
+        let passed = mismatched as f32 <= tolerance.max_mismatch * pixels as f32;
+        GoldenDiff { mismatched, pixels, max_difference, size_changed: false, passed }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct Tolerance {
+    // Per pixel difference, out of 255, that is still a match
+    pub threshold: u8,
+    // Fraction of pixels allowed over the threshold
+    pub max_mismatch: f32,
+}
+
+impl Default for Tolerance {
+    fn default() -> Self {
+        Self { threshold: 24, max_mismatch: 0.001 }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GoldenDiff {
+    pub mismatched: usize,
+    pub pixels: usize,
+    pub max_difference: u8,
+    pub size_changed: bool,
+    pub passed: bool,
+}
+
+impl TextWare<CpuBackend> {
+    // Draws the text with its layout origin at the top left corner, the image sized to fit
+    // the layout and any glyphs overhanging it
+    pub fn render_golden(&mut self, text: &mut Text) -> GoldenImage {
+        let mesh = self.generate_mesh(text);
+        let metrics = self.measure(text);
+        self.prepare(&());
+
+        let (mut width, mut height) = (metrics.width, metrics.height);
+        for vertex in &mesh.vertices {
+            width = width.max(vertex.position[0]);
+            height = height.max(vertex.position[1]);
+        }
+
+        let mut image = GoldenImage::new(width.ceil().max(1.0) as u32, height.ceil().max(1.0) as u32);
+        image.draw_mesh(&mesh, self.glyph_cache.backend());
+        image
+    }
+
+    // Compares against the PNG at `reference`, writing it instead when it is missing or
+    // UPDATE_ENV is set. On a mismatch the actual image lands next to it as *.actual.png.
+    pub fn check_golden(&mut self, text: &mut Text, reference: impl AsRef<Path>, tolerance: Tolerance) -> Result<GoldenDiff, TextError> {
+        let image = self.render_golden(text);
+        let reference = reference.as_ref();
+
+        if std::env::var_os(UPDATE_ENV).is_some() || !reference.exists() {
+            image.save(reference)?;
+            return Ok(GoldenDiff { mismatched: 0, pixels: image.pixels.len() / 4, max_difference: 0, size_changed: false, passed: true });
+        }
+
+        let diff = image.compare(&GoldenImage::load(reference)?, tolerance);
+        if !diff.passed {
+            image.save(actual_path(reference))?;
+        }
+        Ok(diff)
+    }
+
+    // Panicking check_golden for tests
+    pub fn assert_golden(&mut self, text: &mut Text, reference: impl AsRef<Path>, tolerance: Tolerance) {
+        let reference = reference.as_ref();
+        match self.check_golden(text, reference, tolerance) {
+            Ok(diff) if diff.passed => {}
+            Ok(diff) => panic!(
+                "{} differs from the rendered text: {} of {} pixels over the threshold, max difference {}{}; see {}",
+                reference.display(),
+                diff.mismatched,
+                diff.pixels,
+                diff.max_difference,
+                if diff.size_changed { ", size changed" } else { "" },
+                actual_path(reference).display(),
+            ),
+            Err(err) => panic!("golden check of {} failed: {}", reference.display(), err),
+        }
+    }
+}
+
+fn png_error(err: impl std::fmt::Display) -> TextError {
+    TextError::Io(std::io::Error::new(std::io::ErrorKind::InvalidData, format!("png: {}", err)))
+}
+
+fn actual_path(reference: &Path) -> PathBuf {
+    reference.with_extension("actual.png")
+}
@@ -0,0 +1,124 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use crate::backend::AtlasBackend;
+use crate::error::TextError;
+use crate::style::TextStyle;
+use crate::{Text, TextWare};
+
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StyleRegistry {
+    styles: HashMap<String, TextStyle>,
+}
+
+impl StyleRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, name: impl Into<String>, style: TextStyle) -> Option<TextStyle> {
+        self.styles.insert(name.into(), style)
+    }
+
+    pub fn get(&self, name: &str) -> Option<&TextStyle> {
+        self.styles.get(name)
+    }
+
+    pub fn remove(&mut self, name: &str) -> Option<TextStyle> {
+        self.styles.remove(name)
+    }
+
+    pub fn len(&self) -> usize {
+        self.styles.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.styles.is_empty()
+    }
+}
+
+// Texts remember the registry generation their style was applied from and catch up
+// the next time they are shaped, so a theme swap never has to visit every Text
+#[derive(Default)]
+pub(crate) struct Theme {
+    registry: StyleRegistry,
+    generation: u64,
+}
+
+impl Text {
+    pub fn style_name(&self) -> Option<&str> {
+        self.style_name.as_deref()
+    }
+}
+
+impl<B: AtlasBackend> TextWare<B> {
+    pub fn styles(&self) -> &StyleRegistry {
+        &self.theme.registry
+    }
+
+    // Swaps every named style at once, e.g. for a dark/light switch. Texts using
+    // them pick up the new font, size and color the next time they are drawn.
+    pub fn set_theme(&mut self, registry: StyleRegistry) {
+        self.theme.registry = registry;
+        self.theme.generation += 1;
+    }
+
+    pub fn create_text_named(&mut self, content: &str, style_name: &str) -> Result<Text, TextError> {
+        let style = self.named_style(style_name)?.clone();
+        let mut text = self.create_text_styled(content, &style)?;
+        text.style_name = Some(Arc::from(style_name));
+        text.style_generation = self.theme.generation;
+        Ok(text)
+    }
+
+    // None detaches the text, which keeps its current look
+    pub fn set_text_style(&mut self, text: &mut Text, style_name: Option<&str>) -> Result<(), TextError> {
+        match style_name {
+            Some(name) => {
+                let style = self.named_style(name)?.clone();
+                self.apply_style(text, &style)?;
+                text.style_name = Some(Arc::from(name));
+                text.style_generation = self.theme.generation;
+            }
+            None => text.style_name = None,
+        }
+        Ok(())
+    }
+
+    pub(crate) fn refresh_style(&mut self, text: &mut Text) {
+        if text.style_generation == self.theme.generation {
+            return;
+        }
+        text.style_generation = self.theme.generation;
+
+        let Some(style) = text.style_name.as_deref().and_then(|name| self.theme.registry.get(name)).cloned() else {
+            return;
+        };
+        if let Err(err) = self.apply_style(text, &style) {
+            log::debug!("textware: {}", err);
+        }
+    }
+
+    fn named_style(&self, name: &str) -> Result<&TextStyle, TextError> {
+        self.theme
+            .registry
+            .get(name)
+            .ok_or_else(|| TextError::InvalidArgument(format!("no style named {:?}", name)))
+    }
+
+    // Rich texts keep their span fonts, only size and color follow the style
+    fn apply_style(&mut self, text: &mut Text, style: &TextStyle) -> Result<(), TextError> {
+        if text.font_id != style.font_id && text.spans.is_empty() {
+            self.set_font(text, style.font_id)?;
+        }
+
+        let metrics = text.buffer.metrics();
+        let line_height = style.line_height.unwrap_or(style.font_size * 1.2);
+        if metrics.font_size != style.font_size || metrics.line_height != line_height {
+            self.resize_text(text, style.font_size, style.line_height)?;
+        }
+
+        text.color = style.color;
+        Ok(())
+    }
+}
@@ -0,0 +1,27 @@
+#[cfg(feature = "tracing")]
+macro_rules! trace_span {
+    ($($args:tt)*) => {
+        tracing::trace_span!($($args)*).entered()
+    };
+}
+
+#[cfg(not(feature = "tracing"))]
+macro_rules! trace_span {
+    ($($args:tt)*) => {
+        ()
+    };
+}
+
+#[cfg(feature = "tracing")]
+macro_rules! trace_event {
+    ($($args:tt)*) => {
+        tracing::trace!($($args)*)
+    };
+}
+
+#[cfg(not(feature = "tracing"))]
+macro_rules! trace_event {
+    ($($args:tt)*) => {
+        ()
+    };
+}
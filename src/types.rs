@@ -0,0 +1,101 @@
+// Own copies of the cosmic-text types that show up in user code, so callers don't need
+// cosmic-text as a direct dependency pinned to the same version
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FontStyle {
+    #[default]
+    Normal,
+    Italic,
+    Oblique,
+}
+
+impl From<FontStyle> for cosmic_text::Style {
+    fn from(style: FontStyle) -> Self {
+        match style {
+            FontStyle::Normal => cosmic_text::Style::Normal,
+            FontStyle::Italic => cosmic_text::Style::Italic,
+            FontStyle::Oblique => cosmic_text::Style::Oblique,
+        }
+    }
+}
+
+impl From<cosmic_text::Style> for FontStyle {
+    fn from(style: cosmic_text::Style) -> Self {
+        match style {
+            cosmic_text::Style::Normal => FontStyle::Normal,
+            cosmic_text::Style::Italic => FontStyle::Italic,
+            cosmic_text::Style::Oblique => FontStyle::Oblique,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FontStretch {
+    UltraCondensed,
+    ExtraCondensed,
+    Condensed,
+    SemiCondensed,
+    #[default]
+    Normal,
+    SemiExpanded,
+    Expanded,
+    ExtraExpanded,
+    UltraExpanded,
+}
+
+impl From<FontStretch> for cosmic_text::Stretch {
+    fn from(stretch: FontStretch) -> Self {
+        match stretch {
+            FontStretch::UltraCondensed => cosmic_text::Stretch::UltraCondensed,
+            FontStretch::ExtraCondensed => cosmic_text::Stretch::ExtraCondensed,
+            FontStretch::Condensed => cosmic_text::Stretch::Condensed,
+            FontStretch::SemiCondensed => cosmic_text::Stretch::SemiCondensed,
+            FontStretch::Normal => cosmic_text::Stretch::Normal,
+            FontStretch::SemiExpanded => cosmic_text::Stretch::SemiExpanded,
+            FontStretch::Expanded => cosmic_text::Stretch::Expanded,
+            FontStretch::ExtraExpanded => cosmic_text::Stretch::ExtraExpanded,
+            FontStretch::UltraExpanded => cosmic_text::Stretch::UltraExpanded,
+        }
+    }
+}
+
+impl From<cosmic_text::Stretch> for FontStretch {
+    fn from(stretch: cosmic_text::Stretch) -> Self {
+        match stretch {
+            cosmic_text::Stretch::UltraCondensed => FontStretch::UltraCondensed,
+            cosmic_text::Stretch::ExtraCondensed => FontStretch::ExtraCondensed,
+            cosmic_text::Stretch::Condensed => FontStretch::Condensed,
+            cosmic_text::Stretch::SemiCondensed => FontStretch::SemiCondensed,
+            cosmic_text::Stretch::Normal => FontStretch::Normal,
+            cosmic_text::Stretch::SemiExpanded => FontStretch::SemiExpanded,
+            cosmic_text::Stretch::Expanded => FontStretch::Expanded,
+            cosmic_text::Stretch::ExtraExpanded => FontStretch::ExtraExpanded,
+            cosmic_text::Stretch::UltraExpanded => FontStretch::UltraExpanded,
+        }
+    }
+}
+
+// Rasterization flags of a glyph key
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct GlyphFlags {
+    // Skewed to stand in for a missing italic face
+    pub fake_italic: bool,
+}
+
+impl From<GlyphFlags> for cosmic_text::CacheKeyFlags {
+    fn from(flags: GlyphFlags) -> Self {
+        let mut bits = cosmic_text::CacheKeyFlags::empty();
+        bits.set(cosmic_text::CacheKeyFlags::FAKE_ITALIC, flags.fake_italic);
+        bits
+    }
+}
+
+impl From<cosmic_text::CacheKeyFlags> for GlyphFlags {
+    fn from(flags: cosmic_text::CacheKeyFlags) -> Self {
+        Self { fake_italic: flags.contains(cosmic_text::CacheKeyFlags::FAKE_ITALIC) }
+    }
+}
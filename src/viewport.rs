@@ -0,0 +1,170 @@
+use crate::error::TextError;
+use crate::TextMesh;
+
+// A pane of the render target with its own projection and scissor. Every viewport
+// samples the same atlas, so panes share one TextWare and one bind group.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Viewport {
+    // Pane rectangle in target pixels
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+    // Text space position shown at the pane's top left corner
+    pub scroll: [f32; 2],
+    // Column major matrix used instead of the pixel projection, e.g. a camera for world space text
+    pub custom_projection: Option<[[f32; 4]; 4]>,
+}
+
+impl Viewport {
+    pub fn new(x: f32, y: f32, width: f32, height: f32) -> Self {
+        Self { x, y, width, height, scroll: [0.0, 0.0], custom_projection: None }
+    }
+
+    // Column major orthographic projection from text pixels (y down) to clip space
+    pub fn projection(&self) -> [[f32; 4]; 4] {
+        if let Some(projection) = self.custom_projection {
+            return projection;
+        }
+        let sx = 2.0 / self.width.max(1.0);
+        let sy = -2.0 / self.height.max(1.0);
+        [
+            [sx, 0.0, 0.0, 0.0],
+            [0.0, sy, 0.0, 0.0],
+            [0.0, 0.0, 1.0, 0.0],
+            [-1.0 - self.scroll[0] * sx, 1.0 - self.scroll[1] * sy, 0.0, 1.0],
+        ]
+    }
+
+    // Projection into the whole target, placing the pane itself, for passes that keep the
+    // viewport on the full target and only move the scissor. Panes may hang off the target.
+    pub fn target_projection(&self, target_width: u32, target_height: u32) -> [[f32; 4]; 4] {
+        self.place(self.projection(), target_width, target_height)
+    }
+
+    // Moves a projection into pane clip space over to target clip space
+    pub(crate) fn place(&self, projection: [[f32; 4]; 4], target_width: u32, target_height: u32) -> [[f32; 4]; 4] {
+        let (target_width, target_height) = (target_width.max(1) as f32, target_height.max(1) as f32);
+        let (width, height) = (self.width.max(1.0), self.height.max(1.0));
+        // Pane clip space to target clip space
+        let pane = [
+            [width / target_width, 0.0, 0.0, 0.0],
+            [0.0, height / target_height, 0.0, 0.0],
+            [0.0, 0.0, 1.0, 0.0],
+            [(2.0 * self.x + width) / target_width - 1.0, 1.0 - (2.0 * self.y + height) / target_height, 0.0, 1.0],
+        ];
+        mul(pane, projection)
+    }
+
+    // Pane clamped to the target as [x, y, width, height], None when nothing is visible
+    pub fn scissor(&self, target_width: u32, target_height: u32) -> Option<[u32; 4]> {
+        let left = self.x.max(0.0).floor() as u32;
+        let top = self.y.max(0.0).floor() as u32;
+        let right = ((self.x + self.width).ceil().max(0.0) as u32).min(target_width);
+        let bottom = ((self.y + self.height).ceil().max(0.0) as u32).min(target_height);
+
+        (right > left && bottom > top).then(|| [left, top, right - left, bottom - top])
+    }
+
+    // Sets viewport and scissor on the pass, false means the pane is off target and can be skipped.
+    // wgpu rejects viewports past the target's edges, panes that may hang off it should keep a
+    // full target viewport with target_projection instead
+    pub fn apply(&self, pass: &mut wgpu::RenderPass<'_>, target_width: u32, target_height: u32) -> bool {
+        let Some([x, y, width, height]) = self.scissor(target_width, target_height) else {
+            return false;
+        };
+
+        pass.set_viewport(self.x, self.y, self.width, self.height, 0.0, 1.0);
+        pass.set_scissor_rect(x, y, width, height);
+        true
+    }
+}
+
+// Meshes collected for one viewport, queued with TextRenderer::queue_batch as a single draw
+pub struct ViewportBatch {
+    pub viewport: Viewport,
+    pub mesh: TextMesh,
+}
+
+impl ViewportBatch {
+    pub fn new(viewport: Viewport) -> Self {
+        Self { viewport, mesh: TextMesh::default() }
+    }
+
+    pub fn push(&mut self, mesh: &TextMesh) -> Result<(), TextError> {
+        self.mesh.append(mesh)
+    }
+
+    pub fn clear(&mut self) {
+        self.mesh.vertices.clear();
+        self.mesh.indices.clear();
+    }
+}
+
+// Per-eye view-projection matrices for multiview passes, laid out as a uniform
+// buffer the vertex shader indexes with @builtin(view_index)
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct EyeProjections {
+    pub views: [[[f32; 4]; 4]; 2],
+}
+
+impl EyeProjections {
+    pub fn new(left: [[f32; 4]; 4], right: [[f32; 4]; 4]) -> Self {
+        Self { views: [left, right] }
+    }
+
+    // Bakes a model transform into both eyes, e.g. one that places HUD text pixels on a panel in view space
+    pub fn with_model(left: [[f32; 4]; 4], right: [[f32; 4]; 4], model: [[f32; 4]; 4]) -> Self {
+        Self { views: [mul(left, model), mul(right, model)] }
+    }
+}
+
+// Column major a * b
+fn mul(a: [[f32; 4]; 4], b: [[f32; 4]; 4]) -> [[f32; 4]; 4] {
+    let mut out = [[0.0; 4]; 4];
+    for (column, b_column) in out.iter_mut().zip(b.iter()) {
+        for (row, value) in column.iter_mut().enumerate() {
+            *value = (0..4).map(|k| a[k][row] * b_column[k]).sum();
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Clip space position of a text space point, rounded off float noise
+    fn clip(matrix: [[f32; 4]; 4], [x, y]: [f32; 2]) -> [f32; 2] {
+        [0, 1].map(|row| ((matrix[0][row] * x + matrix[1][row] * y + matrix[3][row]) * 1e4).round() / 1e4)
+    }
+
+    #[test]
+    fn scissor_clamps_to_the_target() {
+        assert_eq!(Viewport::new(10.0, 20.0, 100.0, 50.0).scissor(800, 600), Some([10, 20, 100, 50]));
+        assert_eq!(Viewport::new(-30.0, 580.0, 100.0, 50.0).scissor(800, 600), Some([0, 580, 70, 20]));
+        // Fractional edges round outwards
+        assert_eq!(Viewport::new(10.5, 0.0, 9.2, 10.0).scissor(800, 600), Some([10, 0, 10, 10]));
+        assert_eq!(Viewport::new(800.0, 0.0, 100.0, 50.0).scissor(800, 600), None);
+        assert_eq!(Viewport::new(-200.0, 0.0, 100.0, 50.0).scissor(800, 600), None);
+    }
+
+    #[test]
+    fn target_projection_places_the_pane() {
+        let mut pane = Viewport::new(100.0, 50.0, 200.0, 100.0);
+        pane.scroll = [10.0, 0.0];
+        assert_eq!(clip(pane.projection(), [10.0, 0.0]), [-1.0, 1.0]);
+
+        // The scrolled corner lands on the pane's corner, a quarter into a 400 x 200 target
+        let projection = pane.target_projection(400, 200);
+        assert_eq!(clip(projection, [10.0, 0.0]), [-0.5, 0.5]);
+        assert_eq!(clip(projection, [210.0, 100.0]), [0.5, -0.5]);
+
+        // Custom projections are placed the same way
+        let identity = [[1.0, 0.0, 0.0, 0.0], [0.0, 1.0, 0.0, 0.0], [0.0, 0.0, 1.0, 0.0], [0.0, 0.0, 0.0, 1.0]];
+        pane.custom_projection = Some(identity);
+        assert_eq!(clip(pane.target_projection(400, 200), [-1.0, 1.0]), [-0.5, 0.5]);
+    }
+}
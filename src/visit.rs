@@ -0,0 +1,59 @@
+use std::ops::Range;
+use crate::backend::AtlasBackend;
+use crate::cache::{QuadSink, Raster};
+use crate::error::TextError;
+use crate::{Text, TextMesh, TextWare, UvRect};
+
+// One glyph bitmap in the text's layout space, y down and unsnapped
+#[derive(Debug, Clone, PartialEq)]
+pub struct GlyphQuad {
+    pub rect: [f32; 4],
+    pub uv: UvRect,
+    pub color: [f32; 4],
+    // Buffer line and layout run the glyph sits on
+    pub line: usize,
+    pub run: usize,
+    // Byte range of the glyph's cluster in the buffer line
+    pub cluster: Range<usize>,
+    // Glyph index across the whole text
+    pub index: usize,
+    pub font_size: f32,
+}
+
+struct Visitor<F> {
+    visit: F,
+    count: usize,
+    // Scale factor of the text, quads come in device pixels
+    scale: f32,
+}
+
+impl<F: FnMut(GlyphQuad)> QuadSink for Visitor<F> {
+    fn quad_count(&self) -> usize {
+        self.count
+    }
+
+    fn decorations(&mut self) -> Option<&mut TextMesh> {
+        None
+    }
+
+    fn push_glyph(&mut self, mut quad: GlyphQuad) -> Result<(), TextError> {
+        self.count += 1;
+        quad.rect = quad.rect.map(|v| v / self.scale);
+        quad.font_size /= self.scale;
+        (self.visit)(quad);
+        Ok(())
+    }
+}
+
+impl<B: AtlasBackend> TextWare<B> {
+    // Hands every glyph quad to `visit` instead of building a mesh, for engines
+    // filling their own vertex buffers. Underlines and placeholder frames are left out.
+    pub fn for_each_glyph<F: FnMut(GlyphQuad)>(&mut self, text: &mut Text, visit: F) -> Result<(), TextError> {
+        let _span = trace_span!("textware::for_each_glyph");
+
+        self.shape(text);
+
+        let mut visitor = Visitor { visit, count: 0, scale: text.scale_factor };
+        self.glyph_cache.append_layout_glyphs(&mut self.font_system, text, [0.0, 0.0], &mut visitor, Raster { scale: text.scale_factor, ..Raster::PLAIN }, |_, _, _| true)
+    }
+}
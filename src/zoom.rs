@@ -0,0 +1,159 @@
+use crate::backend::AtlasBackend;
+use crate::cache::GlyphKey;
+use crate::error::TextError;
+use crate::features;
+use crate::{Text, TextMesh, TextWare};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct ZoomPolicy {
+    // Quads are stretched until zoom / raster scale leaves this range
+    pub upscale_threshold: f32,
+    pub downscale_threshold: f32,
+    // Frames the zoom has to hold still before a re-raster is asked for, so pinch gestures don't thrash the atlas
+    pub settle_frames: u32,
+    // Relative zoom change that still counts as holding still
+    pub settle_tolerance: f32,
+    // Glyphs rasterized per warm_glyphs call
+    pub glyphs_per_frame: usize,
+}
+
+impl Default for ZoomPolicy {
+    fn default() -> Self {
+        Self {
+            upscale_threshold: 1.3,
+            downscale_threshold: 0.7,
+            settle_frames: 6,
+            settle_tolerance: 0.02,
+            glyphs_per_frame: 64,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ZoomAction {
+    // Keep the current glyphs, drawing quads scaled by this factor
+    Scale(f32),
+    // Glyphs should be rasterized at `raster_scale`. Keep drawing with `quad_scale`
+    // while warm_glyphs catches up, then commit the new scale.
+    Rerasterize { raster_scale: f32, quad_scale: f32 },
+}
+
+#[derive(Debug, Clone)]
+pub struct ZoomController {
+    policy: ZoomPolicy,
+    raster_scale: f32,
+    candidate: f32,
+    stable_frames: u32,
+}
+
+impl ZoomController {
+    pub fn new(policy: ZoomPolicy) -> Self {
+        Self {
+            policy,
+            raster_scale: 1.0,
+            candidate: 1.0,
+            stable_frames: 0,
+        }
+    }
+
+    pub fn policy(&self) -> &ZoomPolicy {
+        &self.policy
+    }
+
+    pub fn raster_scale(&self) -> f32 {
+        self.raster_scale
+    }
+
+    // Call once per frame with the canvas zoom
+    pub fn update(&mut self, zoom: f32) -> ZoomAction {
+        if !zoom.is_finite() || zoom <= 0.0 {
+            return ZoomAction::Scale(1.0);
+        }
+
+        let quad_scale = zoom / self.raster_scale;
+        if (self.policy.downscale_threshold..=self.policy.upscale_threshold).contains(&quad_scale) {
+            self.stable_frames = 0;
+            return ZoomAction::Scale(quad_scale);
+        }
+
+        if ((zoom - self.candidate) / self.candidate).abs() <= self.policy.settle_tolerance {
+            self.stable_frames = self.stable_frames.saturating_add(1);
+        } else {
+            self.candidate = zoom;
+            self.stable_frames = 0;
+        }
+
+        if self.stable_frames >= self.policy.settle_frames {
+            ZoomAction::Rerasterize { raster_scale: zoom, quad_scale }
+        } else {
+            ZoomAction::Scale(quad_scale)
+        }
+    }
+
+    // Switches to glyphs rasterized at `raster_scale`, once they are warm
+    pub fn commit(&mut self, raster_scale: f32) {
+        if raster_scale.is_finite() && raster_scale > 0.0 {
+            self.raster_scale = raster_scale;
+            self.candidate = raster_scale;
+            self.stable_frames = 0;
+        }
+    }
+}
+
+impl<B: AtlasBackend> TextWare<B> {
+    // Rasterizes up to `budget` of the text's glyphs at `scale` ahead of a zoom switch,
+    // spreading the work over frames. Returns how many are still missing, always none for
+    // distance field texts, whose glyphs serve every scale.
+    pub fn warm_glyphs(&mut self, text: &mut Text, scale: f32, budget: usize) -> usize {
+        let _span = trace_span!("textware::warm_glyphs", scale, budget);
+
+        if text.distance_field.is_some() {
+            return 0;
+        }
+
+        self.shape(text);
+
+        let mut keys: Vec<_> = text.buffer
+            .layout_runs()
+            .flat_map(|run| run.glyphs.iter())
+            .map(|glyph| GlyphKey::new(self.glyph_cache.bucketed(glyph.physical((0.0, 0.0), scale).cache_key).0, features::glyph_features(text, glyph)))
+            .filter(|key| !self.glyph_cache.contains(key))
+            .collect();
+        keys.sort_unstable_by_key(|key| {
+            let key = key.cache_key;
+            (key.font_id, key.glyph_id, key.font_size_bits, key.x_bin as u8, key.y_bin as u8)
+        });
+        keys.dedup();
+
+        let done = keys.len().min(budget);
+        for key in keys.drain(..done) {
+            if let Err(err) = self.glyph_cache.glyph_placement(key, &mut self.font_system) {
+                log::debug!("textware: {}", err);
+            }
+        }
+
+        keys.len()
+    }
+
+    // Mesh at `zoom` times the size with glyphs rasterized for it, leaving the text's
+    // metrics alone. Pairs with warm_glyphs and ZoomAction::Rerasterize. Distance field
+    // texts keep their glyphs and only get larger quads, snapped at the zoomed size.
+    pub fn generate_mesh_zoomed(&mut self, text: &mut Text, zoom: f32) -> Result<TextMesh, TextError> {
+        let _span = trace_span!("textware::generate_mesh_zoomed", zoom);
+
+        if !zoom.is_finite() || zoom <= 0.0 {
+            return Err(TextError::InvalidArgument(format!("zoom must be positive, got {}", zoom)));
+        }
+        if !text.exclusions.is_empty() {
+            return Err(TextError::InvalidArgument("zoomed meshes are not available for texts with exclusions".to_string()));
+        }
+
+        self.shape(text);
+
+        let mut mesh = TextMesh::with_capacity(text.glyph_count());
+        self.glyph_cache.append_scaled(&mut self.font_system, text, zoom, &mut mesh, |_, _, _| true)?;
+        Ok(mesh)
+    }
+}
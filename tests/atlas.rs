@@ -0,0 +1,71 @@
+#![cfg(feature = "testing")]
+
+use textware::testing::CpuBackend;
+use textware::{CacheConfig, CellFlags, TextMesh, TextWare};
+
+#[test]
+fn uvs_match_the_atlas_after_growing_mid_text() {
+    // Deterministic keeps the parallel pre-pass from growing the atlas before the glyph loop
+    let config = CacheConfig { deterministic: true, ..Default::default() };
+    let mut textware = TextWare::with_backend_config(CpuBackend::new(64), config);
+    let mut text = textware.create_text("ABCDEFGHIJKLMNOPQRSTUVWXYZ abcdefghijklmnopqrstuvwxyz 0123456789", None, 24.0, None).unwrap();
+
+    let generation = textware.atlas_generation();
+    let mesh = textware.generate_mesh(&mut text);
+    let atlas_size = (textware.memory_report().atlas_bytes as f32).sqrt();
+    assert!(textware.atlas_generation() > generation);
+    assert!(atlas_size > 64.0);
+
+    // Quads from before and after the grow must sample exactly their own bitmap
+    assert!(mesh.vertices.len() >= 40 * 4);
+    assert_uvs_match(&mesh, atlas_size);
+}
+
+#[test]
+fn terminal_rows_follow_the_atlas() {
+    let mut textware = TextWare::with_backend(CpuBackend::new(64));
+    let mut grid = textware.create_terminal_grid(40, 2, None, 24.0).unwrap();
+    let clear = [0.0; 4];
+    grid.write_str(0, 0, "ABCDEFGHIJKLMNOPQRSTUVWXYZ", [1.0; 4], clear, CellFlags::default());
+    grid.write_str(0, 1, "abcdefghijklmnopqrstuvwxyz", [1.0; 4], clear, CellFlags::default());
+
+    // The atlas grows while the rows are built
+    let generation = textware.atlas_generation();
+    let mesh = textware.generate_terminal_mesh(&mut grid).unwrap();
+    assert!(textware.atlas_generation() > generation);
+    assert_uvs_match(&mesh, (textware.memory_report().atlas_bytes as f32).sqrt());
+
+    // And again from another text, with no cell touched
+    let generation = textware.atlas_generation();
+    let mut text = textware.create_text("0123456789 !?#$%&@ ÀÉÎÕÜ àéîõü", None, 48.0, None).unwrap();
+    textware.generate_mesh(&mut text);
+    assert!(textware.atlas_generation() > generation);
+    assert!(!grid.is_damaged());
+    let mesh = textware.generate_terminal_mesh(&mut grid).unwrap();
+    assert_uvs_match(&mesh, (textware.memory_report().atlas_bytes as f32).sqrt());
+}
+
+#[test]
+fn grown_atlas_keeps_earlier_glyphs() {
+    let coverage = |size: u32| {
+        let mut textware = TextWare::with_backend(CpuBackend::new(size));
+        let mut first = textware.create_text("ABCDEFGHIJKLM", None, 24.0, None).unwrap();
+        textware.render_golden(&mut first);
+        // Uploaded before the grow, the second text pushes the atlas past its size
+        let mut second = textware.create_text("NOPQRSTUVWXYZ abcdefghijklmnopqrstuvwxyz", None, 24.0, None).unwrap();
+        textware.render_golden(&mut second);
+        textware.render_golden(&mut first).pixels.chunks_exact(4).map(|pixel| pixel[3] as u64).sum::<u64>()
+    };
+    assert_eq!(coverage(128), coverage(1024));
+}
+
+fn assert_uvs_match(mesh: &TextMesh, atlas_size: f32) {
+    let extent = |values: [f32; 4]| values.iter().copied().fold(f32::MIN, f32::max) - values.iter().copied().fold(f32::MAX, f32::min);
+    for quad in mesh.vertices.chunks_exact(4) {
+        for axis in 0..2 {
+            let size = extent(std::array::from_fn(|i| quad[i].position[axis]));
+            let uv_size = extent(std::array::from_fn(|i| quad[i].uv[axis])) * atlas_size;
+            assert!((size - uv_size).abs() < 0.01, "quad {size}px wide samples {uv_size} atlas pixels");
+        }
+    }
+}
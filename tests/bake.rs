@@ -0,0 +1,43 @@
+#![cfg(feature = "testing")]
+
+use textware::testing::CpuBackend;
+use textware::{BakedMesh, CacheConfig, TextVertex, TextWare};
+
+// Texels a quad samples, row by row
+fn texels(quad: &[TextVertex], pixels: &[u8], width: u32, height: u32) -> Vec<u8> {
+    let [x0, y0] = [quad[0].uv[0] * width as f32, quad[0].uv[1] * height as f32].map(|v| v.round() as usize);
+    let [x1, y1] = [quad[2].uv[0] * width as f32, quad[2].uv[1] * height as f32].map(|v| v.round() as usize);
+    (y0..y1).flat_map(|y| pixels[y * width as usize + x0..y * width as usize + x1].to_vec()).collect()
+}
+
+#[test]
+fn baked_quads_sample_their_own_glyph_without_padding() {
+    // Unpadded entries touch, so a quad's edge lies on its neighbour's
+    let config = CacheConfig { padding: 0, ..Default::default() };
+    let mut textware = TextWare::with_backend_config(CpuBackend::new(256), config);
+    let mut text = textware.create_text("Hamburgefonstiv WAVE", None, 24.0, None).unwrap();
+    let mesh = textware.generate_mesh(&mut text);
+    let baked = textware.bake_mesh(&mesh).unwrap();
+    textware.prepare(&());
+
+    let size = textware.backend().pixels().len().isqrt() as u32;
+    assert_eq!(baked.mesh.vertices.len(), mesh.vertices.len());
+    for (original, quad) in mesh.vertices.chunks_exact(4).zip(baked.mesh.vertices.chunks_exact(4)) {
+        let expected = texels(original, textware.backend().pixels(), size, size);
+        assert_eq!(texels(quad, &baked.atlas.data, baked.atlas.width, baked.atlas.height), expected);
+    }
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn baked_meshes_round_trip_through_json() {
+    let mut textware = TextWare::with_backend(CpuBackend::new(256));
+    let mut text = textware.create_text("Credits", None, 24.0, None).unwrap();
+    let mesh = textware.generate_mesh(&mut text);
+    let baked = textware.bake_mesh(&mesh).unwrap();
+
+    let json = serde_json::to_string(&baked).unwrap();
+    let loaded: BakedMesh = serde_json::from_str(&json).unwrap();
+    assert_eq!(loaded.atlas, baked.atlas);
+    assert_eq!(loaded.mesh.checksum(), baked.mesh.checksum());
+}
@@ -0,0 +1,18 @@
+#![cfg(feature = "testing")]
+
+use textware::testing::CpuBackend;
+use textware::{CoordinateSpace, TextWare};
+
+#[test]
+fn coordinate_space_change_rebuilds_the_cached_mesh() {
+    let mut textware = TextWare::with_backend(CpuBackend::new(512));
+    let mut text = textware.create_text("Cached", None, 20.0, None).unwrap();
+
+    let screen = textware.mesh_for(&mut text).clone();
+    assert!(text.has_cached_mesh());
+
+    textware.set_coordinate_space(&mut text, CoordinateSpace::World { units_per_pixel: 0.5 }).unwrap();
+    let world = textware.mesh_for(&mut text).clone();
+    assert_ne!(screen.vertices[0].position, world.vertices[0].position);
+    assert_eq!(world.checksum(), textware.generate_mesh(&mut text).checksum());
+}
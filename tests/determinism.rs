@@ -0,0 +1,37 @@
+#![cfg(feature = "testing")]
+
+use textware::testing::CpuBackend;
+use textware::{CacheConfig, TextMesh, TextWare};
+
+const SANS: &str = "/usr/share/fonts/truetype/dejavu/DejaVuSans.ttf";
+const MATH: &str = "/usr/share/fonts/truetype/dejavu/DejaVuMathTeXGyre.ttf";
+
+// Latin from the primary font, math italics only the fallback has
+const CONTENT: &str = "Area 𝑥² + 𝑦² = 𝑟², wrap me please";
+
+fn build() -> (TextMesh, usize) {
+    let config = CacheConfig { deterministic: true, ..Default::default() };
+    let mut textware = TextWare::with_backend_config(CpuBackend::new(512), config);
+    let sans = textware.load_font_file(SANS).unwrap();
+    textware.load_font_file(MATH).unwrap();
+
+    let mut text = textware.create_text(CONTENT, Some(sans), 18.0, None).unwrap();
+    textware.set_size(&mut text, Some(160.0), None).unwrap();
+    let mut faces: Vec<_> = textware.glyph_runs(&mut text).iter().map(|run| run.face).collect();
+    faces.sort();
+    faces.dedup();
+
+    (textware.generate_mesh(&mut text), faces.len())
+}
+
+#[test]
+fn identical_input_gives_identical_bytes() {
+    let (first, faces) = build();
+    let (second, _) = build();
+
+    assert!(faces > 1, "the content should need a fallback font");
+    assert!(!first.vertices.is_empty());
+    assert_eq!(bytemuck::cast_slice::<_, u8>(&first.vertices), bytemuck::cast_slice::<_, u8>(&second.vertices));
+    assert_eq!(bytemuck::cast_slice::<_, u8>(&first.indices), bytemuck::cast_slice::<_, u8>(&second.indices));
+    assert_eq!(first.checksum(), second.checksum());
+}
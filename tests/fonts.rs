@@ -0,0 +1,21 @@
+#![cfg(feature = "testing")]
+
+use textware::testing::CpuBackend;
+use textware::{Span, SpanStyle, TextWare};
+
+const SERIF: &str = "/usr/share/fonts/truetype/dejavu/DejaVuSerif.ttf";
+
+#[test]
+fn set_font_keeps_rich_spans() {
+    let mut textware = TextWare::with_backend(CpuBackend::new(512));
+    let serif = textware.load_font_file(SERIF).unwrap();
+    assert_eq!(textware.split().0.find_font("DejaVuSerif"), Some(serif));
+
+    let red = [1.0, 0.0, 0.0, 1.0];
+    let spans = [Span::new("plain ", SpanStyle::default()), Span::new("red", SpanStyle { color: Some(red), ..Default::default() })];
+    let mut text = textware.create_rich_text(&spans, None, 20.0, None).unwrap();
+    textware.set_font(&mut text, Some(serif)).unwrap();
+
+    let mesh = textware.generate_mesh(&mut text);
+    assert_eq!(mesh.vertices.iter().filter(|vertex| vertex.color == red).count(), 3 * 4);
+}
@@ -0,0 +1,35 @@
+#![cfg(feature = "testing")]
+
+use textware::testing::{CpuBackend, GoldenImage, Tolerance};
+use textware::TextWare;
+
+const SANS: &str = "/usr/share/fonts/truetype/dejavu/DejaVuSans.ttf";
+const REFERENCE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/golden/hello.png");
+
+#[test]
+fn text_matches_its_reference() {
+    let mut textware = TextWare::with_backend(CpuBackend::new(1024));
+    let sans = textware.load_font_file(SANS).unwrap();
+    let mut text = textware.create_text("Hello, golden world", Some(sans), 24.0, None).unwrap();
+    text.set_color([0.2, 0.4, 0.9, 1.0]);
+    textware.assert_golden(&mut text, REFERENCE, Tolerance::default());
+}
+
+#[test]
+fn changed_text_fails_and_saves_the_actual_image() {
+    let dir = std::env::temp_dir().join(format!("textware-golden-{}", std::process::id()));
+    let reference = dir.join("word.png");
+    let mut textware = TextWare::with_backend(CpuBackend::new(1024));
+    let sans = textware.load_font_file(SANS).unwrap();
+
+    let mut text = textware.create_text("Hello", Some(sans), 24.0, None).unwrap();
+    assert!(textware.check_golden(&mut text, &reference, Tolerance::default()).unwrap().passed);
+    let saved = GoldenImage::load(&reference).unwrap();
+    assert_eq!(saved, textware.render_golden(&mut text));
+
+    let mut other = textware.create_text("Hullo", Some(sans), 24.0, None).unwrap();
+    let diff = textware.check_golden(&mut other, &reference, Tolerance::default()).unwrap();
+    assert!(!diff.passed && diff.mismatched > 0);
+    assert!(dir.join("word.actual.png").exists());
+    std::fs::remove_dir_all(dir).unwrap();
+}
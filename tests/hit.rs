@@ -0,0 +1,19 @@
+#![cfg(feature = "testing")]
+
+use textware::testing::CpuBackend;
+use textware::TextWare;
+
+#[test]
+fn hits_report_the_text_they_landed_on() {
+    let mut textware = TextWare::with_backend(CpuBackend::new(512));
+    let mut title = textware.create_text("Title", None, 20.0, None).unwrap();
+    let mut body = textware.create_text("Body", None, 20.0, None).unwrap();
+    let (title_id, body_id) = (title.id(), body.id());
+
+    assert_eq!(textware.hit_test(&mut title, 2.0, 10.0).map(|hit| hit.id), Some(title_id));
+
+    let mut placed = [(&mut title, [0.0, 0.0]), (&mut body, [0.0, 100.0])];
+    assert_eq!(textware.hit_test_texts(&mut placed, 2.0, 10.0).map(|hit| hit.id), Some(title_id));
+    assert_eq!(textware.hit_test_texts(&mut placed, 2.0, 110.0).map(|hit| hit.id), Some(body_id));
+    assert_eq!(textware.hit_test_texts(&mut placed, 2.0, 60.0), None);
+}
@@ -0,0 +1,18 @@
+#![cfg(feature = "testing")]
+
+use textware::testing::CpuBackend;
+use textware::TextWare;
+
+#[test]
+fn placeholders_keep_the_content() {
+    let mut textware = TextWare::with_backend(CpuBackend::new(512));
+    let mut hidden = textware.create_text("ab\u{200B}cd", None, 20.0, None).unwrap();
+    textware.set_show_invisibles(true);
+    let mut shown = textware.create_text("ab\u{200B}cd", None, 20.0, None).unwrap();
+
+    // The hatched box adds quads but the buffer, and so every byte offset, stays the same
+    assert!(textware.generate_mesh(&mut shown).vertices.len() > textware.generate_mesh(&mut hidden).vertices.len());
+    assert_eq!(shown.buffer_bytes(), hidden.buffer_bytes());
+    let end = textware.hit_test(&mut shown, 1000.0, 10.0).unwrap();
+    assert_eq!(end.cursor.index, "ab\u{200B}cd".len());
+}
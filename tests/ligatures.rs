@@ -0,0 +1,23 @@
+#![cfg(feature = "testing")]
+
+use textware::testing::CpuBackend;
+use textware::{FontFeatures, TextWare};
+
+const SANS: &str = "/usr/share/fonts/truetype/dejavu/DejaVuSans.ttf";
+
+#[test]
+fn ligatures_toggle_without_touching_the_content() {
+    let mut textware = TextWare::with_backend(CpuBackend::new(512));
+    let sans = textware.load_font_file(SANS).unwrap();
+    let mut text = textware.create_text("office fluff", Some(sans), 24.0, None).unwrap();
+    let bytes = text.buffer_bytes();
+    let joined = textware.generate_mesh(&mut text).vertices.len();
+
+    textware.set_font_features(&mut text, FontFeatures { ligatures: false, ..Default::default() });
+    let split = textware.mesh_for(&mut text).vertices.len();
+    assert!(split > joined, "{split} vertices with ligatures off, {joined} with them on");
+    assert_eq!(text.buffer_bytes(), bytes);
+
+    textware.set_font_features(&mut text, FontFeatures::default());
+    assert_eq!(textware.mesh_for(&mut text).vertices.len(), joined);
+}
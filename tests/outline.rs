@@ -0,0 +1,26 @@
+#![cfg(feature = "testing")]
+
+use textware::testing::CpuBackend;
+use textware::{Text, TextWare};
+
+fn coverage(textware: &mut TextWare<CpuBackend>, text: &mut Text) -> u64 {
+    textware.render_golden(text).pixels.chunks_exact(4).map(|pixel| pixel[3] as u64).sum()
+}
+
+#[test]
+fn outline_variants_change_coverage() {
+    let mut textware = TextWare::with_backend(CpuBackend::new(1024));
+    let mut text = textware.create_text("Hamburgefonstiv", None, 40.0, None).unwrap();
+    let plain = coverage(&mut textware, &mut text);
+
+    textware.set_embolden(&mut text, 0.05).unwrap();
+    let bold = coverage(&mut textware, &mut text);
+    textware.set_embolden(&mut text, -0.015).unwrap();
+    let thin = coverage(&mut textware, &mut text);
+    textware.set_embolden(&mut text, 0.0).unwrap();
+    textware.set_hollow(&mut text, Some(0.03)).unwrap();
+    let hollow = coverage(&mut textware, &mut text);
+
+    assert!(thin < plain && plain < bold, "thin {thin}, plain {plain}, bold {bold}");
+    assert!(hollow > 0 && hollow < bold, "hollow {hollow}, bold {bold}");
+}
@@ -0,0 +1,19 @@
+#![cfg(feature = "testing")]
+
+use textware::testing::CpuBackend;
+use textware::{FontSystem, GlyphCache, TextWare};
+
+#[test]
+fn font_system_and_glyph_cache_work_on_their_own() {
+    let mut fonts = FontSystem::new();
+    let mut cache = GlyphCache::with_backend(CpuBackend::new(256), 1);
+    let mut text = fonts.create_text("Standalone", None, 18.0, None).unwrap();
+    fonts.shape(&mut text);
+    let mesh = cache.generate_mesh(&mut fonts, &text);
+    assert_eq!(mesh.vertices.len(), "Standalone".len() * 4);
+
+    let mut textware = TextWare::from_parts(fonts, cache);
+    assert_eq!(textware.generate_mesh(&mut text).checksum(), mesh.checksum());
+    let (mut fonts, mut cache) = textware.into_parts();
+    assert_eq!(cache.generate_mesh(&mut fonts, &text).checksum(), mesh.checksum());
+}
@@ -0,0 +1,31 @@
+#![cfg(feature = "testing")]
+
+use textware::testing::CpuBackend;
+use textware::{SubtitleStyle, TextError, TextWare};
+
+#[test]
+fn subtitles_stay_inside_the_safe_area() {
+    let mut textware = TextWare::with_backend(CpuBackend::new(1024));
+    let style = SubtitleStyle { shadow_color: [0.0; 4], ..Default::default() };
+    let long = "A subtitle that keeps going well past what fits on a small screen in two lines ".repeat(4);
+
+    let mesh = textware.generate_subtitle(&long, &style, [320.0, 180.0]).unwrap();
+    let top = mesh.vertices.iter().map(|vertex| vertex.position[1]).fold(f32::MAX, f32::min);
+    assert!(top >= 180.0 * style.safe_area[1], "text starts at {top}");
+
+    // Outline strokes and fills, one quad each per glyph
+    let glyphs = textware.generate_subtitle("Hi", &style, [320.0, 180.0]).unwrap().vertices.len() / 4;
+    assert_eq!(glyphs, 4);
+}
+
+#[test]
+fn invalid_subtitle_styles_are_rejected() {
+    let mut textware = TextWare::with_backend(CpuBackend::new(256));
+    for style in [
+        SubtitleStyle { shadow_blur: f32::NAN, ..Default::default() },
+        SubtitleStyle { shadow_blur: -1.0, ..Default::default() },
+        SubtitleStyle { max_width: 0.0, ..Default::default() },
+    ] {
+        assert!(matches!(textware.generate_subtitle("Hi", &style, [320.0, 180.0]), Err(TextError::InvalidArgument(_))));
+    }
+}
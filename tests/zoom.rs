@@ -0,0 +1,29 @@
+#![cfg(feature = "testing")]
+
+use textware::testing::CpuBackend;
+use textware::{DistanceField, TextWare};
+
+#[test]
+fn distance_field_zoom_scales_quads() {
+    let mut textware = TextWare::with_backend(CpuBackend::new(512));
+    let mut text = textware.create_text("Zoom in", None, 16.0, None).unwrap();
+    textware.set_distance_field(&mut text, Some(DistanceField::default())).unwrap();
+
+    let base = textware.generate_mesh_zoomed(&mut text, 1.0).unwrap();
+    let glyphs = textware.memory_report().cached_glyphs;
+    assert!(!base.vertices.is_empty());
+
+    assert_eq!(textware.warm_glyphs(&mut text, 2.5, 64), 0);
+    let zoomed = textware.generate_mesh_zoomed(&mut text, 2.5).unwrap();
+
+    // Same atlas entries, only the quads grew, snapped to whole pixels at the zoomed size
+    assert_eq!(textware.memory_report().cached_glyphs, glyphs);
+    assert_eq!(base.indices, zoomed.indices);
+    for (a, b) in base.vertices.iter().zip(&zoomed.vertices) {
+        assert_eq!(a.uv, b.uv);
+        for axis in 0..2 {
+            assert_eq!(b.position[axis].fract(), 0.0);
+            assert!((a.position[axis] * 2.5 - b.position[axis]).abs() <= 2.0, "{} vs {}", a.position[axis], b.position[axis]);
+        }
+    }
+}